@@ -0,0 +1,218 @@
+//! Compares memory footprint, frame diffing and refresh speed of Glyph against
+//! the previous layout, where every glyph carried both colors and ten bools.
+//! Run with: cargo bench --features bench --bench glyph
+use animaterm::bench::FramePlayer;
+use animaterm::prelude::*;
+use std::hint::black_box;
+use std::mem::size_of;
+use std::time::{Duration, Instant};
+
+const COLS: usize = 320;
+const ROWS: usize = 96;
+const FRAMES: usize = 8;
+const ROUNDS: u32 = 20;
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, PartialEq)]
+struct LegacyGlyph {
+    character: char,
+    color: Color,
+    background: Color,
+    plain: bool,
+    bright: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+    blink: bool,
+    blink_fast: bool,
+    reverse: bool,
+    transparent: bool,
+    strike: bool,
+}
+
+fn color_at(frame: usize, i: usize) -> Color {
+    let shade = ((i / COLS + frame) % 24) as u8;
+    Color::new_truecolor(shade * 10, (i % COLS) as u8, 128)
+}
+
+fn wallpaper() -> Vec<Vec<Glyph>> {
+    (0..FRAMES)
+        .map(|f| {
+            (0..COLS * ROWS)
+                .map(|i| {
+                    let mut g = Glyph::default_with_char('▀');
+                    g.set_color(color_at(f, i));
+                    g.set_background(color_at(f + 1, i));
+                    g
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn legacy_wallpaper() -> Vec<Vec<LegacyGlyph>> {
+    (0..FRAMES)
+        .map(|f| {
+            (0..COLS * ROWS)
+                .map(|i| LegacyGlyph {
+                    character: '▀',
+                    color: color_at(f, i),
+                    background: color_at(f + 1, i),
+                    plain: false,
+                    bright: false,
+                    dim: false,
+                    italic: false,
+                    underline: false,
+                    blink: false,
+                    blink_fast: false,
+                    reverse: false,
+                    transparent: false,
+                    strike: false,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Pixels that differ between two frames, the way Graphic finds what to refresh.
+fn diff<T: Copy + PartialEq>(old: &[T], new: &[T]) -> Vec<(usize, usize, T)> {
+    let mut changed = Vec::with_capacity(COLS);
+    for (i, (old_glyph, new_glyph)) in old.iter().zip(new).enumerate() {
+        if new_glyph != old_glyph {
+            changed.push((i % COLS, i / COLS, *new_glyph));
+        }
+    }
+    changed
+}
+
+fn legacy_color(color: Color, background: bool) -> String {
+    let base = if background { 48 } else { 38 };
+    match color {
+        Color::Basic(name) => format!("{}", base - 8 + name as u8),
+        Color::EightBit(index) | Color::Grayscale(index) => format!("{};5;{}", base, index),
+        Color::Truecolor(r, g, b) => format!("{};2;{};{};{}", base, r, g, b),
+    }
+}
+
+/// Previous refresh path: every changed glyph was printed with
+/// all of it's attributes and both colors built anew from it's fields,
+/// the way Screen::gformat did. Layers are not composited here,
+/// so this side does less work than Screen does.
+struct LegacyScreen {
+    shown: Vec<LegacyGlyph>,
+    out: String,
+    written: usize,
+}
+
+impl LegacyScreen {
+    fn show(&mut self, frame: &[LegacyGlyph]) {
+        let mut cursor = (usize::MAX, usize::MAX);
+        for (x, y, glyph) in diff(&self.shown, frame) {
+            self.shown[y * COLS + x] = glyph;
+            if cursor != (x, y) {
+                self.out.push_str(&format!("\x1b[{};{}H", y + 1, x + 1));
+            }
+            cursor = (x + 1, y);
+            let mut modifier = String::new();
+            modifier.push_str(if glyph.bright {
+                "1;"
+            } else if glyph.dim {
+                "2;"
+            } else {
+                "22;"
+            });
+            for (on, code, off) in [(glyph.italic, "3;", "23;"), (glyph.underline, "4;", "24;")] {
+                modifier.push_str(if on { code } else { off });
+            }
+            if glyph.blink {
+                modifier.push_str("5;");
+            }
+            if glyph.blink_fast {
+                modifier.push_str("6;");
+            }
+            if !glyph.blink && !glyph.blink_fast {
+                modifier.push_str("25;");
+            }
+            for (on, code, off) in [(glyph.reverse, "7;", "27;"), (glyph.strike, "9;", "29;")] {
+                modifier.push_str(if on { code } else { off });
+            }
+            modifier.push_str(&legacy_color(glyph.color, false));
+            modifier.push(';');
+            modifier.push_str(&legacy_color(glyph.background, true));
+            self.out
+                .push_str(&format!("\x1b[{}m{}", modifier, glyph.character));
+        }
+        self.written += self.out.len();
+        self.out.clear();
+    }
+}
+
+fn measure<F: FnMut()>(mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        f();
+    }
+    start.elapsed() / ROUNDS
+}
+
+fn main() {
+    let frame_cells = COLS * ROWS * FRAMES;
+    println!(
+        "Glyph: {} bytes (legacy {} bytes), Option<Glyph>: {} bytes (legacy {} bytes)",
+        size_of::<Glyph>(),
+        size_of::<LegacyGlyph>(),
+        size_of::<Option<Glyph>>(),
+        size_of::<Option<LegacyGlyph>>()
+    );
+    println!(
+        "{}x{} wallpaper with {} frames: {} KiB (legacy {} KiB)",
+        COLS,
+        ROWS,
+        FRAMES,
+        frame_cells * size_of::<Glyph>() / 1024,
+        frame_cells * size_of::<LegacyGlyph>() / 1024
+    );
+
+    // Animating a graphic diffs every frame against previous one
+    // in order to find which pixels need to be refreshed.
+    let frames = wallpaper();
+    let legacy = legacy_wallpaper();
+    let current = measure(|| {
+        for frame_id in 0..FRAMES {
+            let previous = (frame_id + FRAMES - 1) % FRAMES;
+            black_box(diff(&frames[previous], &frames[frame_id]));
+        }
+    });
+    let previous = measure(|| {
+        for frame_id in 0..FRAMES {
+            let previous = (frame_id + FRAMES - 1) % FRAMES;
+            black_box(diff(&legacy[previous], &legacy[frame_id]));
+        }
+    });
+    println!(
+        "Diffing {} frames: {:?} (legacy {:?})",
+        FRAMES, current, previous
+    );
+
+    // Whole way from a frame to bytes sent to terminal
+    let mut player = FramePlayer::new(COLS, ROWS, frames);
+    let current = measure(|| player.play());
+    let mut legacy_screen = LegacyScreen {
+        shown: legacy[0].clone(),
+        out: String::with_capacity(1 << 20),
+        written: 0,
+    };
+    let previous = measure(|| {
+        for frame in &legacy {
+            legacy_screen.show(frame);
+        }
+    });
+    println!(
+        "Refreshing {} frames: {:?}, {} KiB written (legacy {:?}, {} KiB written)",
+        FRAMES,
+        current,
+        player.written() / 1024,
+        previous,
+        legacy_screen.written / 1024
+    );
+}
@@ -90,23 +90,13 @@ fn main() {
         (start_frame, Timestamp::new(0, 300)),
         (start_frame + 1, Timestamp::new(0, 300)),
     ];
-    // let mut all_results_read = false;
-    // while !all_results_read {
-    //     let result = mgr.read_result();
-    //     match result {
-    //         Ok(AnimOk::AllResultsRead) => all_results_read = true,
-    //         _ => continue,
-    //     }
-    // }
-    mgr.add_animation(
-        graphic_id,
-        Animation::new(false, true, var_ordering, Timestamp::now()),
-    );
-    let mut var_anim_id = 0;
-
-    if let Ok(AnimOk::AnimationAdded(anim_id)) = mgr.read_result() {
-        var_anim_id = anim_id;
-    }
+    let var_anim_id = mgr
+        .add_animation(
+            graphic_id,
+            Animation::new(false, true, var_ordering, Timestamp::now()),
+        )
+        .wait()
+        .unwrap_or(0);
 
     let mut keep_running = true;
     while keep_running {
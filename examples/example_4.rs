@@ -51,10 +51,6 @@ fn main() {
         .unwrap();
     mgr.start_animation(gid, 0);
     let second_display_id = mgr.new_display(keep_existing);
-    // let result = mgr.read_result();
-    // if let Ok(AnimOk::DisplayStored(disp_id)) = result {
-    //     first_display_id = disp_id;
-    // }
     let mbox = message_box(Some(title), text, Glyph::default(), 32, 5);
     let mbid = mgr
         .add_graphic(mbox, 1, (1, screen_size.1 as isize - 6))
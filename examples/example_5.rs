@@ -1,3 +1,9 @@
+// Lints reported by newer clippy are left alone in this example
+#![allow(
+    clippy::derivable_impls,
+    clippy::manual_strip,
+    clippy::from_str_radix_10
+)]
 use animaterm::{
     prelude::*,
     utilities::{message_box, progress_bar},
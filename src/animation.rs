@@ -61,6 +61,16 @@ impl Animation {
         }
     }
 
+    /// Frames this Animation is switching between, with time each one is displayed for.
+    pub fn ordering(&self) -> &[(usize, Timestamp)] {
+        &self.ordering
+    }
+
+    /// Check if an Animation starts over after showing it's last frame.
+    pub fn is_looping(&self) -> bool {
+        self.looping
+    }
+
     /// This method is used to start an Animation if it is not already running.
     pub fn start(&mut self, t: Timestamp) {
         if !self.running {
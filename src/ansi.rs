@@ -0,0 +1,330 @@
+use super::color::{Color, ColorName};
+use super::error::AnimError;
+use super::style::{self, Appearance, Style};
+use super::utilities::char_width;
+use super::Glyph;
+
+/// States of a parser reading text mixed with ANSI escape sequences.
+enum State {
+    Ground,
+    Escape(usize),
+    // intermediate bytes of an escape sequence, like ( in ESC ( B
+    EscapeIntermediate(usize),
+    Csi(usize),
+    Osc(usize),
+    OscEscape(usize),
+}
+
+/// Read a frame from text. Every line of text is a row of the frame,
+/// rows shorter than the widest one are filled with transparent glyphs.
+/// Returns frame's width together with its glyphs.
+pub fn parse_frame(text: &str) -> Result<(usize, Vec<Glyph>), AnimError> {
+    let mut look = style::DEFAULT;
+    let mut rows = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let mut row = Vec::with_capacity(line.len());
+        parse_line(line, i + 1, &mut look, &mut row)?;
+        rows.push(row);
+    }
+    let cols = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    if cols == 0 {
+        return Err(AnimError::UnableToParseFrame(
+            1,
+            1,
+            "frame is empty".to_string(),
+        ));
+    }
+    let mut frame = Vec::with_capacity(cols * rows.len());
+    for mut row in rows {
+        row.resize(cols, Glyph::transparent());
+        frame.append(&mut row);
+    }
+    Ok((cols, frame))
+}
+
+/// Write a frame as text, one line per row, with SGR sequences only where
+/// appearance changes. Every line ends with default style restored,
+/// so that it can be printed as is or read back with parse_frame.
+pub fn frame_to_text(cols: usize, frame: &[Glyph]) -> String {
+    let mut text = String::with_capacity(frame.len() * 2);
+    for row in frame.chunks(cols.max(1)) {
+        let mut previous = style::DEFAULT;
+        for glyph in row {
+            // Filler following a wide char prints nothing, the wide char covers it's column
+            if glyph.character == '\u{0}' {
+                continue;
+            }
+            let look = glyph.appearance();
+            let sgr = look.sgr_from(&previous);
+            if !sgr.is_empty() {
+                text.push_str("\x1b[");
+                text.push_str(&sgr);
+                text.push('m');
+            }
+            previous = look;
+            // Control characters would be dropped when reading, breaking row's width
+            if glyph.character.is_control() {
+                text.push(' ');
+            } else {
+                text.push(glyph.character);
+            }
+        }
+        if previous.sgr_from(&style::DEFAULT).is_empty() {
+            text.push('\n');
+        } else {
+            text.push_str("\x1b[0m\n");
+        }
+    }
+    text
+}
+
+/// Read a single line of text, pushing a glyph for every printable character,
+/// followed by a filler glyph for wide chars.
+/// look holds terminal's style state that is carried over between lines.
+pub fn parse_line(
+    line: &str,
+    line_no: usize,
+    look: &mut Appearance,
+    glyphs: &mut Vec<Glyph>,
+) -> Result<(), AnimError> {
+    let mut state = State::Ground;
+    let mut params = String::new();
+    for (i, character) in line.chars().enumerate() {
+        let column = i + 1;
+        state = match state {
+            State::Ground => match character {
+                '\x1b' => State::Escape(column),
+                c if c.is_control() => State::Ground,
+                c => {
+                    glyphs.push(Glyph::with_appearance(c, *look));
+                    // Wide char covers two columns, second one is held by a filler
+                    if char_width(c) == 2 {
+                        glyphs.push(Glyph::with_appearance('\u{0}', *look));
+                    }
+                    State::Ground
+                }
+            },
+            State::Escape(start) => match character {
+                '[' => {
+                    params.clear();
+                    State::Csi(start)
+                }
+                ']' => State::Osc(start),
+                '\x20'..='\x2f' => State::EscapeIntermediate(start),
+                // Other escape sequences do not affect how glyphs look
+                '\x30'..='\x7e' => State::Ground,
+                c => {
+                    return Err(AnimError::UnableToParseFrame(
+                        line_no,
+                        column,
+                        format!("unexpected {:?} in escape sequence", c),
+                    ))
+                }
+            },
+            State::EscapeIntermediate(start) => match character {
+                '\x20'..='\x2f' => State::EscapeIntermediate(start),
+                '\x30'..='\x7e' => State::Ground,
+                c => {
+                    return Err(AnimError::UnableToParseFrame(
+                        line_no,
+                        column,
+                        format!("unexpected {:?} in escape sequence", c),
+                    ))
+                }
+            },
+            State::Csi(start) => match character {
+                '\x20'..='\x3f' => {
+                    params.push(character);
+                    State::Csi(start)
+                }
+                'm' if !params.starts_with(['<', '=', '>', '?'])
+                    && !params.contains(|c: char| c < '0') =>
+                {
+                    if let Err((offset, reason)) = apply_sgr(&params, look) {
+                        return Err(AnimError::UnableToParseFrame(
+                            line_no,
+                            start + 2 + offset,
+                            reason,
+                        ));
+                    }
+                    State::Ground
+                }
+                // Any other control sequence is not relevant for a frame
+                '\x40'..='\x7e' => State::Ground,
+                c => {
+                    return Err(AnimError::UnableToParseFrame(
+                        line_no,
+                        column,
+                        format!("unexpected {:?} in control sequence", c),
+                    ))
+                }
+            },
+            State::Osc(start) => match character {
+                '\x07' => State::Ground,
+                '\x1b' => State::OscEscape(start),
+                _ => State::Osc(start),
+            },
+            State::OscEscape(start) => match character {
+                '\\' => State::Ground,
+                _ => State::Osc(start),
+            },
+        };
+    }
+    match state {
+        State::Ground => Ok(()),
+        State::Escape(start)
+        | State::EscapeIntermediate(start)
+        | State::Csi(start)
+        | State::Osc(start)
+        | State::OscEscape(start) => Err(AnimError::UnableToParseFrame(
+            line_no,
+            start,
+            "unterminated escape sequence".to_string(),
+        )),
+    }
+}
+
+/// Update style according to parameters of a Select Graphic Rendition sequence.
+/// Parameters are separated by semicolons, each one can contain subparameters
+/// separated by colons, e.g. 1;38:2::255:128:0;48;5;17 .
+/// On failure offset of invalid parameter and a reason are returned.
+pub fn apply_sgr(params: &str, look: &mut Appearance) -> Result<(), (usize, String)> {
+    let mut groups = Vec::with_capacity(8);
+    let mut offset = 0;
+    for group in params.split(';') {
+        let mut values = Vec::with_capacity(1);
+        for value in group.split(':') {
+            if value.is_empty() {
+                values.push(None);
+            } else if let Ok(number) = value.parse::<u32>() {
+                values.push(Some(number));
+            } else {
+                return Err((offset, format!("invalid SGR parameter {:?}", group)));
+            }
+        }
+        groups.push((offset, values));
+        offset += group.len() + 1;
+    }
+
+    let mut groups = groups.into_iter();
+    while let Some((offset, values)) = groups.next() {
+        let code = values[0].unwrap_or(0);
+        if values.len() > 1 {
+            match code {
+                38 | 48 | 58 => {
+                    let color = color_from_subparams(&values[1..]).map_err(|r| (offset, r))?;
+                    match code {
+                        38 => look.color = color,
+                        48 => look.background = color,
+                        _ => {}
+                    }
+                }
+                4 => look.set_underline(values[1].unwrap_or(0) > 0),
+                _ => {}
+            }
+            continue;
+        }
+        match code {
+            0 => *look = style::DEFAULT,
+            1 => look.set_bright(true),
+            2 => look.set_dim(true),
+            3 => look.set_italic(true),
+            4 => look.set_underline(true),
+            5 => look.set_blink(true),
+            6 => look.set_blinkfast(true),
+            7 => look.set_reverse(true),
+            8 => look.set_transparent(true),
+            9 => look.set_strike(true),
+            21 => look.set_bright(false),
+            22 => look.style.set(Style::BRIGHT | Style::DIM, false),
+            23 => look.set_italic(false),
+            24 => look.set_underline(false),
+            25 => look.style.set(Style::BLINK | Style::BLINK_FAST, false),
+            27 => look.set_reverse(false),
+            28 => look.set_transparent(false),
+            29 => look.set_strike(false),
+            30..=37 => look.color = basic_color(code - 30),
+            39 => look.color = Color::white(),
+            40..=47 => look.background = basic_color(code - 40),
+            49 => look.background = Color::black(),
+            90..=97 => {
+                look.color = basic_color(code - 90);
+                look.set_bright(true);
+            }
+            100..=107 => {
+                look.background = basic_color(code - 100);
+                look.set_bright(true);
+            }
+            38 | 48 | 58 => {
+                let kind = groups.next().and_then(|(_, v)| v[0]);
+                let needed = match kind {
+                    Some(5) => 1,
+                    Some(2) => 3,
+                    _ => {
+                        return Err((
+                            offset,
+                            format!("expected 2 or 5 after {}, got {:?}", code, kind),
+                        ))
+                    }
+                };
+                let mut subparams = vec![kind];
+                for _i in 0..needed {
+                    if let Some((_, v)) = groups.next() {
+                        subparams.push(v[0]);
+                    } else {
+                        return Err((offset, format!("color definition {} is incomplete", code)));
+                    }
+                }
+                let color = color_from_subparams(&subparams).map_err(|r| (offset, r))?;
+                match code {
+                    38 => look.color = color,
+                    48 => look.background = color,
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn basic_color(index: u32) -> Color {
+    Color::Basic(match index {
+        0 => ColorName::Black,
+        1 => ColorName::Red,
+        2 => ColorName::Green,
+        3 => ColorName::Yellow,
+        4 => ColorName::Blue,
+        5 => ColorName::Magenta,
+        6 => ColorName::Cyan,
+        _ => ColorName::White,
+    })
+}
+
+/// Build a color out of subparameters following 38 or 48,
+/// accepting both 2:r:g:b and 2:colorspace:r:g:b forms.
+fn color_from_subparams(values: &[Option<u32>]) -> Result<Color, String> {
+    let byte = |value: Option<u32>| -> Result<u8, String> {
+        let value = value.unwrap_or(0);
+        u8::try_from(value).map_err(|_| format!("color value {} out of range", value))
+    };
+    match values.first().copied().flatten() {
+        Some(5) if values.len() == 2 => {
+            let index = byte(values[1])?;
+            if index > 231 {
+                Ok(Color::new_gray(index - 232))
+            } else {
+                Ok(Color::EightBit(index))
+            }
+        }
+        Some(2) if values.len() == 4 || values.len() == 5 => {
+            let rgb = &values[values.len() - 3..];
+            Ok(Color::new_truecolor(
+                byte(rgb[0])?,
+                byte(rgb[1])?,
+                byte(rgb[2])?,
+            ))
+        }
+        _ => Err(format!("unsupported color definition {:?}", values)),
+    }
+}
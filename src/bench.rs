@@ -0,0 +1,64 @@
+//! Hooks letting benches drive parts of the library that are not public,
+//! not covered by any stability guarantees.
+use super::screen::Screen;
+use super::{Glyph, Graphic};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Counts bytes written to it, dropping them.
+#[derive(Clone, Default)]
+struct ByteCounter(Arc<AtomicUsize>);
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.fetch_add(buf.len(), Ordering::Relaxed);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A graphic placed on a Screen that is not attached to a terminal.
+pub struct FramePlayer {
+    screen: Screen,
+    graphic_id: usize,
+    frames: usize,
+    written: ByteCounter,
+}
+
+impl FramePlayer {
+    /// Place a graphic made of given frames on a screen of it's size.
+    pub fn new(cols: usize, rows: usize, frames: Vec<Vec<Glyph>>) -> Self {
+        let written = ByteCounter::default();
+        let mut screen = Screen::with_output(cols, rows, None, Box::new(written.clone()));
+        let count = frames.len();
+        let library: HashMap<usize, Vec<Glyph>> = frames.into_iter().enumerate().collect();
+        let graphic = Graphic::new(cols, rows, 0, library, None);
+        let graphic_id = screen
+            .add_graphic(graphic, 0, (0, 0))
+            .expect("Empty screen has free graphic ids");
+        FramePlayer {
+            screen,
+            graphic_id,
+            frames: count,
+            written,
+        }
+    }
+
+    /// Show every frame in turn, the way an animation does: diffing it against
+    /// previous one, compositing changed pixels, refreshing and printing them.
+    pub fn play(&mut self) {
+        for frame_id in 0..self.frames {
+            self.screen.set_graphic(&self.graphic_id, &frame_id, false);
+        }
+    }
+
+    /// Bytes that would have been sent to terminal so far.
+    pub fn written(&self) -> usize {
+        self.written.0.load(Ordering::Relaxed)
+    }
+}
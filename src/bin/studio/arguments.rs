@@ -86,6 +86,39 @@ pub struct Bindings {
     pub exit: Vec<Key>,
 }
 
+/// Movement within a focused window.
+pub enum Move {
+    Left,
+    Right,
+    Up,
+    Down,
+    First,
+    Last,
+    Prev,
+    Next,
+}
+
+impl Bindings {
+    /// Movement a key stands for in a focused window. Keys are those bound
+    /// to moving around Workspace and to paging through Glyphs.
+    pub fn navigation(&self, key: &Key) -> Option<Move> {
+        let moves = [
+            (&self.workspace_left, Move::Left),
+            (&self.workspace_right, Move::Right),
+            (&self.workspace_up, Move::Up),
+            (&self.workspace_down, Move::Down),
+            (&self.glyphs_home, Move::First),
+            (&self.glyphs_end, Move::Last),
+            (&self.glyphs_prev, Move::Prev),
+            (&self.glyphs_next, Move::Next),
+        ];
+        moves
+            .into_iter()
+            .find(|(keys, _)| keys.contains(key))
+            .map(|(_, movement)| movement)
+    }
+}
+
 impl Default for Bindings {
     fn default() -> Self {
         Bindings {
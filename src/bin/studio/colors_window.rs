@@ -1,5 +1,8 @@
+use crate::arguments::{Bindings, Move};
+use animaterm::prelude::{Focusable, Key, Manager};
 use animaterm::Color;
 use animaterm::Message;
+use std::rc::Rc;
 use std::sync::mpsc;
 
 pub struct ColorsWindow {
@@ -25,6 +28,9 @@ pub struct ColorsWindow {
     progress_bar_3_id: usize,
     progress_bar_3_title_id: usize,
     glyph_matrix_id: usize,
+    highlight_id: usize,
+    background: bool,
+    bindings: Rc<Bindings>,
 }
 
 pub struct ColorsWindowArgs {
@@ -41,6 +47,9 @@ pub struct ColorsWindowArgs {
     pub progress_bar_3_id: usize,
     pub progress_bar_3_title_id: usize,
     pub glyph_matrix_id: usize,
+    pub highlight_id: usize,
+    pub background: bool,
+    pub bindings: Rc<Bindings>,
 }
 impl ColorsWindow {
     pub fn new(args: ColorsWindowArgs) -> Self {
@@ -77,6 +86,9 @@ impl ColorsWindow {
             progress_bar_3_id: args.progress_bar_3_id,
             progress_bar_3_title_id: args.progress_bar_3_title_id,
             glyph_matrix_id: args.glyph_matrix_id,
+            highlight_id: args.highlight_id,
+            background: args.background,
+            bindings: args.bindings,
         }
     }
 
@@ -2657,3 +2669,77 @@ impl ColorsWindow {
         }
     }
 }
+
+impl ColorsWindow {
+    /// Handle a key bound to this window, regardless of focus.
+    pub fn handle_binding(&mut self, key: &Key) -> bool {
+        let bindings = self.bindings.clone();
+        let background = self.background;
+        let actions = if background {
+            [
+                &bindings.backgrounds_left,
+                &bindings.backgrounds_right,
+                &bindings.backgrounds_far_left,
+                &bindings.backgrounds_far_right,
+                &bindings.backgrounds_up,
+                &bindings.backgrounds_top,
+                &bindings.backgrounds_down,
+                &bindings.backgrounds_bottom,
+                &bindings.backgrounds_invisible,
+                &bindings.backgrounds_visible,
+            ]
+        } else {
+            [
+                &bindings.colors_left,
+                &bindings.colors_right,
+                &bindings.colors_far_left,
+                &bindings.colors_far_right,
+                &bindings.colors_up,
+                &bindings.colors_top,
+                &bindings.colors_down,
+                &bindings.colors_bottom,
+                &bindings.colors_invisible,
+                &bindings.colors_visible,
+            ]
+        };
+        match actions.iter().position(|keys| keys.contains(key)) {
+            Some(0) => self.move_left(background),
+            Some(1) => self.move_right(background),
+            Some(2) => self.move_far_left(background),
+            Some(3) => self.move_far_right(background),
+            Some(4) => self.move_up(),
+            Some(5) => self.move_top(),
+            Some(6) => self.move_down(),
+            Some(7) => self.move_bottom(),
+            Some(8) => self.set_invisible(true),
+            Some(9) => self.set_invisible(false),
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl Focusable for ColorsWindow {
+    fn handle_key(&mut self, _mgr: &mut Manager, key: &Key) -> bool {
+        if self.handle_binding(key) {
+            return true;
+        }
+        let background = self.background;
+        match self.bindings.navigation(key) {
+            Some(Move::Left) => self.move_left(background),
+            Some(Move::Right) => self.move_right(background),
+            Some(Move::First) => self.move_far_left(background),
+            Some(Move::Last) => self.move_far_right(background),
+            Some(Move::Up) => self.move_up(),
+            Some(Move::Down) => self.move_down(),
+            Some(Move::Prev) => self.move_top(),
+            Some(Move::Next) => self.move_bottom(),
+            None => return false,
+        }
+        true
+    }
+
+    fn set_focused(&mut self, mgr: &mut Manager, focused: bool) {
+        mgr.set_invisible(self.highlight_id, !focused);
+    }
+}
@@ -0,0 +1,220 @@
+use crate::arguments::{Bindings, Move};
+use animaterm::prelude::{Focusable, Glyph, Key, Manager, Timestamp};
+use std::cmp::max;
+use std::rc::Rc;
+
+// Glyphs are presented on pages of 16 columns and 10 rows
+const LAST_COL: usize = 15;
+const LAST_ROW: usize = 9;
+
+pub struct GlyphsWindow {
+    selector_id: usize,
+    selector_layer: usize,
+    glyph_matrix_id: usize,
+    highlight_id: usize,
+    offset: (isize, isize),
+    col: usize,
+    row: usize,
+    frame_id: usize,
+    max_frame_id: usize,
+    focused: bool,
+    bindings: Rc<Bindings>,
+}
+
+pub struct GlyphsWindowArgs {
+    pub selector_id: usize,
+    pub selector_layer: usize,
+    pub glyph_matrix_id: usize,
+    pub highlight_id: usize,
+    pub offset: (isize, isize),
+    pub max_frame_id: usize,
+    pub bindings: Rc<Bindings>,
+}
+
+impl GlyphsWindow {
+    pub fn new(args: GlyphsWindowArgs) -> Self {
+        GlyphsWindow {
+            selector_id: args.selector_id,
+            selector_layer: args.selector_layer,
+            glyph_matrix_id: args.glyph_matrix_id,
+            highlight_id: args.highlight_id,
+            offset: args.offset,
+            col: 0,
+            row: 0,
+            frame_id: 0,
+            max_frame_id: args.max_frame_id,
+            focused: false,
+            bindings: args.bindings,
+        }
+    }
+
+    pub fn move_left(&mut self, mgr: &mut Manager) {
+        if self.col > 0 {
+            self.col -= 1;
+            mgr.move_graphic(self.selector_id, self.selector_layer, (-1, 0));
+        } else {
+            mgr.move_graphic(
+                self.selector_id,
+                self.selector_layer,
+                (LAST_COL as isize, 0),
+            );
+            self.col = LAST_COL;
+        }
+    }
+
+    pub fn move_right(&mut self, mgr: &mut Manager) {
+        if self.col < LAST_COL {
+            self.col += 1;
+            mgr.move_graphic(self.selector_id, self.selector_layer, (1, 0));
+        } else {
+            mgr.move_graphic(
+                self.selector_id,
+                self.selector_layer,
+                (-(LAST_COL as isize), 0),
+            );
+            self.clear_selector_trace(mgr);
+            self.col = 0;
+        }
+    }
+
+    pub fn move_up(&mut self, mgr: &mut Manager) {
+        if self.row > 0 {
+            self.row -= 1;
+            mgr.move_graphic(self.selector_id, self.selector_layer, (0, -1));
+        } else {
+            mgr.move_graphic(
+                self.selector_id,
+                self.selector_layer,
+                (0, LAST_ROW as isize),
+            );
+            self.row = LAST_ROW;
+        }
+    }
+
+    pub fn move_down(&mut self, mgr: &mut Manager) {
+        if self.row < LAST_ROW {
+            self.row += 1;
+            mgr.move_graphic(self.selector_id, self.selector_layer, (0, 1));
+        } else {
+            mgr.move_graphic(
+                self.selector_id,
+                self.selector_layer,
+                (0, -(LAST_ROW as isize)),
+            );
+            self.clear_selector_trace(mgr);
+            self.row = 0;
+        }
+    }
+
+    /// Selector jumping back to first column or row leaves it's old frame behind.
+    fn clear_selector_trace(&self, mgr: &mut Manager) {
+        let start_x = max(0, self.offset.0) as usize + self.col;
+        let start_y = max(0, self.offset.1) as usize + self.row;
+        mgr.clear_area(self.selector_layer, (start_x, start_y), (2, 3));
+    }
+
+    pub fn show_page(&mut self, mgr: &mut Manager, frame_id: usize) {
+        self.frame_id = frame_id;
+        mgr.set_graphic(self.glyph_matrix_id, frame_id, true);
+        // Highlight shows up once it's frame is set, so it is only updated when focused
+        if self.focused {
+            mgr.set_graphic(self.highlight_id, frame_id, false);
+        }
+    }
+
+    pub fn prev_page(&mut self, mgr: &mut Manager) {
+        let frame_id = if self.frame_id == 0 {
+            self.max_frame_id
+        } else {
+            self.frame_id - 1
+        };
+        self.show_page(mgr, frame_id);
+    }
+
+    pub fn next_page(&mut self, mgr: &mut Manager) {
+        let frame_id = if self.frame_id == self.max_frame_id {
+            0
+        } else {
+            self.frame_id + 1
+        };
+        self.show_page(mgr, frame_id);
+    }
+
+    /// Glyph under selector.
+    pub fn selected(&self, mgr: &mut Manager) -> Option<Glyph> {
+        mgr.get_glyph(self.glyph_matrix_id, self.col + 1, self.row + 1)
+            .wait()
+            .ok()
+    }
+
+    /// Glyph under selector, with selector flashing to show it was picked.
+    pub fn pick(&self, mgr: &mut Manager) -> Option<Glyph> {
+        mgr.start_animation(self.selector_id, 1);
+        mgr.enqueue_animation(self.selector_id, 0, Timestamp::now());
+        self.selected(mgr)
+    }
+
+    /// Move selector onto given character, if it is on current page.
+    pub fn select_char(&mut self, mgr: &mut Manager, character: char) {
+        for col in 0..=LAST_COL {
+            for row in 0..=LAST_ROW {
+                if let Ok(glyph) = mgr.get_glyph(self.glyph_matrix_id, col + 1, row + 1).wait() {
+                    if glyph.character == character {
+                        let dc = col as isize - self.col as isize;
+                        let dr = row as isize - self.row as isize;
+                        self.col = col;
+                        self.row = row;
+                        mgr.move_graphic(self.selector_id, self.selector_layer, (dc, dr));
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Handle a key bound to this window, regardless of focus.
+    /// Selecting a glyph is left for Workspace, since that is where it goes.
+    pub fn handle_binding(&mut self, mgr: &mut Manager, key: &Key) -> bool {
+        let bindings = self.bindings.clone();
+        match key {
+            k if bindings.glyphs_left.contains(k) => self.move_left(mgr),
+            k if bindings.glyphs_right.contains(k) => self.move_right(mgr),
+            k if bindings.glyphs_up.contains(k) => self.move_up(mgr),
+            k if bindings.glyphs_down.contains(k) => self.move_down(mgr),
+            k if bindings.glyphs_prev.contains(k) => self.prev_page(mgr),
+            k if bindings.glyphs_next.contains(k) => self.next_page(mgr),
+            k if bindings.glyphs_home.contains(k) => self.show_page(mgr, 0),
+            k if bindings.glyphs_end.contains(k) => self.show_page(mgr, self.max_frame_id),
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl Focusable for GlyphsWindow {
+    fn handle_key(&mut self, mgr: &mut Manager, key: &Key) -> bool {
+        if self.handle_binding(mgr, key) {
+            return true;
+        }
+        match self.bindings.navigation(key) {
+            Some(Move::Left) => self.move_left(mgr),
+            Some(Move::Right) => self.move_right(mgr),
+            Some(Move::Up) => self.move_up(mgr),
+            Some(Move::Down) => self.move_down(mgr),
+            Some(Move::Prev) => self.prev_page(mgr),
+            Some(Move::Next) => self.next_page(mgr),
+            Some(Move::First) => self.show_page(mgr, 0),
+            Some(Move::Last) => self.show_page(mgr, self.max_frame_id),
+            None => return false,
+        }
+        true
+    }
+
+    fn set_focused(&mut self, mgr: &mut Manager, focused: bool) {
+        self.focused = focused;
+        mgr.set_invisible(self.highlight_id, !focused);
+        if focused {
+            mgr.set_graphic(self.highlight_id, self.frame_id, false);
+        }
+    }
+}
@@ -321,7 +321,16 @@ pub fn build_workspace_matrix(cols: usize, rows: usize, graphic: Option<Graphic>
     Graphic::new(cols + 2, rows + 2, 0, library, None)
 }
 
-pub fn build_glyph_matrix(index_file: Option<String>) -> Graphic {
+/// Builds a reversed copy of window's title, to be shown over it when window is focused.
+pub fn build_title_highlight(title: &str) -> Graphic {
+    let mut glyph = Glyph::default();
+    glyph.set_reverse(true);
+    Graphic::from_text(title.chars().count(), title, glyph)
+}
+
+/// Glyph matrix, along with titles of it's pages.
+pub fn build_glyph_matrix(index_file: Option<String>) -> (Graphic, Vec<String>) {
+    let mut titles = vec![];
     let mut glyph_files = vec![];
     if let Some(index_file) = index_file {
         if let Ok(file) = File::open(index_file) {
@@ -466,6 +475,7 @@ pub fn build_glyph_matrix(index_file: Option<String>) -> Graphic {
                 avail_index,
                 wrap_border_around(frame, cols, border, Some(&name)),
             );
+            titles.push(name);
             avail_index += 1;
         }
     }
@@ -481,8 +491,30 @@ pub fn build_glyph_matrix(index_file: Option<String>) -> Graphic {
         avail_index,
         wrap_border_around(frame, cols, border, Some("default")),
     );
+    titles.push("default".to_string());
 
-    Graphic::new(cols + 2, rows + 2, avail_index, library, None)
+    (
+        Graphic::new(cols + 2, rows + 2, avail_index, library, None),
+        titles,
+    )
+}
+
+/// Highlight of titles placed on a border of given width, one frame for every title.
+pub fn build_titles_highlight(titles: &[String], cols: usize) -> Graphic {
+    let border = Glyph::default_with_char('\u{2500}');
+    let mut highlight = border;
+    highlight.set_reverse(true);
+    let width = cols.saturating_sub(2);
+    let mut library = HashMap::with_capacity(titles.len());
+    for (frame_id, title) in titles.iter().enumerate() {
+        let mut frame = vec![border; width];
+        for (glyph, character) in frame.iter_mut().zip(title.chars()) {
+            *glyph = highlight;
+            glyph.set_char(character);
+        }
+        library.insert(frame_id, frame);
+    }
+    Graphic::new(width, 1, 0, library, None)
 }
 
 pub fn build_selector() -> Graphic {
@@ -17,6 +17,11 @@
 //! All navigation in studio is done via keyboard. You can customize your preffered key for every
 //! available action in this app.
 //!
+//! Tab and Shift-Tab move focus between Workspace, Color, Background, Glyphs and Style windows,
+//! focused window has it's title highlighted. Keys bound to moving around Workspace
+//! and to paging through Glyphs move within focused window instead,
+//! all other keys work as bound regardless of focus.
+//!
 //! ## TUI elements
 //! studio consists of five interactive windows:
 //! - Color
@@ -63,6 +68,12 @@
 //! <frame_id>:<time_msec> defines a frame and it's display time. You can define a lot of these in every animation definition.
 //!
 //! > animation loop run 0:1000 1:1000 2:1000 3:1000 4:1000 5:1000 6:1000 7:1000 8:1000 9:1000
+// Lints reported by newer clippy are left alone until studio gets reworked
+#![allow(
+    clippy::unnecessary_unwrap,
+    clippy::double_ended_iterator_last,
+    clippy::collapsible_match
+)]
 
 //!
 //! ## Configuration file
@@ -169,16 +180,15 @@
 
 use animaterm::prelude::*;
 use animaterm::utilities::progress_bar;
-use std::cmp::max;
 use std::default::Default;
-use std::fs::rename;
-use std::path::Path;
 use std::process::exit;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::rc::Rc;
+use std::time::Duration;
 mod helpers;
 use helpers::{
     build_basic_colors_graphic, build_color_selector, build_glyph_matrix, build_glyph_selector,
-    build_selector, build_style_graphics, build_workspace_matrix,
+    build_selector, build_style_graphics, build_title_highlight, build_titles_highlight,
+    build_workspace_matrix,
 };
 mod arguments;
 use arguments::{parse_arguments, read_config_file, verify_cols_and_rows};
@@ -188,6 +198,10 @@ use style_window::StyleWindowArgs;
 mod colors_window;
 use colors_window::ColorsWindow;
 use colors_window::ColorsWindowArgs;
+mod glyphs_window;
+use glyphs_window::{GlyphsWindow, GlyphsWindowArgs};
+mod workspace_window;
+use workspace_window::{WindowIds, WorkspaceWindow, WorkspaceWindowArgs};
 
 fn main() {
     let mut args = parse_arguments();
@@ -274,6 +288,7 @@ fn main() {
             ),
         ])
     };
+    let bindings = Rc::new(args.bindings);
     let mut mgr = Manager::new(
         true,
         cols,
@@ -291,16 +306,16 @@ fn main() {
     }
     let mut wallpaper_id = None;
     if let Some(wallpaper_file) = args.wallpaper_file {
-        let opt_wallpaper_graphic = Graphic::from_file(wallpaper_file);
-        if let Some(wallpaper_graphic) = opt_wallpaper_graphic {
+        let wallpaper_result = Graphic::from_file(wallpaper_file);
+        if let Ok(wallpaper_graphic) = wallpaper_result {
             let result = mgr.add_graphic(wallpaper_graphic, 0, (0, 0));
             if result.is_none() {
                 eprintln!("Unable to create wallpaper graphic.");
             } else {
                 wallpaper_id = result;
             }
-        } else {
-            eprintln!("Unable to load wallpaper graphic file.")
+        } else if let Err(e) = wallpaper_result {
+            eprintln!("Unable to load wallpaper graphic file: {:?}", e)
         }
     }
     if let Some(w_id) = wallpaper_id {
@@ -318,9 +333,8 @@ fn main() {
         exit(2);
     }
     let _other_selector = build_glyph_selector();
-    let glyph_matrix = build_glyph_matrix(args.glyphs);
+    let (glyph_matrix, glyph_titles) = build_glyph_matrix(args.glyphs);
     let max_glyph_frame_id = glyph_matrix.current_frame;
-    let mut glyph_frame_id = 0;
     let glyph_matrix_id;
     let result = mgr.add_graphic(glyph_matrix, 1, glyphs_offset);
     if let Some(id) = result {
@@ -519,6 +533,18 @@ fn main() {
     mgr.set_invisible(pb3t_id, true);
     mgr.set_invisible(pb3_id, true);
 
+    let colors_highlight_id;
+    let result = mgr.add_graphic(
+        build_title_highlight("Color"),
+        2,
+        (color_offset_cols + 1, color_offset_rows),
+    );
+    if let Some(id) = result {
+        colors_highlight_id = id;
+    } else {
+        eprintln!("Did not receive color window's highlight graphic id");
+        exit(2);
+    }
     let cw_args = ColorsWindowArgs {
         sender: mgr.get_message_sender(),
         selected_tab: 0,
@@ -533,8 +559,11 @@ fn main() {
         progress_bar_3_id: pb3_id,
         progress_bar_3_title_id: pb3t_id,
         glyph_matrix_id,
+        highlight_id: colors_highlight_id,
+        background: false,
+        bindings: bindings.clone(),
     };
-    let mut colors_window = ColorsWindow::new(cw_args);
+    let colors_window = ColorsWindow::new(cw_args);
     mgr.set_graphic(pb1_id, 0, false);
     mgr.set_graphic(pb2_id, 0, false);
     mgr.set_graphic(pb3_id, 0, false);
@@ -734,6 +763,18 @@ fn main() {
     }
     mgr.set_graphic(bg_basic_sel_id, 0, true);
 
+    let backgrounds_highlight_id;
+    let result = mgr.add_graphic(
+        build_title_highlight("Background"),
+        2,
+        (bg_offset_cols + 1, bg_offset_rows),
+    );
+    if let Some(id) = result {
+        backgrounds_highlight_id = id;
+    } else {
+        eprintln!("Did not receive background window's highlight graphic id");
+        exit(2);
+    }
     let bw_args = ColorsWindowArgs {
         sender: mgr.get_message_sender(),
         selected_tab: 0,
@@ -748,8 +789,11 @@ fn main() {
         progress_bar_3_id: bg_pb3_id,
         progress_bar_3_title_id: bg_pb3t_id,
         glyph_matrix_id,
+        highlight_id: backgrounds_highlight_id,
+        background: true,
+        bindings: bindings.clone(),
     };
-    let mut backgrounds_window = ColorsWindow::new(bw_args);
+    let backgrounds_window = ColorsWindow::new(bw_args);
 
     // Workspace window
     let mut workspace_offset = ((start_col + 18) as isize, (start_row + 7) as isize);
@@ -764,7 +808,7 @@ fn main() {
     }
     let mut initial_workspace_graphic = None;
     if let Some(i_file) = args.input_file {
-        if let Some((cs, frame)) = frame_from_file(&i_file) {
+        if let Ok((cs, frame)) = frame_from_file(&i_file) {
             initial_workspace_graphic = Some(Graphic::from_frame(cs, frame));
             if let Some(ref loaded) = initial_workspace_graphic {
                 matrix_cols = loaded.cols;
@@ -786,6 +830,18 @@ fn main() {
     }
 
     mgr.set_graphic(workspace_id, 0, true);
+    let workspace_highlight_id;
+    let result = mgr.add_graphic(
+        build_title_highlight(&format!("Workspace {}x{}", matrix_cols, matrix_rows)),
+        2,
+        (workspace_offset.0 + 1, workspace_offset.1),
+    );
+    if let Some(id) = result {
+        workspace_highlight_id = id;
+    } else {
+        eprintln!("Did not receive workspace's highlight graphic id");
+        exit(2);
+    }
 
     let mut reversed = Glyph::default();
     reversed.set_reverse(true);
@@ -956,6 +1012,18 @@ fn main() {
     mgr.set_graphic(style_transparent_id, 0, true);
     mgr.set_graphic(style_strike_id, 0, true);
 
+    let style_highlight_id;
+    let result = mgr.add_graphic(
+        build_title_highlight("Style"),
+        2,
+        (styles_offset_cols + 1, styles_offset_rows),
+    );
+    if let Some(id) = result {
+        style_highlight_id = id;
+    } else {
+        eprintln!("Did not receive style window's highlight graphic id");
+        exit(2);
+    }
     let sw_args = StyleWindowArgs {
         style_glyph: Glyph::default(),
         sender: mgr.get_message_sender(),
@@ -972,22 +1040,35 @@ fn main() {
         style_transparent_id,
         style_strike_id,
         style_selector_value: 0,
+        highlight_id: style_highlight_id,
+        bindings: bindings.clone(),
     };
-    let mut style_window = StyleWindow::new(sw_args);
-
-    let mut c = 1; // worskpace column where cursor is placed
-    let mut r = 1; // worskpace row where cursor is placed
-    let mut mc: usize = 0; // glyph matrix column where selector is placed
-    let mut mr: usize = 0; // glyph matrix row where selector is placed
+    let style_window = StyleWindow::new(sw_args);
 
-    let mut glyph_under_cursor = Glyph::default();
-    mgr.get_glyph(workspace_id, c, r);
-    let result = mgr.read_result();
-    if let Ok(AnimOk::GlyphRetrieved(_gid, glyph)) = result {
-        glyph_under_cursor = glyph;
+    let glyphs_highlight_id;
+    let result = mgr.add_graphic(
+        build_titles_highlight(&glyph_titles, 16),
+        2,
+        (glyphs_offset.0 + 1, glyphs_offset.1),
+    );
+    if let Some(id) = result {
+        glyphs_highlight_id = id;
+    } else {
+        eprintln!("Did not receive glyphs window's highlight graphic id");
+        exit(2);
     }
+    let gw_args = GlyphsWindowArgs {
+        selector_id,
+        selector_layer,
+        glyph_matrix_id,
+        highlight_id: glyphs_highlight_id,
+        offset: glyphs_offset,
+        max_frame_id: max_glyph_frame_id,
+        bindings: bindings.clone(),
+    };
+    let glyphs_window = GlyphsWindow::new(gw_args);
 
-    let g = Glyph::new(
+    let cursor_glyph = Glyph::new(
         '\u{2588}',
         Color::blue(),
         Color::white(),
@@ -1002,16 +1083,39 @@ fn main() {
         false,
         false,
     );
-    mgr.set_glyph(workspace_id, g, c, r);
-    let mut action_counter = 1;
-    let mut counter_initialized = false;
+    let ww_args = WorkspaceWindowArgs {
+        workspace_id,
+        highlight_id: workspace_highlight_id,
+        cols: matrix_cols,
+        rows: matrix_rows,
+        cursor_glyph,
+        bindings: bindings.clone(),
+    };
+    let mut workspace_window = WorkspaceWindow::new(ww_args);
+    workspace_window.show_cursor(&mut mgr);
+
+    // Tab and Shift-Tab move focus between windows, focused window gets keys first.
+    // Workspace is parent of all other windows, so whatever they do not consume
+    // goes to Workspace, which passes keys bound to other windows on to them.
+    let workspace_widget_id = mgr.register_widget(Box::new(workspace_window), None);
+    let parent = Some(workspace_widget_id);
+    let windows = WindowIds {
+        colors: mgr.register_widget(Box::new(colors_window), parent),
+        backgrounds: mgr.register_widget(Box::new(backgrounds_window), parent),
+        glyphs: mgr.register_widget(Box::new(glyphs_window), parent),
+        style: mgr.register_widget(Box::new(style_window), parent),
+    };
+    mgr.update_widget(workspace_widget_id, |workspace: &mut WorkspaceWindow, _| {
+        workspace.windows = windows
+    });
+
     loop {
         let read_result = mgr.read_key();
         if read_result.is_none() {
             continue;
         }
         let key_pressed = read_result.unwrap();
-        if macro_mode == 2 && !args.bindings.macro_key.contains(&key_pressed) {
+        if macro_mode == 2 && !bindings.macro_key.contains(&key_pressed) {
             if macro_loop {
                 mgr.set_graphic(selector_id, 28, true);
             } else {
@@ -1019,614 +1123,10 @@ fn main() {
             }
             macro_mode = 3;
         }
-        match key_pressed {
-            // Colors window
-            k if args.bindings.colors_left.contains(&k) => {
-                colors_window.move_left(false);
-            }
-            k if args.bindings.colors_right.contains(&k) => {
-                colors_window.move_right(false);
-            }
-            k if args.bindings.colors_far_right.contains(&k) => {
-                colors_window.move_far_right(false);
-            }
-            k if args.bindings.colors_far_left.contains(&k) => {
-                colors_window.move_far_left(false);
-            }
-            k if args.bindings.colors_up.contains(&k) => {
-                colors_window.move_up();
-            }
-            k if args.bindings.colors_top.contains(&k) => {
-                colors_window.move_top();
-            }
-            k if args.bindings.colors_down.contains(&k) => {
-                colors_window.move_down();
-            }
-            k if args.bindings.colors_bottom.contains(&k) => {
-                colors_window.move_bottom();
-            }
-            k if args.bindings.colors_invisible.contains(&k) => {
-                colors_window.set_invisible(true);
-            }
-            k if args.bindings.colors_visible.contains(&k) => {
-                colors_window.set_invisible(false);
-            }
-
-            // Background window
-            k if args.bindings.backgrounds_left.contains(&k) => {
-                backgrounds_window.move_left(true);
-            }
-            k if args.bindings.backgrounds_right.contains(&k) => {
-                backgrounds_window.move_right(true);
-            }
-            k if args.bindings.backgrounds_far_left.contains(&k) => {
-                backgrounds_window.move_far_left(true);
-            }
-            k if args.bindings.backgrounds_far_right.contains(&k) => {
-                backgrounds_window.move_far_right(true);
-            }
-            k if args.bindings.backgrounds_up.contains(&k) => {
-                backgrounds_window.move_up();
-            }
-            k if args.bindings.backgrounds_top.contains(&k) => {
-                backgrounds_window.move_top();
-            }
-            k if args.bindings.backgrounds_down.contains(&k) => {
-                backgrounds_window.move_down();
-            }
-            k if args.bindings.backgrounds_bottom.contains(&k) => {
-                backgrounds_window.move_bottom();
-            }
-            k if args.bindings.backgrounds_invisible.contains(&k) => {
-                backgrounds_window.set_invisible(true);
-            }
-            k if args.bindings.backgrounds_visible.contains(&k) => {
-                backgrounds_window.set_invisible(false);
-            }
-
-            //Glyphs window
-            k if args.bindings.glyphs_left.contains(&k) => {
-                if mc > 0 {
-                    mc -= 1;
-                    mgr.move_graphic(selector_id, 2, (-1, 0))
-                } else {
-                    mgr.move_graphic(selector_id, 2, (15, 0));
-                    mc = 15;
-                }
-            }
-
-            // glyphs
-            k if args.bindings.glyphs_right.contains(&k) => {
-                if mc < 15 {
-                    mc += 1;
-                    mgr.move_graphic(selector_id, 2, (1, 0));
-                } else {
-                    mgr.move_graphic(selector_id, 2, (-15, 0));
-                    let start_x = max(0, glyphs_offset.0) as usize + mc;
-                    let start_y = max(0, glyphs_offset.1) as usize + mr;
-                    mgr.clear_area(selector_layer, (start_x, start_y), (2, 3));
-                    mc = 0;
-                }
-            }
-
-            // glyphs
-            k if args.bindings.glyphs_up.contains(&k) => {
-                if mr > 0 {
-                    mr -= 1;
-                    mgr.move_graphic(selector_id, 2, (0, -1))
-                } else {
-                    mgr.move_graphic(selector_id, 2, (0, 9));
-                    mr = 9;
-                }
-            }
-
-            //glyphs
-            k if args.bindings.glyphs_down.contains(&k) => {
-                if mr < 9 {
-                    mr += 1;
-                    mgr.move_graphic(selector_id, 2, (0, 1))
-                } else {
-                    mgr.move_graphic(selector_id, 2, (0, -(mr as isize)));
-                    let start_x = max(0, glyphs_offset.0) as usize + mc;
-                    let start_y = max(0, glyphs_offset.1) as usize + mr;
-                    mgr.clear_area(selector_layer, (start_x as usize, start_y as usize), (2, 3));
-                    mr = 0;
-                }
-            }
-
-            //glyphs
-            k if args.bindings.glyphs_select.contains(&k) => {
-                mgr.start_animation(selector_id, 1);
-                mgr.enqueue_animation(selector_id, 0, Timestamp::now());
-
-                mgr.get_glyph(glyph_matrix_id, mc + 1, mr + 1);
-                let result = mgr.read_result();
-                if let Ok(AnimOk::GlyphRetrieved(_gid, glyph)) = result {
-                    while action_counter > 0 {
-                        mgr.set_glyph(workspace_id, glyph, c, r);
-                        if c < matrix_cols {
-                            c += 1;
-                        } else if r < matrix_rows {
-                            c = 1;
-                            r += 1;
-                        } else {
-                            c = 1;
-                            r = 1;
-                        }
-                        action_counter -= 1;
-                    }
-                    action_counter = 1;
-                    counter_initialized = false;
-                    mgr.get_glyph(workspace_id, c, r);
-                    let result = mgr.read_result();
-                    if let Ok(AnimOk::GlyphRetrieved(_gid, glyph)) = result {
-                        glyph_under_cursor = glyph;
-                    }
-                    mgr.set_glyph(workspace_id, g, c, r);
-                }
-            }
-
-            //glyphs
-            k if args.bindings.glyphs_prev.contains(&k) => {
-                if glyph_frame_id == 0 {
-                    glyph_frame_id = max_glyph_frame_id;
-                } else {
-                    glyph_frame_id -= 1;
-                }
-                mgr.set_graphic(glyph_matrix_id, glyph_frame_id, true)
-            }
-            //glyphs
-            k if args.bindings.glyphs_home.contains(&k) => {
-                glyph_frame_id = 0;
-                mgr.set_graphic(glyph_matrix_id, glyph_frame_id, true);
-            }
-            //glyphs
-            k if args.bindings.glyphs_next.contains(&k) => {
-                if glyph_frame_id == max_glyph_frame_id {
-                    glyph_frame_id = 0;
-                } else {
-                    glyph_frame_id += 1;
-                }
-                mgr.set_graphic(glyph_matrix_id, glyph_frame_id, true)
-            }
-            //glyphs
-            k if args.bindings.glyphs_end.contains(&k) => {
-                glyph_frame_id = max_glyph_frame_id;
-                mgr.set_graphic(glyph_matrix_id, glyph_frame_id, true);
-            }
-
-            // workspace window
-            k if args.bindings.workspace_left.contains(&k) => {
-                mgr.set_glyph(workspace_id, glyph_under_cursor, c, r);
-                if c > 1 {
-                    c -= 1;
-                } else {
-                    c = matrix_cols;
-                    if r > 1 {
-                        r -= 1;
-                    } else {
-                        r = matrix_rows;
-                    }
-                }
-                mgr.get_glyph(workspace_id, c, r);
-                let result = mgr.read_result();
-                if let Ok(AnimOk::GlyphRetrieved(_gid, glyph)) = result {
-                    glyph_under_cursor = glyph;
-                }
-                mgr.set_glyph(workspace_id, g, c, r);
-            }
-            k if args.bindings.workspace_line_start.contains(&k) => {
-                mgr.set_glyph(workspace_id, glyph_under_cursor, c, r);
-                c = 1;
-                mgr.get_glyph(workspace_id, c, r);
-                let result = mgr.read_result();
-                if let Ok(AnimOk::GlyphRetrieved(_gid, glyph)) = result {
-                    glyph_under_cursor = glyph;
-                }
-                mgr.set_glyph(workspace_id, g, c, r);
-            }
-
-            k if args.bindings.workspace_line_end.contains(&k) => {
-                mgr.set_glyph(workspace_id, glyph_under_cursor, c, r);
-                c = matrix_cols;
-                mgr.get_glyph(workspace_id, c, r);
-                let result = mgr.read_result();
-                if let Ok(AnimOk::GlyphRetrieved(_gid, glyph)) = result {
-                    glyph_under_cursor = glyph;
-                }
-                mgr.set_glyph(workspace_id, g, c, r);
-            }
-
-            // workspace window
-            k if args.bindings.workspace_right.contains(&k) => {
-                mgr.set_glyph(workspace_id, glyph_under_cursor, c, r);
-                if c < matrix_cols {
-                    c += 1;
-                } else {
-                    c = 1;
-                    if r < matrix_rows {
-                        r += 1;
-                    } else {
-                        r = 1;
-                    }
-                }
-                mgr.get_glyph(workspace_id, c, r);
-                let result = mgr.read_result();
-                if let Ok(AnimOk::GlyphRetrieved(_gid, glyph)) = result {
-                    glyph_under_cursor = glyph;
-                }
-                mgr.set_glyph(workspace_id, g, c, r);
-            }
-
-            //workspace window
-            k if args.bindings.workspace_set_color.contains(&k) => {
-                // println!("set color!");
-                mgr.get_glyph(glyph_matrix_id, mc + 1, mr + 1);
-                let result = mgr.read_result();
-                if let Ok(AnimOk::GlyphRetrieved(_gid, glyph)) = result {
-                    while action_counter > 0 {
-                        glyph_under_cursor.set_color(glyph.color);
-                        mgr.set_glyph(workspace_id, glyph_under_cursor, c, r);
-                        if c < matrix_cols {
-                            c += 1;
-                        } else if r < matrix_rows {
-                            c = 1;
-                            r += 1;
-                        } else {
-                            c = 1;
-                            r = 1;
-                        }
-                        mgr.get_glyph(workspace_id, c, r);
-                        let result = mgr.read_result();
-                        if let Ok(AnimOk::GlyphRetrieved(_gid, glyph)) = result {
-                            glyph_under_cursor = glyph;
-                        }
-                        action_counter -= 1;
-                    }
-                    mgr.set_glyph(workspace_id, g, c, r);
-                    action_counter = 1;
-                    counter_initialized = false;
-                }
-            }
-            k if args.bindings.workspace_set_background.contains(&k) => {
-                mgr.get_glyph(glyph_matrix_id, mc + 1, mr + 1);
-                let result = mgr.read_result();
-                if let Ok(AnimOk::GlyphRetrieved(_gid, glyph)) = result {
-                    while action_counter > 0 {
-                        glyph_under_cursor.set_background(glyph.background);
-                        mgr.set_glyph(workspace_id, glyph_under_cursor, c, r);
-                        if c < matrix_cols {
-                            c += 1;
-                        } else if r < matrix_rows {
-                            c = 1;
-                            r += 1;
-                        } else {
-                            c = 1;
-                            r = 1;
-                        }
-                        mgr.get_glyph(workspace_id, c, r);
-                        let result = mgr.read_result();
-                        if let Ok(AnimOk::GlyphRetrieved(_gid, glyph)) = result {
-                            glyph_under_cursor = glyph;
-                        }
-                        action_counter -= 1;
-                    }
-                    mgr.set_glyph(workspace_id, g, c, r);
-                    action_counter = 1;
-                    counter_initialized = false;
-                }
-            }
-            k if args.bindings.workspace_set_glyph.contains(&k) => {
-                mgr.get_glyph(glyph_matrix_id, mc + 1, mr + 1);
-                let result = mgr.read_result();
-                if let Ok(AnimOk::GlyphRetrieved(_gid, glyph)) = result {
-                    while action_counter > 0 {
-                        glyph_under_cursor.set_char(glyph.character);
-                        mgr.set_glyph(workspace_id, glyph_under_cursor, c, r);
-                        if c < matrix_cols {
-                            c += 1;
-                        } else if r < matrix_rows {
-                            c = 1;
-                            r += 1;
-                        } else {
-                            c = 1;
-                            r = 1;
-                        }
-                        mgr.get_glyph(workspace_id, c, r);
-                        let result = mgr.read_result();
-                        if let Ok(AnimOk::GlyphRetrieved(_gid, glyph)) = result {
-                            glyph_under_cursor = glyph;
-                        }
-                        action_counter -= 1;
-                    }
-                    mgr.set_glyph(workspace_id, g, c, r);
-                    action_counter = 1;
-                    counter_initialized = false;
-                }
-            }
-            k if args.bindings.workspace_set_style.contains(&k) => {
-                let mut new_glyph = style_window.style_glyph;
-                new_glyph.set_char(glyph_under_cursor.character);
-                new_glyph.set_color(glyph_under_cursor.color);
-                new_glyph.set_background(glyph_under_cursor.background);
-                while action_counter > 0 {
-                    mgr.set_glyph(workspace_id, new_glyph, c, r);
-                    if c < matrix_cols {
-                        c += 1;
-                    } else if r < matrix_rows {
-                        c = 1;
-                        r += 1;
-                    } else {
-                        c = 1;
-                        r = 1;
-                    }
-                    mgr.get_glyph(workspace_id, c, r);
-                    let result = mgr.read_result();
-                    if let Ok(AnimOk::GlyphRetrieved(_gid, glyph)) = result {
-                        glyph_under_cursor = glyph;
-                    }
-                    action_counter -= 1;
-                }
-                mgr.set_glyph(workspace_id, g, c, r);
-                action_counter = 1;
-                counter_initialized = false;
-            }
-            k if args.bindings.workspace_select_color.contains(&k) => {
-                colors_window.select_color(glyph_under_cursor.color, false);
-            }
-            k if args.bindings.workspace_select_color.contains(&k) => {
-                colors_window.select_color(glyph_under_cursor.color, false);
-            }
-            k if args.bindings.workspace_select_background.contains(&k) => {
-                backgrounds_window.select_color(glyph_under_cursor.background, true);
-            }
-            k if args.bindings.workspace_select_glyph.contains(&k) => {
-                'break_point: for c in 1..17 {
-                    for r in 0..10 {
-                        mgr.get_glyph(glyph_matrix_id, c, r);
-                        let result = mgr.read_result();
-                        if let Ok(AnimOk::GlyphRetrieved(_gid, glyph)) = result {
-                            if glyph.character == glyph_under_cursor.character {
-                                let dc: isize = c as isize - mc as isize - 1;
-                                let dr: isize = r as isize - mr as isize - 1;
-                                mc = c - 1;
-                                mr = r - 1;
-                                mgr.move_graphic(selector_id, 2, (dc, dr));
-                                break 'break_point;
-                            };
-                        }
-                    }
-                }
-            }
-            k if args.bindings.workspace_select_style.contains(&k) => {
-                if glyph_under_cursor.bright {
-                    mgr.set_graphic(style_bright_id, 1, false);
-                    style_window.style_glyph.set_bright(true);
-                } else {
-                    mgr.set_graphic(style_bright_id, 0, false);
-                    style_window.style_glyph.set_bright(false);
-                }
-                if glyph_under_cursor.dim {
-                    mgr.set_graphic(style_dim_id, 1, false);
-                    style_window.style_glyph.set_dim(true);
-                } else {
-                    mgr.set_graphic(style_dim_id, 0, false);
-                    style_window.style_glyph.set_dim(false);
-                }
-                if glyph_under_cursor.italic {
-                    mgr.set_graphic(style_italic_id, 1, false);
-                    style_window.style_glyph.set_italic(true);
-                } else {
-                    mgr.set_graphic(style_italic_id, 0, false);
-                    style_window.style_glyph.set_italic(false);
-                }
-                if glyph_under_cursor.underline {
-                    mgr.set_graphic(style_underline_id, 1, false);
-                    style_window.style_glyph.set_underline(true);
-                } else {
-                    mgr.set_graphic(style_underline_id, 0, false);
-                    style_window.style_glyph.set_underline(false);
-                }
-                if glyph_under_cursor.blink {
-                    mgr.set_graphic(style_blink_id, 1, false);
-                    style_window.style_glyph.set_blink(true);
-                } else {
-                    mgr.set_graphic(style_blink_id, 0, false);
-                    style_window.style_glyph.set_blink(false);
-                }
-                if glyph_under_cursor.blink_fast {
-                    mgr.set_graphic(style_blinkfast_id, 1, false);
-                    style_window.style_glyph.set_blinkfast(true);
-                } else {
-                    mgr.set_graphic(style_blinkfast_id, 0, false);
-                    style_window.style_glyph.set_blinkfast(false);
-                }
-                if glyph_under_cursor.reverse {
-                    mgr.set_graphic(style_reverse_id, 1, false);
-                    style_window.style_glyph.set_reverse(true);
-                } else {
-                    mgr.set_graphic(style_reverse_id, 0, false);
-                    style_window.style_glyph.set_reverse(false);
-                }
-                if glyph_under_cursor.transparent {
-                    mgr.set_graphic(style_transparent_id, 1, false);
-                    style_window.style_glyph.set_transparent(true);
-                } else {
-                    mgr.set_graphic(style_transparent_id, 0, false);
-                    style_window.style_glyph.set_transparent(false);
-                }
-                if glyph_under_cursor.strike {
-                    mgr.set_graphic(style_strike_id, 1, false);
-                    style_window.style_glyph.set_strike(true);
-                } else {
-                    mgr.set_graphic(style_strike_id, 0, false);
-                    style_window.style_glyph.set_strike(false);
-                }
-                style_window.activate_style_on_glyph_matrix();
-            }
-
-            //workspace window
-            k if args.bindings.workspace_up.contains(&k) => {
-                // workspace_window.move_cursor_up();
-                mgr.set_glyph(workspace_id, glyph_under_cursor, c, r);
-                if r > 1 {
-                    r -= 1;
-                } else {
-                    r = matrix_rows;
-                }
-                mgr.get_glyph(workspace_id, c, r);
-                let result = mgr.read_result();
-                if let Ok(AnimOk::GlyphRetrieved(_gid, glyph)) = result {
-                    glyph_under_cursor = glyph;
-                }
-                mgr.set_glyph(workspace_id, g, c, r);
-            }
-
-            // workspace window
-            k if args.bindings.workspace_down.contains(&k) => {
-                // workspace_window.move_cursor_down(glyph_under_cursor);
-                mgr.set_glyph(workspace_id, glyph_under_cursor, c, r);
-                if r < matrix_rows {
-                    r += 1;
-                } else {
-                    r = 1;
-                }
-                mgr.get_glyph(workspace_id, c, r);
-                let result = mgr.read_result();
-                if let Ok(AnimOk::GlyphRetrieved(_gid, glyph)) = result {
-                    glyph_under_cursor = glyph;
-                }
-                mgr.set_glyph(workspace_id, g, c, r);
-            }
-
-            // workspace window
-            k if args.bindings.workspace_erase.contains(&k) => {
-                while action_counter > 0 {
-                    mgr.set_glyph(workspace_id, Glyph::default(), c, r);
-                    if c > 1 {
-                        c -= 1
-                    } else {
-                        if r > 1 {
-                            r -= 1;
-                        } else {
-                            r = matrix_rows;
-                        }
-                        c = matrix_cols;
-                    }
-                    mgr.set_glyph(workspace_id, g, c, r);
-                    action_counter -= 1;
-                }
-                action_counter = 1;
-                counter_initialized = false;
-            }
-            // Key::AltCtrlShiftUp => {
-            //     // println!("move up!");
-            // }
-            // Key::AltCtrlShiftDown => {}
-            // Key::AltCtrlShiftLeft => {}
-            // Key::AltCtrlShiftRight => {}
-
-            // style window
-            k if args.bindings.style_up.contains(&k) => {
-                style_window.move_selector_up();
-            }
-            k if args.bindings.style_down.contains(&k) => {
-                style_window.move_selector_down();
-            }
-            k if args.bindings.style_enable.contains(&k) => {
-                style_window.enable_selected_style();
-            }
-            k if args.bindings.style_disable.contains(&k) => {
-                style_window.disable_selected_style();
-            }
-            // Key::AltCtrlShiftDown => {
-            //    style_window.move_selector_bottom();
-            // }
-            // Key::AltCtrlShiftUp => {
-            //     style_window.move_selector_top();
-            // }
-            k if args.bindings.print_graphic.contains(&k) => {
-                mgr.set_glyph(workspace_id, glyph_under_cursor, c, r);
-                // mgr.print_screen_section(
-                //     (workspace_offset.0 + 1, workspace_offset.1 + 1),
-                //     matrix_cols,
-                //     matrix_rows,
-                // );
-                mgr.print_graphic(workspace_id, true);
-                mgr.set_glyph(workspace_id, g, c, r);
-                let result = mgr.read_result();
-                if let Ok(AnimOk::PrintScreen(print_screen_text)) = result {
-                    use std::fs::OpenOptions;
-                    use std::io::Write;
-                    let secs = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs();
-                    let filename = format!("print_graphic_{}.txf", secs);
-
-                    let mut f = OpenOptions::new()
-                        .create(true)
-                        .truncate(true)
-                        .write(true)
-                        .append(false)
-                        .open(filename)
-                        .expect("Unable to create file");
-
-                    for line in print_screen_text.iter() {
-                        f.write_all(line.as_bytes()).expect("Unable to write data");
-                        // f.write_all("\x1b[K".as_bytes())
-                        //     .expect("Unable to write data");
-                        // f.write_all("\x1b[1B".as_bytes())
-                        //     .expect("Unable to write data");
-                        let fmted = "\n".to_string();
-                        f.write_all(fmted.as_bytes()).expect("Unable to write data");
-                    }
-                    //     let fmted = format!("\n\x1b[{}D", self.wiersze[0].len());
-                    //     f.write_all(fmted.as_bytes()).expect("Unable to write data");
-                }
-            }
-            k if args.bindings.print_screen.contains(&k) => {
-                mgr.set_glyph(workspace_id, glyph_under_cursor, c, r);
-                mgr.print_screen();
-                mgr.set_glyph(workspace_id, g, c, r);
-                let result = mgr.read_result();
-                if let Ok(AnimOk::PrintScreen(print_screen_text)) = result {
-                    use std::fs::OpenOptions;
-                    use std::io::Write;
-                    let secs = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs();
-                    let filename = format!("print_screen_{}.txf", secs);
-
-                    let mut f = OpenOptions::new()
-                        .create(true)
-                        .truncate(true)
-                        .write(true)
-                        .append(false)
-                        .open(filename)
-                        .expect("Unable to create file");
-
-                    for line in print_screen_text.iter() {
-                        f.write_all(line.as_bytes()).expect("Unable to write data");
-                        // f.write_all("\x1b[K".as_bytes())
-                        //     .expect("Unable to write data");
-                        // f.write_all("\x1b[1B".as_bytes())
-                        //     .expect("Unable to write data");
-                        let fmted = "\n".to_string();
-                        f.write_all(fmted.as_bytes()).expect("Unable to write data");
-                    }
-                    //     let fmted = format!("\n\x1b[{}D", self.wiersze[0].len());
-                    //     f.write_all(fmted.as_bytes()).expect("Unable to write data");
-                }
-            }
-            // reset action counter
-            k if args.bindings.action_counter_reset.contains(&k) => {
-                action_counter = 1;
-                counter_initialized = false;
-            }
+        match mgr.route_key(key_pressed) {
+            Routed::Handled(_) | Routed::FocusMoved(_) => {}
             // macro recording presentation logic
-            k if args.bindings.macro_key.contains(&k) => {
+            Routed::Unhandled(k) if bindings.macro_key.contains(&k) => {
                 match macro_mode {
                     0 => {
                         macro_mode = 1;
@@ -1654,150 +1154,20 @@ fn main() {
                 };
             }
             // exit program
-            k if args.bindings.exit.contains(&k) => {
+            Routed::Unhandled(k) if bindings.exit.contains(&k) => {
                 if let Some(output_file) = args.output_file {
-                    mgr.set_glyph(workspace_id, glyph_under_cursor, c, r);
-                    mgr.print_graphic(workspace_id, true);
-                    mgr.set_glyph(workspace_id, g, c, r);
-                    let result = mgr.read_result();
-                    if let Ok(AnimOk::PrintScreen(print_screen_text)) = result {
-                        use std::fs::OpenOptions;
-                        use std::io::Write;
-                        let old_path = Path::new(&output_file);
-
-                        if Path::exists(old_path) {
-                            let mut old_file;
-                            let mut base = output_file.clone();
-                            base.push('_');
-                            for i in 0..usize::MAX {
-                                old_file = base.clone();
-                                old_file.push_str(&format!("{}", i));
-                                let new_path = Path::new(&old_file);
-                                if !Path::exists(new_path) {
-                                    if rename(old_path, new_path).is_err() {
-                                        eprintln!(
-                                            "Unable to rename existing file {}, removing it.",
-                                            output_file
-                                        );
-                                    }
-                                    break;
-                                }
-                            }
-                        }
-
-                        let mut f = OpenOptions::new()
-                            .create(true)
-                            .append(true)
-                            .open(output_file)
-                            .expect("Unable to create file");
-
-                        for line in print_screen_text.iter() {
-                            f.write_all(line.as_bytes()).expect("Unable to write data");
-                            // f.write_all("\x1b[K".as_bytes())
-                            //     .expect("Unable to write data");
-                            // f.write_all("\x1b[1B".as_bytes())
-                            //     .expect("Unable to write data");
-                            let fmted = "\n".to_string();
-                            f.write_all(fmted.as_bytes()).expect("Unable to write data");
-                        }
-                        //     let fmted = format!("\n\x1b[{}D", self.wiersze[0].len());
-                        //     f.write_all(fmted.as_bytes()).expect("Unable to write data");
-                    }
+                    mgr.update_widget(
+                        workspace_widget_id,
+                        |workspace: &mut WorkspaceWindow, mgr| workspace.save(mgr, &output_file),
+                    );
                 }
                 break;
             }
-            Key::Zero => {
-                action_counter *= 10;
-            }
-            Key::One => {
-                if counter_initialized {
-                    action_counter *= 10;
-                    action_counter += 1;
-                }
-                counter_initialized = true;
-            }
-            Key::Two => {
-                if counter_initialized {
-                    action_counter *= 10;
-                    action_counter += 2;
-                } else {
-                    counter_initialized = true;
-                    action_counter = 2;
-                }
-            }
-            Key::Three => {
-                if counter_initialized {
-                    action_counter *= 10;
-                    action_counter += 3;
-                } else {
-                    counter_initialized = true;
-                    action_counter = 3;
-                }
-            }
-            Key::Four => {
-                if counter_initialized {
-                    action_counter *= 10;
-                    action_counter += 4;
-                } else {
-                    counter_initialized = true;
-                    action_counter = 4;
-                }
-            }
-            Key::Five => {
-                if counter_initialized {
-                    action_counter *= 10;
-                    action_counter += 5;
-                } else {
-                    counter_initialized = true;
-                    action_counter = 5;
-                }
-            }
-            Key::Six => {
-                if counter_initialized {
-                    action_counter *= 10;
-                    action_counter += 6;
-                } else {
-                    counter_initialized = true;
-                    action_counter = 6;
-                }
-            }
-            Key::Seven => {
-                if counter_initialized {
-                    action_counter *= 10;
-                    action_counter += 7;
-                } else {
-                    counter_initialized = true;
-                    action_counter = 7;
-                }
-            }
-            Key::Eight => {
-                if counter_initialized {
-                    action_counter *= 10;
-                    action_counter += 8;
-                } else {
-                    counter_initialized = true;
-                    action_counter = 8;
-                }
-            }
-            Key::Nine => {
-                if counter_initialized {
-                    action_counter *= 10;
-                    action_counter += 9;
-                } else {
-                    counter_initialized = true;
-                    action_counter = 9;
-                }
-            }
-
-            _ => {
-                // println!("You pressed {}", key);
-                continue;
-            }
+            Routed::Unhandled(_) => continue,
         }
         if macro_mode == 1 {
             macro_mode = 2;
         }
-        // }
     }
     mgr.terminate();
 }
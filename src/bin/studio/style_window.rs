@@ -1,5 +1,8 @@
+use crate::arguments::{Bindings, Move};
 use animaterm::glyph::Glyph;
+use animaterm::prelude::{Focusable, Key, Manager};
 use animaterm::Message;
+use std::rc::Rc;
 use std::sync::mpsc;
 
 pub struct StyleWindow {
@@ -18,6 +21,8 @@ pub struct StyleWindow {
     style_transparent_id: usize,
     style_strike_id: usize,
     style_selector_value: usize,
+    highlight_id: usize,
+    bindings: Rc<Bindings>,
 }
 
 pub struct StyleWindowArgs {
@@ -36,6 +41,8 @@ pub struct StyleWindowArgs {
     pub style_transparent_id: usize,
     pub style_strike_id: usize,
     pub style_selector_value: usize,
+    pub highlight_id: usize,
+    pub bindings: Rc<Bindings>,
 }
 impl StyleWindow {
     pub fn new(args: StyleWindowArgs) -> Self {
@@ -55,6 +62,8 @@ impl StyleWindow {
             style_transparent_id: args.style_transparent_id,
             style_strike_id: args.style_strike_id,
             style_selector_value: args.style_selector_value,
+            highlight_id: args.highlight_id,
+            bindings: args.bindings,
         }
     }
 
@@ -515,3 +524,72 @@ impl StyleWindow {
         self.activate_style_on_glyph_matrix();
     }
 }
+
+impl StyleWindow {
+    /// Take style from given glyph, showing which styles are enabled.
+    pub fn select_style_of(&mut self, glyph: Glyph) {
+        let styles = [
+            (self.style_bright_id, glyph.is_bright()),
+            (self.style_dim_id, glyph.is_dim()),
+            (self.style_italic_id, glyph.is_italic()),
+            (self.style_underline_id, glyph.is_underline()),
+            (self.style_blink_id, glyph.is_blink()),
+            (self.style_blinkfast_id, glyph.is_blink_fast()),
+            (self.style_reverse_id, glyph.is_reverse()),
+            (self.style_transparent_id, glyph.is_transparent()),
+            (self.style_strike_id, glyph.is_strike()),
+        ];
+        for (id, enabled) in styles {
+            if self
+                .sender
+                .send(Message::SetGraphic(id, enabled as usize, false))
+                .is_err()
+            {
+                eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send SetGraphic message")
+            };
+        }
+        self.style_glyph.set_bright(glyph.is_bright());
+        self.style_glyph.set_dim(glyph.is_dim());
+        self.style_glyph.set_italic(glyph.is_italic());
+        self.style_glyph.set_underline(glyph.is_underline());
+        self.style_glyph.set_blink(glyph.is_blink());
+        self.style_glyph.set_blinkfast(glyph.is_blink_fast());
+        self.style_glyph.set_reverse(glyph.is_reverse());
+        self.style_glyph.set_transparent(glyph.is_transparent());
+        self.style_glyph.set_strike(glyph.is_strike());
+        self.activate_style_on_glyph_matrix();
+    }
+
+    /// Handle a key bound to this window, regardless of focus.
+    pub fn handle_binding(&mut self, key: &Key) -> bool {
+        let bindings = self.bindings.clone();
+        match key {
+            k if bindings.style_up.contains(k) => self.move_selector_up(),
+            k if bindings.style_down.contains(k) => self.move_selector_down(),
+            k if bindings.style_enable.contains(k) => self.enable_selected_style(),
+            k if bindings.style_disable.contains(k) => self.disable_selected_style(),
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl Focusable for StyleWindow {
+    fn handle_key(&mut self, _mgr: &mut Manager, key: &Key) -> bool {
+        if self.handle_binding(key) {
+            return true;
+        }
+        match self.bindings.navigation(key) {
+            Some(Move::Up) => self.move_selector_up(),
+            Some(Move::Down) => self.move_selector_down(),
+            Some(Move::Right) => self.enable_selected_style(),
+            Some(Move::Left) => self.disable_selected_style(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn set_focused(&mut self, mgr: &mut Manager, focused: bool) {
+        mgr.set_invisible(self.highlight_id, !focused);
+    }
+}
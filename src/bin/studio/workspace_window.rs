@@ -0,0 +1,370 @@
+use crate::arguments::Bindings;
+use crate::colors_window::ColorsWindow;
+use crate::glyphs_window::GlyphsWindow;
+use crate::style_window::StyleWindow;
+use animaterm::prelude::{Focusable, Glyph, Key, Manager};
+use std::fs::{rename, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Ids other windows were registered with, Workspace is their parent.
+#[derive(Default)]
+pub struct WindowIds {
+    pub colors: usize,
+    pub backgrounds: usize,
+    pub glyphs: usize,
+    pub style: usize,
+}
+
+pub struct WorkspaceWindow {
+    pub windows: WindowIds,
+    workspace_id: usize,
+    highlight_id: usize,
+    cols: usize,
+    rows: usize,
+    // cursor position, starting from 1
+    c: usize,
+    r: usize,
+    under_cursor: Glyph,
+    cursor_glyph: Glyph,
+    action_counter: usize,
+    counter_initialized: bool,
+    bindings: Rc<Bindings>,
+}
+
+pub struct WorkspaceWindowArgs {
+    pub workspace_id: usize,
+    pub highlight_id: usize,
+    pub cols: usize,
+    pub rows: usize,
+    pub cursor_glyph: Glyph,
+    pub bindings: Rc<Bindings>,
+}
+
+impl WorkspaceWindow {
+    pub fn new(args: WorkspaceWindowArgs) -> Self {
+        WorkspaceWindow {
+            windows: WindowIds::default(),
+            workspace_id: args.workspace_id,
+            highlight_id: args.highlight_id,
+            cols: args.cols,
+            rows: args.rows,
+            c: 1,
+            r: 1,
+            under_cursor: Glyph::default(),
+            cursor_glyph: args.cursor_glyph,
+            action_counter: 1,
+            counter_initialized: false,
+            bindings: args.bindings,
+        }
+    }
+
+    /// Remember glyph under cursor and cover it with cursor.
+    pub fn show_cursor(&mut self, mgr: &mut Manager) {
+        if let Ok(glyph) = mgr.get_glyph(self.workspace_id, self.c, self.r).wait() {
+            self.under_cursor = glyph;
+        }
+        mgr.set_glyph(self.workspace_id, self.cursor_glyph, self.c, self.r);
+    }
+
+    /// Put glyph covered by cursor back in place.
+    fn hide_cursor(&self, mgr: &mut Manager) {
+        mgr.set_glyph(self.workspace_id, self.under_cursor, self.c, self.r);
+    }
+
+    fn move_cursor(&mut self, mgr: &mut Manager, c: usize, r: usize) {
+        self.hide_cursor(mgr);
+        self.c = c;
+        self.r = r;
+        self.show_cursor(mgr);
+    }
+
+    pub fn move_left(&mut self, mgr: &mut Manager) {
+        let (c, r) = self.before();
+        self.move_cursor(mgr, c, r);
+    }
+
+    pub fn move_right(&mut self, mgr: &mut Manager) {
+        let (c, r) = self.after();
+        self.move_cursor(mgr, c, r);
+    }
+
+    pub fn move_up(&mut self, mgr: &mut Manager) {
+        let r = if self.r > 1 { self.r - 1 } else { self.rows };
+        self.move_cursor(mgr, self.c, r);
+    }
+
+    pub fn move_down(&mut self, mgr: &mut Manager) {
+        let r = if self.r < self.rows { self.r + 1 } else { 1 };
+        self.move_cursor(mgr, self.c, r);
+    }
+
+    /// Position preceding cursor, wrapping to previous line and to the bottom.
+    fn before(&self) -> (usize, usize) {
+        if self.c > 1 {
+            (self.c - 1, self.r)
+        } else if self.r > 1 {
+            (self.cols, self.r - 1)
+        } else {
+            (self.cols, self.rows)
+        }
+    }
+
+    /// Position following cursor, wrapping to next line and to the top.
+    fn after(&self) -> (usize, usize) {
+        if self.c < self.cols {
+            (self.c + 1, self.r)
+        } else if self.r < self.rows {
+            (1, self.r + 1)
+        } else {
+            (1, 1)
+        }
+    }
+
+    /// Replace glyphs starting under cursor, as many as action counter says,
+    /// leaving cursor after the last one.
+    fn put_glyphs<F: Fn(Glyph) -> Glyph>(&mut self, mgr: &mut Manager, change: F) {
+        for _ in 0..self.action_counter {
+            mgr.set_glyph(self.workspace_id, change(self.under_cursor), self.c, self.r);
+            (self.c, self.r) = self.after();
+            if let Ok(glyph) = mgr.get_glyph(self.workspace_id, self.c, self.r).wait() {
+                self.under_cursor = glyph;
+            }
+        }
+        mgr.set_glyph(self.workspace_id, self.cursor_glyph, self.c, self.r);
+        self.reset_counter();
+    }
+
+    /// Erase glyphs going back from cursor, as many as action counter says.
+    pub fn erase(&mut self, mgr: &mut Manager) {
+        for _ in 0..self.action_counter {
+            mgr.set_glyph(self.workspace_id, Glyph::default(), self.c, self.r);
+            (self.c, self.r) = self.before();
+        }
+        self.show_cursor(mgr);
+        self.reset_counter();
+    }
+
+    fn reset_counter(&mut self) {
+        self.action_counter = 1;
+        self.counter_initialized = false;
+    }
+
+    /// Number keys make next action repeat given number of times.
+    fn add_digit(&mut self, digit: usize) {
+        if self.counter_initialized {
+            self.action_counter = self.action_counter * 10 + digit;
+        } else if digit > 0 {
+            self.action_counter = digit;
+            self.counter_initialized = true;
+        }
+    }
+
+    fn selected_glyph(&self, mgr: &mut Manager) -> Option<Glyph> {
+        mgr.update_widget(self.windows.glyphs, |window: &mut GlyphsWindow, mgr| {
+            window.selected(mgr)
+        })
+        .flatten()
+    }
+
+    /// Write Workspace's frame to a timestamped file.
+    pub fn print_graphic(&self, mgr: &mut Manager) {
+        self.hide_cursor(mgr);
+        let printed = mgr.print_graphic(self.workspace_id, true);
+        mgr.set_glyph(self.workspace_id, self.cursor_glyph, self.c, self.r);
+        if let Ok(lines) = printed.wait() {
+            write_lines(&mut create_timestamped("print_graphic"), &lines);
+        }
+    }
+
+    /// Write entire screen to a timestamped file.
+    pub fn print_screen(&self, mgr: &mut Manager) {
+        self.hide_cursor(mgr);
+        let printed = mgr.print_screen();
+        mgr.set_glyph(self.workspace_id, self.cursor_glyph, self.c, self.r);
+        if let Ok(lines) = printed.wait() {
+            write_lines(&mut create_timestamped("print_screen"), &lines);
+        }
+    }
+
+    /// Write Workspace's frame to given file, existing one is renamed.
+    pub fn save(&self, mgr: &mut Manager, output_file: &str) {
+        self.hide_cursor(mgr);
+        let printed = mgr.print_graphic(self.workspace_id, true);
+        mgr.set_glyph(self.workspace_id, self.cursor_glyph, self.c, self.r);
+        let Ok(lines) = printed.wait() else {
+            return;
+        };
+        let old_path = Path::new(output_file);
+        if Path::exists(old_path) {
+            let base = format!("{}_", output_file);
+            for i in 0..usize::MAX {
+                let old_file = format!("{}{}", base, i);
+                let new_path = Path::new(&old_file);
+                if !Path::exists(new_path) {
+                    if rename(old_path, new_path).is_err() {
+                        eprintln!(
+                            "Unable to rename existing file {}, removing it.",
+                            output_file
+                        );
+                    }
+                    break;
+                }
+            }
+        }
+        let mut f = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(output_file)
+            .expect("Unable to create file");
+        write_lines(&mut f, &lines);
+    }
+
+    /// Handle a key bound to Workspace, or to an action working on it.
+    pub fn handle_binding(&mut self, mgr: &mut Manager, key: &Key) -> bool {
+        let bindings = self.bindings.clone();
+        match key {
+            k if bindings.workspace_left.contains(k) => self.move_left(mgr),
+            k if bindings.workspace_right.contains(k) => self.move_right(mgr),
+            k if bindings.workspace_up.contains(k) => self.move_up(mgr),
+            k if bindings.workspace_down.contains(k) => self.move_down(mgr),
+            k if bindings.workspace_line_start.contains(k) => self.move_cursor(mgr, 1, self.r),
+            k if bindings.workspace_line_end.contains(k) => {
+                self.move_cursor(mgr, self.cols, self.r)
+            }
+            k if bindings.glyphs_select.contains(k) => {
+                let picked = mgr
+                    .update_widget(self.windows.glyphs, |window: &mut GlyphsWindow, mgr| {
+                        window.pick(mgr)
+                    })
+                    .flatten();
+                if let Some(glyph) = picked {
+                    self.put_glyphs(mgr, |_| glyph);
+                }
+            }
+            k if bindings.workspace_set_color.contains(k) => {
+                if let Some(glyph) = self.selected_glyph(mgr) {
+                    self.put_glyphs(mgr, |mut under| {
+                        under.set_color(glyph.color());
+                        under
+                    });
+                }
+            }
+            k if bindings.workspace_set_background.contains(k) => {
+                if let Some(glyph) = self.selected_glyph(mgr) {
+                    self.put_glyphs(mgr, |mut under| {
+                        under.set_background(glyph.background());
+                        under
+                    });
+                }
+            }
+            k if bindings.workspace_set_glyph.contains(k) => {
+                if let Some(glyph) = self.selected_glyph(mgr) {
+                    self.put_glyphs(mgr, |mut under| {
+                        under.set_char(glyph.character);
+                        under
+                    });
+                }
+            }
+            k if bindings.workspace_set_style.contains(k) => {
+                if let Some(style) = mgr.widget::<StyleWindow>(self.windows.style) {
+                    let mut new_glyph = style.style_glyph;
+                    new_glyph.set_char(self.under_cursor.character);
+                    new_glyph.set_color(self.under_cursor.color());
+                    new_glyph.set_background(self.under_cursor.background());
+                    self.put_glyphs(mgr, |_| new_glyph);
+                }
+            }
+            k if bindings.workspace_select_color.contains(k) => {
+                let color = self.under_cursor.color();
+                mgr.update_widget(self.windows.colors, |window: &mut ColorsWindow, _| {
+                    window.select_color(color, false)
+                });
+            }
+            k if bindings.workspace_select_background.contains(k) => {
+                let background = self.under_cursor.background();
+                mgr.update_widget(self.windows.backgrounds, |window: &mut ColorsWindow, _| {
+                    window.select_color(background, true)
+                });
+            }
+            k if bindings.workspace_select_glyph.contains(k) => {
+                let character = self.under_cursor.character;
+                mgr.update_widget(self.windows.glyphs, |window: &mut GlyphsWindow, mgr| {
+                    window.select_char(mgr, character)
+                });
+            }
+            k if bindings.workspace_select_style.contains(k) => {
+                let glyph = self.under_cursor;
+                mgr.update_widget(self.windows.style, |window: &mut StyleWindow, _| {
+                    window.select_style_of(glyph)
+                });
+            }
+            k if bindings.workspace_erase.contains(k) => self.erase(mgr),
+            k if bindings.print_graphic.contains(k) => self.print_graphic(mgr),
+            k if bindings.print_screen.contains(k) => self.print_screen(mgr),
+            k if bindings.action_counter_reset.contains(k) => self.reset_counter(),
+            Key::Zero => self.add_digit(0),
+            Key::One => self.add_digit(1),
+            Key::Two => self.add_digit(2),
+            Key::Three => self.add_digit(3),
+            Key::Four => self.add_digit(4),
+            Key::Five => self.add_digit(5),
+            Key::Six => self.add_digit(6),
+            Key::Seven => self.add_digit(7),
+            Key::Eight => self.add_digit(8),
+            Key::Nine => self.add_digit(9),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Workspace is parent of other windows, so keys they did not consume end up here.
+    /// Keys bound to a window that is not focused are passed on to it.
+    fn pass_on(&self, mgr: &mut Manager, key: &Key) -> bool {
+        let windows = &self.windows;
+        mgr.update_widget(windows.colors, |window: &mut ColorsWindow, _| {
+            window.handle_binding(key)
+        }) == Some(true)
+            || mgr.update_widget(windows.backgrounds, |window: &mut ColorsWindow, _| {
+                window.handle_binding(key)
+            }) == Some(true)
+            || mgr.update_widget(windows.glyphs, |window: &mut GlyphsWindow, mgr| {
+                window.handle_binding(mgr, key)
+            }) == Some(true)
+            || mgr.update_widget(windows.style, |window: &mut StyleWindow, _| {
+                window.handle_binding(key)
+            }) == Some(true)
+    }
+}
+
+impl Focusable for WorkspaceWindow {
+    fn handle_key(&mut self, mgr: &mut Manager, key: &Key) -> bool {
+        self.handle_binding(mgr, key) || self.pass_on(mgr, key)
+    }
+
+    fn set_focused(&mut self, mgr: &mut Manager, focused: bool) {
+        mgr.set_invisible(self.highlight_id, !focused);
+    }
+}
+
+fn create_timestamped(prefix: &str) -> File {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    OpenOptions::new()
+        .create(true)
+        .truncate(true)
+        .write(true)
+        .open(format!("{}_{}.txf", prefix, secs))
+        .expect("Unable to create file")
+}
+
+fn write_lines(f: &mut File, lines: &[String]) {
+    for line in lines {
+        f.write_all(line.as_bytes()).expect("Unable to write data");
+        f.write_all(b"\n").expect("Unable to write data");
+    }
+}
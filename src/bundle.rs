@@ -0,0 +1,284 @@
+//! Single file format holding a whole graphic: it's metadata, every frame
+//! from library and all animations. It looks as follows:
+//!
+//! ```text
+//! animaterm-bundle 1
+//! size 4 2
+//! layer 1
+//! invisible
+//! frame first
+//! ABCD
+//! EFGH
+//! frame second
+//! abcd
+//! efgh
+//! animation loop run first:500 second:250
+//! current second
+//! ```
+//!
+//! `size` (cols rows) is required before any frame, every `frame` line is followed
+//! by exactly rows lines of text with optional SGR sequences.
+//! Lines starting with # outside of frames are comments.
+use super::animation::Animation;
+use super::ansi::{frame_to_text, parse_line};
+use super::error::AnimError;
+use super::style;
+use super::time::Timestamp;
+use super::{Glyph, Graphic};
+use std::collections::HashMap;
+
+/// Header every bundle starts with.
+pub const MAGIC: &str = "animaterm-bundle";
+/// Newest version of a bundle format that can be read.
+pub const VERSION: u32 = 1;
+
+/// Serialize a graphic into a bundle.
+pub fn write(graphic: &Graphic) -> String {
+    let ids = graphic.frame_ids();
+    let names = frame_names(graphic, &ids);
+    let mut text = format!(
+        "{} {}\nsize {} {}\n",
+        MAGIC, VERSION, graphic.cols, graphic.rows
+    );
+    if let Some(layer) = graphic.layer {
+        text.push_str(&format!("layer {}\n", layer));
+    }
+    if graphic.invisible {
+        text.push_str("invisible\n");
+    }
+    for id in &ids {
+        if let Some(frame) = graphic.library_frame(*id) {
+            text.push_str(&format!("frame {}\n", names[id]));
+            text.push_str(&frame_to_text(graphic.cols, frame));
+        }
+    }
+    text.push_str(&animations_text(graphic, &names));
+    if let Some(name) = names.get(&graphic.current_frame) {
+        text.push_str(&format!("current {}\n", name));
+    }
+    text
+}
+
+/// Define every animation of a graphic on a separate line,
+/// the same way it is done in a .txg file.
+pub fn animations_text(graphic: &Graphic, names: &HashMap<usize, String>) -> String {
+    let mut text = String::new();
+    let mut anim_ids: Vec<&usize> = graphic.animations.keys().collect();
+    anim_ids.sort_unstable();
+    for anim_id in anim_ids {
+        let animation = &graphic.animations[anim_id];
+        text.push_str("animation");
+        if animation.is_looping() {
+            text.push_str(" loop");
+        }
+        if graphic.running_anim == Some(*anim_id) {
+            text.push_str(" run");
+        }
+        for (frame_id, time) in animation.ordering() {
+            if let Some(name) = names.get(frame_id) {
+                text.push_str(&format!(" {}:{}", name, time.as_millis()));
+            }
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Pick a unique name for every frame, falling back to one derived from frame's id
+/// when a frame has no name, or it's name can not be written to a file.
+/// Names given by user are kept, fallbacks get a suffix when already taken.
+pub fn frame_names(graphic: &Graphic, ids: &[usize]) -> HashMap<usize, String> {
+    let mut names = HashMap::with_capacity(ids.len());
+    let mut taken = Vec::with_capacity(ids.len());
+    for id in ids {
+        if let Some(name) = graphic.frame_name(*id) {
+            if is_valid_name(name) && !taken.contains(&name.to_owned()) {
+                taken.push(name.to_owned());
+                names.insert(*id, name.to_owned());
+            }
+        }
+    }
+    for id in ids {
+        if names.contains_key(id) {
+            continue;
+        }
+        let mut name = format!("frame_{}", id);
+        let mut suffix = 1;
+        while taken.contains(&name) {
+            name = format!("frame_{}_{}", id, suffix);
+            suffix += 1;
+        }
+        taken.push(name.clone());
+        names.insert(*id, name);
+    }
+    names
+}
+
+fn is_valid_name(name: &str) -> bool {
+    // Names are also used as file names when a graphic is written to a directory
+    !name.is_empty()
+        && !name.contains(|c: char| c.is_whitespace() || [':', '#', '/', '\\'].contains(&c))
+}
+
+/// Build a graphic out of a bundle.
+pub fn read(text: &str) -> Result<Graphic, AnimError> {
+    let error = |line: usize, reason: String| AnimError::UnableToParseGraphic(line, reason);
+    let mut lines = text.lines().enumerate().map(|(i, line)| (i + 1, line));
+
+    match lines.next() {
+        Some((_, header)) => {
+            let tokens: Vec<&str> = header.split_whitespace().collect();
+            if tokens.len() != 2 || tokens[0] != MAGIC {
+                return Err(error(1, format!("expected '{} <version>'", MAGIC)));
+            }
+            match tokens[1].parse::<u32>() {
+                Ok(version) if version > 0 && version <= VERSION => {}
+                Ok(version) => {
+                    return Err(error(1, format!("unsupported version {}", version)));
+                }
+                Err(_) => return Err(error(1, format!("invalid version {:?}", tokens[1]))),
+            }
+        }
+        None => return Err(error(1, "bundle is empty".to_string())),
+    }
+
+    let mut size: Option<(usize, usize)> = None;
+    let mut layer = None;
+    let mut invisible = false;
+    let mut current_frame = None;
+    let mut running_anim = None;
+    let mut library: HashMap<usize, Vec<Glyph>> = HashMap::new();
+    let mut names_mapping: HashMap<String, usize> = HashMap::new();
+    let mut animations: HashMap<usize, Animation> = HashMap::new();
+
+    while let Some((line_no, line)) = lines.next() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.is_empty() || tokens[0].starts_with('#') {
+            continue;
+        }
+        let number = |token: Option<&&str>| -> Result<usize, AnimError> {
+            token
+                .and_then(|t| t.parse::<usize>().ok())
+                .ok_or_else(|| error(line_no, format!("expected a number in {:?}", line)))
+        };
+        match tokens[0] {
+            "size" => {
+                if size.is_some() {
+                    return Err(error(line_no, "size defined twice".to_string()));
+                }
+                let cols = number(tokens.get(1))?;
+                let rows = number(tokens.get(2))?;
+                if cols == 0 || rows == 0 {
+                    return Err(error(line_no, "size can not be zero".to_string()));
+                }
+                size = Some((cols, rows));
+            }
+            "layer" => layer = Some(number(tokens.get(1))?),
+            "invisible" => invisible = true,
+            "frame" => {
+                let (cols, rows) =
+                    size.ok_or_else(|| error(line_no, "frame defined before size".to_string()))?;
+                let name = match tokens.get(1) {
+                    Some(name) if is_valid_name(name) => name.to_string(),
+                    _ => return Err(error(line_no, "frame has no valid name".to_string())),
+                };
+                if names_mapping.contains_key(&name) {
+                    return Err(error(line_no, format!("frame {} defined twice", name)));
+                }
+                let mut frame = Vec::with_capacity(cols * rows);
+                for _i in 0..rows {
+                    let (row_no, row) = lines.next().ok_or_else(|| {
+                        error(
+                            line_no,
+                            format!("frame {} has less than {} rows", name, rows),
+                        )
+                    })?;
+                    let mut look = style::DEFAULT;
+                    let mut glyphs = Vec::with_capacity(cols);
+                    parse_line(row, row_no, &mut look, &mut glyphs)?;
+                    if glyphs.len() > cols {
+                        return Err(AnimError::UnableToParseFrame(
+                            row_no,
+                            cols + 1,
+                            format!("row is wider than {} columns", cols),
+                        ));
+                    }
+                    glyphs.resize(cols, Glyph::transparent());
+                    frame.append(&mut glyphs);
+                }
+                let id = library.len();
+                names_mapping.insert(name, id);
+                library.insert(id, frame);
+            }
+            "animation" => {
+                let mut looping = false;
+                let mut running = false;
+                let mut ordering = Vec::with_capacity(tokens.len());
+                for token in &tokens[1..] {
+                    match *token {
+                        "loop" => looping = true,
+                        "run" => running = true,
+                        t if t.starts_with('#') => break,
+                        t => {
+                            let (name, time) = t.split_once(':').ok_or_else(|| {
+                                error(line_no, format!("{} should be frame_name:time_ms", t))
+                            })?;
+                            let frame_id = names_mapping
+                                .get(name)
+                                .ok_or_else(|| error(line_no, format!("unknown frame {}", name)))?;
+                            let msec = time.parse::<u64>().map_err(|_| {
+                                error(line_no, format!("invalid time {:?} in {}", time, t))
+                            })?;
+                            ordering.push((
+                                *frame_id,
+                                Timestamp::new(msec / 1000, (msec % 1000) as u32),
+                            ));
+                        }
+                    }
+                }
+                if ordering.is_empty() {
+                    return Err(error(line_no, "animation has no frames".to_string()));
+                }
+                let anim_id = animations.len();
+                if running && running_anim.is_none() {
+                    running_anim = Some(anim_id);
+                    current_frame = ordering.last().map(|(id, _)| *id);
+                } else {
+                    running = false;
+                }
+                animations.insert(
+                    anim_id,
+                    Animation::new(running, looping, ordering, Timestamp::now()),
+                );
+            }
+            "current" => {
+                let name = tokens.get(1).copied().unwrap_or_default();
+                current_frame = Some(
+                    *names_mapping
+                        .get(name)
+                        .ok_or_else(|| error(line_no, format!("unknown frame {:?}", name)))?,
+                );
+            }
+            other => return Err(error(line_no, format!("unknown entry {:?}", other))),
+        }
+    }
+
+    let (cols, rows) = size.ok_or_else(|| error(1, "bundle has no size".to_string()))?;
+    if library.is_empty() {
+        return Err(error(1, "bundle has no frames".to_string()));
+    }
+    let mut graphic = Graphic::new(
+        cols,
+        rows,
+        current_frame.unwrap_or(0),
+        library,
+        Some(animations),
+    );
+    graphic.layer = layer;
+    graphic.invisible = invisible;
+    graphic.running_anim = running_anim;
+    for (name, id) in names_mapping {
+        graphic.set_frame_name(id, &name);
+    }
+    Ok(graphic)
+}
@@ -1,3 +1,5 @@
+use std::fmt::Write;
+
 #[derive(PartialEq, Clone, Copy, Debug)]
 /// A helper used for better code readability.
 pub enum ColorName {
@@ -107,4 +109,54 @@ impl Color {
     pub fn new_truecolor(red: u8, green: u8, blue: u8) -> Color {
         Color::Truecolor(red, green, blue)
     }
+
+    /// Pack a color into lower 26 bits of a word, two highest of them store color type.
+    pub(crate) fn pack(&self) -> u32 {
+        match *self {
+            Color::Basic(name) => name as u32,
+            Color::EightBit(value) => 1 << 24 | value as u32,
+            Color::Grayscale(value) => 2 << 24 | value as u32,
+            Color::Truecolor(red, green, blue) => {
+                3 << 24 | (red as u32) << 16 | (green as u32) << 8 | blue as u32
+            }
+        }
+    }
+
+    /// Restore a color packed with pack.
+    pub(crate) fn unpack(packed: u32) -> Color {
+        let value = (packed & 0xff) as u8;
+        match packed >> 24 {
+            0 => Color::Basic(match value {
+                0 => ColorName::Black,
+                1 => ColorName::Red,
+                2 => ColorName::Green,
+                3 => ColorName::Yellow,
+                4 => ColorName::Blue,
+                5 => ColorName::Magenta,
+                6 => ColorName::Cyan,
+                _ => ColorName::White,
+            }),
+            1 => Color::EightBit(value),
+            2 => Color::Grayscale(value),
+            _ => Color::Truecolor((packed >> 16) as u8, (packed >> 8) as u8, value),
+        }
+    }
+
+    /// Append SGR parameters selecting this color as foreground or background.
+    pub(crate) fn push_sgr(&self, modifier: &mut String, background: bool) {
+        let (basic, extended) = if background { ('4', "48") } else { ('3', "38") };
+        match *self {
+            Color::Basic(name) => {
+                modifier.push(basic);
+                modifier.push(char::from(b'0' + name as u8));
+            }
+            // Writing into a String can not fail
+            Color::EightBit(value) | Color::Grayscale(value) => {
+                let _ = write!(modifier, "{};5;{}", extended, value);
+            }
+            Color::Truecolor(red, green, blue) => {
+                let _ = write!(modifier, "{};2;{};{};{}", extended, red, green, blue);
+            }
+        }
+    }
 }
@@ -0,0 +1,30 @@
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// Shape of terminal's cursor, set with DECSCUSR sequence.
+/// Not every terminal supports every shape.
+pub enum CursorShape {
+    /// Whatever user's terminal is configured to use.
+    #[default]
+    Default,
+    BlinkingBlock,
+    SteadyBlock,
+    BlinkingUnderline,
+    SteadyUnderline,
+    BlinkingBar,
+    SteadyBar,
+}
+
+impl CursorShape {
+    /// Escape sequence switching terminal's cursor to this shape.
+    pub fn sequence(&self) -> String {
+        let code = match self {
+            CursorShape::Default => 0,
+            CursorShape::BlinkingBlock => 1,
+            CursorShape::SteadyBlock => 2,
+            CursorShape::BlinkingUnderline => 3,
+            CursorShape::SteadyUnderline => 4,
+            CursorShape::BlinkingBar => 5,
+            CursorShape::SteadyBar => 6,
+        };
+        format!("\x1b[{} q", code)
+    }
+}
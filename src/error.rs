@@ -1,12 +1,153 @@
+use super::key::Key;
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
 #[derive(Debug)]
 /// Defines errors returned when library is not used as expected.
 pub enum AnimError {
     FailAddingAnimation(usize),
     FailGettingGlyph(usize),
     FailAddingFrame(usize),
-    ResultReceiverNotSet,
-    FrameNotFound,
-    UnableToOpenFile,
-    UnableToReadFile,
-    UnableToBuildGraphicFromFile,
+    /// There is no graphic with given id on current display.
+    UnknownGraphic(usize),
+    /// Graphic has no frame with given id.
+    FrameNotFound(usize),
+    /// Every graphic id is taken, some graphics need to be deleted first.
+    TooManyGraphics,
+    UnableToOpenFile(PathBuf),
+    UnableToReadFile(PathBuf),
+    /// Reading from a reader failed for given reason.
+    UnableToRead(String),
+    /// Frame definition is malformed at given line and column (both starting from 1).
+    UnableToParseFrame(usize, usize, String),
+    /// Graphic definition is malformed at given line (starting from 1).
+    UnableToParseGraphic(usize, String),
+    /// Frame with given name, defined at given line (starting from 1), could not be read.
+    UnableToReadFrame(usize, String, Box<AnimError>),
+    /// Given error was found while parsing file at given path.
+    InFile(PathBuf, Box<AnimError>),
+    UnableToWriteFile(PathBuf),
+    /// Request with given id got no response, or not the one expected.
+    NoResponse(usize),
+    /// Screen thread is gone, so message of given kind was not delivered.
+    MessageNotSent(&'static str),
+    /// Thread of given name has panicked.
+    ThreadFailed(&'static str),
+    /// Keys were read from a Manager not capturing keyboard.
+    KeyboardNotCaptured,
+    /// Keyboard input has ended, no more keys will come.
+    KeyboardClosed,
+    /// Macro recording was started with given key instead of record key.
+    UnexpectedRecordKey(Key),
+    /// Terminal size could not be determined for given reason, defaults were used.
+    UnknownTerminalSize(String),
+}
+
+impl AnimError {
+    /// Attach path of a parsed file to a parse error, other errors are left as they are.
+    pub(crate) fn in_file(self, path: &Path) -> Self {
+        match self {
+            AnimError::UnableToParseFrame(..)
+            | AnimError::UnableToParseGraphic(..)
+            | AnimError::UnableToReadFrame(..) => {
+                AnimError::InFile(path.to_path_buf(), Box::new(self))
+            }
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for AnimError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnimError::FailAddingAnimation(gid) => {
+                write!(f, "unable to add animation to graphic {}", gid)
+            }
+            AnimError::FailGettingGlyph(gid) => write!(f, "unable to get glyph of graphic {}", gid),
+            AnimError::FailAddingFrame(id) => write!(f, "unable to add frame {}", id),
+            AnimError::UnknownGraphic(gid) => write!(f, "there is no graphic {}", gid),
+            AnimError::FrameNotFound(fid) => write!(f, "there is no frame {}", fid),
+            AnimError::TooManyGraphics => write!(f, "all graphic ids are taken"),
+            AnimError::UnableToOpenFile(path) => write!(f, "unable to open {}", path.display()),
+            AnimError::UnableToReadFile(path) => write!(f, "unable to read {}", path.display()),
+            AnimError::UnableToRead(reason) => write!(f, "unable to read: {}", reason),
+            AnimError::UnableToParseFrame(line, col, reason) => {
+                write!(f, "frame line {} column {}: {}", line, col, reason)
+            }
+            AnimError::UnableToParseGraphic(line, reason) => {
+                write!(f, "graphic line {}: {}", line, reason)
+            }
+            AnimError::UnableToReadFrame(line, name, error) => {
+                write!(
+                    f,
+                    "graphic line {}: unable to read frame {}: {}",
+                    line, name, error
+                )
+            }
+            AnimError::InFile(path, error) => write!(f, "{}: {}", path.display(), error),
+            AnimError::UnableToWriteFile(path) => write!(f, "unable to write {}", path.display()),
+            AnimError::NoResponse(id) => write!(f, "no response to request {}", id),
+            AnimError::MessageNotSent(kind) => write!(f, "unable to send {} message", kind),
+            AnimError::ThreadFailed(name) => write!(f, "{} thread has panicked", name),
+            AnimError::KeyboardNotCaptured => write!(f, "keyboard is not captured"),
+            AnimError::KeyboardClosed => write!(f, "keyboard input has ended"),
+            AnimError::UnexpectedRecordKey(key) => {
+                write!(f, "unexpected key upon recording start: {}", key)
+            }
+            AnimError::UnknownTerminalSize(reason) => {
+                write!(
+                    f,
+                    "unable to determine terminal size, using defaults: {}",
+                    reason
+                )
+            }
+        }
+    }
+}
+
+impl Error for AnimError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AnimError::InFile(_path, error) => Some(error.as_ref()),
+            AnimError::UnableToReadFrame(_line, _name, error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+type ErrorHook = Arc<dyn Fn(&AnimError) + Send + Sync>;
+
+static ERROR_HOOK: RwLock<Option<ErrorHook>> = RwLock::new(None);
+
+/// Receive errors that can not be returned to caller, like a failure to send
+/// a message to Screen's thread. Without a hook such errors are dropped,
+/// since printing them would scribble over the screen.
+pub fn set_error_hook<F>(hook: F)
+where
+    F: Fn(&AnimError) + Send + Sync + 'static,
+{
+    *ERROR_HOOK
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Arc::new(hook));
+}
+
+/// Stop passing errors to previously set hook.
+pub fn remove_error_hook() {
+    *ERROR_HOOK
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+}
+
+/// Pass an error to error hook, if one is set.
+/// Hook is called with no lock held, so it may replace or remove itself.
+pub(crate) fn report(error: AnimError) {
+    let hook = ERROR_HOOK
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    if let Some(hook) = hook {
+        hook(&error);
+    }
 }
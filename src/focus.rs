@@ -0,0 +1,155 @@
+use super::key::Key;
+use super::manager::Manager;
+use std::any::Any;
+
+/// A widget that can be registered with Manager to receive keys when focused.
+/// Keys go to focused widget first, and when it does not consume a key
+/// it bubbles up to widget's parent, then parent's parent and so on.
+/// Keys nobody consumed are returned to the caller of `Manager::route_key`,
+/// except for Tab and Shift-Tab which move focus between widgets.
+pub trait Focusable: Any {
+    /// Handle a key, returning true when it was consumed.
+    fn handle_key(&mut self, mgr: &mut Manager, key: &Key) -> bool;
+
+    /// Called when widget gains or loses focus, so it can update it's highlight.
+    fn set_focused(&mut self, mgr: &mut Manager, focused: bool);
+
+    /// Can Tab and Shift-Tab move focus to this widget.
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+}
+
+/// Result of passing a key through registered widgets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Routed {
+    /// Key was consumed by widget with given id.
+    Handled(usize),
+    /// Focus has moved to widget with given id.
+    FocusMoved(usize),
+    /// No widget consumed the key.
+    Unhandled(Key),
+}
+
+struct Entry {
+    id: usize,
+    parent: Option<usize>,
+    // Widget is taken out while it is handling a key,
+    // so that it can be given Manager it belongs to.
+    widget: Option<Box<dyn Focusable>>,
+}
+
+/// Widgets registered with a Manager, in order in which focus cycles through them.
+#[derive(Default)]
+pub struct FocusRing {
+    entries: Vec<Entry>,
+    pub focused: Option<usize>,
+    next_id: usize,
+}
+
+impl FocusRing {
+    pub fn add(&mut self, widget: Box<dyn Focusable>, parent: Option<usize>) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.push(Entry {
+            id,
+            parent,
+            widget: Some(widget),
+        });
+        id
+    }
+
+    /// Remove widget, it's children are adopted by it's parent.
+    pub fn remove(&mut self, id: usize) -> Option<Box<dyn Focusable>> {
+        let index = self.entries.iter().position(|e| e.id == id)?;
+        let entry = self.entries.remove(index);
+        for child in self.entries.iter_mut().filter(|e| e.parent == Some(id)) {
+            child.parent = entry.parent;
+        }
+        if self.focused == Some(id) {
+            self.focused = None;
+        }
+        entry.widget
+    }
+
+    pub fn contains(&self, id: usize) -> bool {
+        self.entries.iter().any(|e| e.id == id)
+    }
+
+    pub fn take(&mut self, id: usize) -> Option<Box<dyn Focusable>> {
+        self.entries
+            .iter_mut()
+            .find(|e| e.id == id)
+            .and_then(|e| e.widget.take())
+    }
+
+    /// Return a taken widget, unless it was unregistered in the meantime.
+    pub fn put(&mut self, id: usize, widget: Box<dyn Focusable>) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.id == id) {
+            entry.widget = Some(widget);
+        }
+    }
+
+    pub fn widget(&self, id: usize) -> Option<&dyn Focusable> {
+        self.entries
+            .iter()
+            .find(|e| e.id == id)
+            .and_then(|e| e.widget.as_deref())
+    }
+
+    pub fn widget_mut(&mut self, id: usize) -> Option<&mut dyn Focusable> {
+        match self.entries.iter_mut().find(|e| e.id == id) {
+            Some(entry) => match entry.widget {
+                Some(ref mut widget) => Some(widget.as_mut()),
+                None => None,
+            },
+            None => None,
+        }
+    }
+
+    /// Widget with given id followed by all of it's ancestors.
+    pub fn chain(&self, id: usize) -> Vec<usize> {
+        let mut chain = vec![id];
+        let mut current = id;
+        while let Some(parent) = self
+            .entries
+            .iter()
+            .find(|e| e.id == current)
+            .and_then(|e| e.parent)
+        {
+            // Guard against a widget registered as it's own ancestor
+            if chain.contains(&parent) {
+                break;
+            }
+            chain.push(parent);
+            current = parent;
+        }
+        chain
+    }
+
+    /// Next widget accepting focus after the focused one, or before it when going back.
+    pub fn next(&self, forward: bool) -> Option<usize> {
+        let len = self.entries.len();
+        if len == 0 {
+            return None;
+        }
+        let start = self
+            .focused
+            .and_then(|id| self.entries.iter().position(|e| e.id == id));
+        (1..=len)
+            .map(|step| match (start, forward) {
+                (Some(start), true) => (start + step) % len,
+                (Some(start), false) => (start + len - step) % len,
+                (None, true) => step - 1,
+                (None, false) => len - step,
+            })
+            .map(|index| &self.entries[index])
+            .find(|e| {
+                e.widget
+                    .as_ref()
+                    .map(|w| w.accepts_focus())
+                    .unwrap_or(false)
+            })
+            .map(|e| e.id)
+    }
+}
@@ -1,71 +1,34 @@
+use super::ansi::parse_frame;
+use super::error::AnimError;
 use super::Glyph;
 use std::fs::File;
 use std::io::{self, Read};
 use std::path::Path;
 
 /// Read a frame from file.
-pub fn from_file<P>(filename: &P) -> Option<(usize, Vec<Glyph>)>
+/// Frame's width is defined by it's longest line, shorter lines are filled with transparent glyphs.
+pub fn from_file<P>(filename: &P) -> Result<(usize, Vec<Glyph>), AnimError>
 where
     P: AsRef<Path>,
 {
-    let mut result = None;
-    if let Ok(file) = File::open(filename) {
-        let mut read_string = String::with_capacity(1024);
-        let mut br = io::BufReader::new(file);
-        if br.read_to_string(&mut read_string).is_ok() {
-            let mut cs = 0;
-            let mut rs = 0;
-            let mut glyph = Glyph::default();
-            let mut frame = Vec::new();
-            for line in read_string.lines() {
-                rs += 1;
-                let mut style_started = false;
-                let mut style_definition = String::new();
-                for char in line.chars() {
-                    match char {
-                        '\x1b' => {
-                            if !style_definition.is_empty() {
-                                glyph.update_from_str(&style_definition);
-                                style_definition.clear();
-                            }
-                            style_started = true;
-                            style_definition.push(char);
-                        }
-                        'm' => {
-                            style_definition.push(char);
-                            if style_started {
-                                style_started = false;
-                            } else {
-                                glyph.update_from_str(&style_definition);
-                                frame.push(glyph);
-                                style_definition.clear();
-                                cs += 1;
-                            }
-                        }
-                        '\n' => {
-                            continue;
-                        }
-                        _ => {
-                            style_definition.push(char);
-                            if !style_started {
-                                glyph.update_from_str(&style_definition);
-                                frame.push(glyph);
-                                style_definition.clear();
-                                cs += 1;
-                            }
-                        }
-                    }
-                }
-            }
-            cs /= rs;
-            if !frame.is_empty() {
-                result = Some((cs, frame));
-            } else {
-                eprintln!("Frame empty!");
-            }
-        } else {
-            eprintln!("Unable to read file!");
-        }
-    }
-    result
+    let path = filename.as_ref();
+    let file = File::open(path).map_err(|_| AnimError::UnableToOpenFile(path.to_path_buf()))?;
+    from_reader(io::BufReader::new(file)).map_err(|e| match e {
+        AnimError::UnableToRead(_) => AnimError::UnableToReadFile(path.to_path_buf()),
+        other => other.in_file(path),
+    })
+}
+
+/// Read a frame from any reader, e.g. an entry of an archive.
+pub fn from_reader<R: Read>(mut reader: R) -> Result<(usize, Vec<Glyph>), AnimError> {
+    let mut read_string = String::with_capacity(1024);
+    reader
+        .read_to_string(&mut read_string)
+        .map_err(|e| AnimError::UnableToRead(e.to_string()))?;
+    parse_frame(&read_string)
+}
+
+/// Read a frame from text, e.g. one embedded with include_str!.
+pub fn from_str(text: &str) -> Result<(usize, Vec<Glyph>), AnimError> {
+    parse_frame(text)
 }
@@ -1,26 +1,15 @@
-use super::color::{Color, ColorName};
-enum ExpectedToken {
-    Any,
-    ColorByte,
-    ColorSpecifier,
-}
-
-#[derive(Copy, Clone, Debug, PartialEq)]
+use super::ansi;
+use super::color::Color;
+use super::error::report;
+use super::style::{self, Appearance, Style};
+use std::fmt;
+#[derive(Copy, Clone, PartialEq, Eq)]
 /// A structure representing a single unicode character on screen together with it's colors and style.
+/// Colors and style are packed into two halves of a single word,
+/// so that a glyph takes 12 bytes and is cheap to compare.
 pub struct Glyph {
     pub character: char,
-    pub color: Color,
-    pub background: Color,
-    pub plain: bool,
-    pub bright: bool,
-    pub dim: bool,
-    pub italic: bool,
-    pub underline: bool,
-    pub blink: bool,
-    pub blink_fast: bool,
-    pub reverse: bool,
-    pub transparent: bool,
-    pub strike: bool,
+    appearance: [u32; 2],
 }
 
 impl Glyph {
@@ -30,6 +19,7 @@ impl Glyph {
         glyph
     }
     /// Create a new glyph.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         character: char,
         color: Color,
@@ -45,20 +35,29 @@ impl Glyph {
         transparent: bool,
         strike: bool,
     ) -> Self {
+        let mut style = Style::NONE;
+        for (flag, enabled) in [
+            (Style::PLAIN, plain),
+            (Style::BRIGHT, bright),
+            (Style::DIM, dim),
+            (Style::ITALIC, italic),
+            (Style::UNDERLINE, underline),
+            (Style::BLINK, blink),
+            (Style::BLINK_FAST, blink_fast),
+            (Style::REVERSE, reverse),
+            (Style::TRANSPARENT, transparent),
+            (Style::STRIKE, strike),
+        ] {
+            style.set(flag, enabled);
+        }
+        Glyph::styled(character, color, background, style)
+    }
+
+    /// Create a new glyph with style defined as a set of flags.
+    pub fn styled(character: char, color: Color, background: Color, style: Style) -> Self {
         Glyph {
             character,
-            color,
-            background,
-            plain,
-            bright,
-            dim,
-            italic,
-            underline,
-            blink,
-            blink_fast,
-            reverse,
-            transparent,
-            strike,
+            appearance: split(Appearance::new(color, background, style).pack()),
         }
     }
 
@@ -66,18 +65,7 @@ impl Glyph {
     pub fn plain() -> Self {
         Glyph {
             character: ' ',
-            color: Color::Basic(ColorName::White),
-            background: Color::Basic(ColorName::Black),
-            plain: true,
-            bright: false,
-            dim: false,
-            italic: false,
-            underline: false,
-            blink: false,
-            blink_fast: false,
-            reverse: false,
-            transparent: false,
-            strike: false,
+            appearance: split(style::PLAIN.pack()),
         }
     }
 
@@ -85,18 +73,7 @@ impl Glyph {
     pub fn transparent() -> Self {
         Glyph {
             character: ' ',
-            color: Color::Basic(ColorName::White),
-            background: Color::Basic(ColorName::Black),
-            plain: false,
-            bright: false,
-            dim: false,
-            italic: false,
-            underline: false,
-            blink: false,
-            blink_fast: false,
-            reverse: false,
-            transparent: true,
-            strike: false,
+            appearance: split(style::TRANSPARENT.pack()),
         }
     }
 
@@ -185,316 +162,22 @@ impl Glyph {
     }
 
     /// Update a glyph with style information provided as &str.
+    /// Style is defined with ANSI escape sequences, last printable character
+    /// found in given text becomes glyph's character.
+    /// An empty definition leaves glyph unchanged, a malformed one is passed to error hook.
     pub fn update_from_str(&mut self, style_definition: &str) {
         if style_definition.is_empty() {
-            eprintln!("can not update empty style");
             return;
         }
-        let mut tokens_started = false;
-        let mut tokens = Vec::with_capacity(16);
-        let mut current_token = String::with_capacity(3);
-        for char in style_definition.chars() {
-            match char {
-                '\x1b' => tokens_started = true,
-                '[' => {
-                    if tokens_started {
-                        continue;
-                    } else {
-                        self.set_char(char);
-                    }
-                }
-                'm' => {
-                    if tokens_started {
-                        tokens_started = false;
-                        if !current_token.is_empty() {
-                            tokens.push(current_token.clone());
-                            current_token.clear();
-                        }
-                    } else {
-                        self.set_char(char);
-                    }
-                }
-                ';' => {
-                    if !current_token.is_empty() {
-                        tokens.push(current_token.clone());
-                        current_token.clear();
-                    }
-                }
-                _ => {
-                    if tokens_started {
-                        current_token.push(char);
-                    } else {
-                        self.set_char(char);
-                    }
-                }
-            }
+        let mut look = self.appearance();
+        let mut glyphs = Vec::with_capacity(1);
+        if let Err(e) = ansi::parse_line(style_definition, 1, &mut look, &mut glyphs) {
+            report(e);
         }
-        let mut color_8bit: u8;
-        let mut color_red: u8 = 0;
-        let mut color_green: u8 = 0;
-        let mut color_blue: u8;
-
-        let mut next_token = ExpectedToken::Any;
-        let mut defining_background = false;
-        let mut defining_truecolor = false;
-        let mut color_bytes_left_to_read: u8 = 0;
-        for token in tokens.iter() {
-            match next_token {
-                ExpectedToken::Any => match &token[..] {
-                    "0" => {
-                        self.set_bright(false);
-                        self.set_transparent(false);
-                        self.set_italic(false);
-                        self.set_underline(false);
-                        self.set_blink(false);
-                        self.set_blinkfast(false);
-                        self.set_reverse(false);
-                        self.set_strike(false);
-                    }
-                    "21" => {
-                        self.set_bright(false);
-                    }
-                    "1" => {
-                        self.set_bright(true);
-                    }
-                    "2" => {
-                        self.set_bright(false);
-                        self.set_dim(true);
-                    }
-                    "22" => {
-                        self.set_dim(false);
-                        self.set_bright(false);
-                    }
-                    "23" => {
-                        self.set_italic(false);
-                    }
-                    "3" => {
-                        self.set_italic(true);
-                    }
-                    "24" => {
-                        self.set_underline(false);
-                    }
-                    "4" => {
-                        self.set_underline(true);
-                    }
-                    "25" => {
-                        self.set_blink(false);
-                        self.set_blinkfast(false);
-                    }
-                    "5" => {
-                        self.set_blink(true);
-                    }
-                    "6" => {
-                        self.set_blinkfast(true);
-                    }
-                    "27" => {
-                        self.set_reverse(false);
-                    }
-                    "7" => {
-                        self.set_reverse(true);
-                    }
-                    "8" => {
-                        self.set_transparent(true);
-                    }
-                    "28" => {
-                        self.set_transparent(false);
-                    }
-                    "29" => {
-                        self.set_strike(false);
-                    }
-                    "9" => {
-                        self.set_strike(true);
-                    }
-                    "30" => {
-                        self.set_color(Color::black());
-                    }
-                    "31" => {
-                        self.set_color(Color::red());
-                    }
-                    "32" => {
-                        self.set_color(Color::green());
-                    }
-                    "33" => {
-                        self.set_color(Color::yellow());
-                    }
-                    "34" => {
-                        self.set_color(Color::blue());
-                    }
-                    "35" => {
-                        self.set_color(Color::magenta());
-                    }
-                    "36" => {
-                        self.set_color(Color::cyan());
-                    }
-                    "37" => {
-                        self.set_color(Color::white());
-                    }
-                    "38" => {
-                        next_token = ExpectedToken::ColorSpecifier;
-                    }
-                    "40" => {
-                        self.set_background(Color::black());
-                    }
-                    "41" => {
-                        self.set_background(Color::red());
-                    }
-                    "42" => {
-                        self.set_background(Color::green());
-                    }
-                    "43" => {
-                        self.set_background(Color::yellow());
-                    }
-                    "44" => {
-                        self.set_background(Color::blue());
-                    }
-                    "45" => {
-                        self.set_background(Color::magenta());
-                    }
-                    "46" => {
-                        self.set_background(Color::cyan());
-                    }
-                    "47" => {
-                        self.set_background(Color::white());
-                    }
-                    "48" => {
-                        next_token = ExpectedToken::ColorSpecifier;
-                        defining_background = true;
-                    }
-                    "90" => {
-                        self.set_color(Color::black());
-                        self.set_bright(true);
-                    }
-                    "91" => {
-                        self.set_color(Color::red());
-                        self.set_bright(true);
-                    }
-                    "92" => {
-                        self.set_color(Color::green());
-                        self.set_bright(true);
-                    }
-                    "93" => {
-                        self.set_color(Color::yellow());
-                        self.set_bright(true);
-                    }
-                    "94" => {
-                        self.set_color(Color::blue());
-                        self.set_bright(true);
-                    }
-                    "95" => {
-                        self.set_color(Color::magenta());
-                        self.set_bright(true);
-                    }
-                    "96" => {
-                        self.set_color(Color::cyan());
-                        self.set_bright(true);
-                    }
-                    "97" => {
-                        self.set_color(Color::white());
-                        self.set_bright(true);
-                    }
-                    "100" => {
-                        self.set_background(Color::black());
-                        self.set_bright(true);
-                    }
-                    "101" => {
-                        self.set_background(Color::red());
-                        self.set_bright(true);
-                    }
-                    "102" => {
-                        self.set_background(Color::green());
-                        self.set_bright(true);
-                    }
-                    "103" => {
-                        self.set_background(Color::yellow());
-                        self.set_bright(true);
-                    }
-                    "104" => {
-                        self.set_background(Color::blue());
-                        self.set_bright(true);
-                    }
-                    "105" => {
-                        self.set_background(Color::magenta());
-                        self.set_bright(true);
-                    }
-                    "106" => {
-                        self.set_background(Color::cyan());
-                        self.set_bright(true);
-                    }
-                    "107" => {
-                        self.set_background(Color::white());
-                        self.set_bright(true);
-                    }
-                    _ => {
-                        continue;
-                    }
-                },
-                ExpectedToken::ColorSpecifier => match &token[..] {
-                    "2" => {
-                        next_token = ExpectedToken::ColorByte;
-                        color_bytes_left_to_read = 3;
-                        defining_truecolor = true;
-                    }
-                    "5" => {
-                        next_token = ExpectedToken::ColorByte;
-                        color_bytes_left_to_read = 1;
-                    }
-                    _ => eprintln!(
-                        "Was expecting 2 or 5, got {} while parsing for color",
-                        token
-                    ),
-                },
-                ExpectedToken::ColorByte => {
-                    if defining_truecolor {
-                        match color_bytes_left_to_read {
-                            3 => {
-                                color_red = token.parse::<u8>().unwrap_or_default();
-                            }
-                            2 => {
-                                color_green = token.parse::<u8>().unwrap_or_default();
-                            }
-                            1 => {
-                                color_blue = token.parse::<u8>().unwrap_or_default();
-                                next_token = ExpectedToken::Any;
-                                if defining_background {
-                                    self.set_background(Color::new_truecolor(
-                                        color_red,
-                                        color_green,
-                                        color_blue,
-                                    ));
-                                } else {
-                                    self.set_color(Color::new_truecolor(
-                                        color_red,
-                                        color_green,
-                                        color_blue,
-                                    ));
-                                }
-                            }
-                            _ => continue,
-                        }
-                    } else {
-                        match color_bytes_left_to_read {
-                            1 => {
-                                color_8bit = token.parse::<u8>().unwrap_or_default();
-                                next_token = ExpectedToken::Any;
-                                if defining_background {
-                                    if color_8bit > 231 {
-                                        self.set_background(Color::new_gray(color_8bit - 232));
-                                    } else {
-                                        self.set_background(Color::EightBit(color_8bit));
-                                    }
-                                } else if color_8bit > 231 {
-                                    self.set_color(Color::new_gray(color_8bit - 232));
-                                } else {
-                                    self.set_color(Color::EightBit(color_8bit));
-                                }
-                            }
-                            _ => continue,
-                        }
-                    }
-                    color_bytes_left_to_read = color_bytes_left_to_read.saturating_sub(1);
-                }
-            }
+        if let Some(glyph) = glyphs.last() {
+            self.character = glyph.character;
         }
+        self.appearance = split(look.pack());
     }
 
     /// Set glyph's character to given value.
@@ -503,102 +186,164 @@ impl Glyph {
     }
     /// Set glyph's color to given value.
     pub fn set_color(&mut self, color: Color) {
-        self.color = color;
+        self.modify(|look| look.color = color);
     }
     /// Set glyph's background to given value.
     pub fn set_background(&mut self, background: Color) {
-        self.background = background;
+        self.modify(|look| look.background = background);
     }
     /// Set glyph's transparency setting to given value.
     pub fn set_transparent(&mut self, transparent: bool) {
-        self.transparent = transparent;
+        self.modify(|look| look.set_transparent(transparent));
     }
     /// Set glyph's brightness to given value.
     pub fn set_bright(&mut self, bright: bool) {
-        self.bright = bright;
-        if self.bright {
-            self.dim = false;
-        }
+        self.modify(|look| look.set_bright(bright));
     }
     /// Set glyph's dimming setting to given value.
     pub fn set_dim(&mut self, dim: bool) {
-        self.dim = dim;
-        if self.dim {
-            self.bright = false;
-        }
+        self.modify(|look| look.set_dim(dim));
     }
 
     /// Set glyph's plain setting to given value.
     pub fn set_plain(&mut self, plain: bool) {
-        self.plain = plain;
-        if self.plain {
-            self.bright = false;
-            self.dim = false;
-            self.italic = false;
-            self.underline = false;
-            self.blink = false;
-            self.blink_fast = false;
-            self.reverse = false;
-            self.transparent = false;
-            self.strike = false;
-            self.color = Color::white();
-            self.background = Color::black();
-        }
+        self.modify(|look| look.set_plain(plain));
     }
 
     /// Set glyph's italic seetting to given value.
     pub fn set_italic(&mut self, italic: bool) {
-        self.italic = italic;
+        self.modify(|look| look.set_italic(italic));
     }
 
     /// Set glyph's underline setting to given value.
     pub fn set_underline(&mut self, underline: bool) {
-        self.underline = underline;
+        self.modify(|look| look.set_underline(underline));
     }
 
     /// Set glyph's blink setting to given value.
     pub fn set_blink(&mut self, blink: bool) {
-        self.blink = blink;
-        if self.blink {
-            self.blink_fast = false;
-        }
+        self.modify(|look| look.set_blink(blink));
     }
 
     /// Set glyph's blinkfast setting to given value.
     pub fn set_blinkfast(&mut self, blink: bool) {
-        self.blink_fast = blink;
-        if self.blink_fast {
-            self.blink = false;
-        }
+        self.modify(|look| look.set_blinkfast(blink));
     }
 
     /// Set glyph's reverse setting to given value.
     pub fn set_reverse(&mut self, reverse: bool) {
-        self.reverse = reverse;
+        self.modify(|look| look.set_reverse(reverse));
     }
 
     /// Set glyph's strike setting to given value.
     pub fn set_strike(&mut self, strike: bool) {
-        self.strike = strike;
+        self.modify(|look| look.set_strike(strike));
+    }
+
+    /// Replace all of glyph's style flags at once.
+    pub fn set_style(&mut self, style: Style) {
+        self.modify(|look| look.style = style);
+    }
+
+    /// Get glyph's color.
+    pub fn color(&self) -> Color {
+        self.appearance().color
+    }
+    /// Get glyph's background.
+    pub fn background(&self) -> Color {
+        self.appearance().background
+    }
+    /// Get all of glyph's style flags.
+    pub fn style(&self) -> Style {
+        self.appearance().style
+    }
+    /// Check if glyph is plain.
+    pub fn is_plain(&self) -> bool {
+        self.style().contains(Style::PLAIN)
+    }
+    /// Check if glyph is bright.
+    pub fn is_bright(&self) -> bool {
+        self.style().contains(Style::BRIGHT)
+    }
+    /// Check if glyph is dimmed.
+    pub fn is_dim(&self) -> bool {
+        self.style().contains(Style::DIM)
+    }
+    /// Check if glyph is italic.
+    pub fn is_italic(&self) -> bool {
+        self.style().contains(Style::ITALIC)
+    }
+    /// Check if glyph is underlined.
+    pub fn is_underline(&self) -> bool {
+        self.style().contains(Style::UNDERLINE)
+    }
+    /// Check if glyph is blinking.
+    pub fn is_blink(&self) -> bool {
+        self.style().contains(Style::BLINK)
+    }
+    /// Check if glyph is blinking fast.
+    pub fn is_blink_fast(&self) -> bool {
+        self.style().contains(Style::BLINK_FAST)
+    }
+    /// Check if glyph has reversed colors.
+    pub fn is_reverse(&self) -> bool {
+        self.style().contains(Style::REVERSE)
+    }
+    /// Check if glyph is transparent.
+    pub fn is_transparent(&self) -> bool {
+        self.style().contains(Style::TRANSPARENT)
+    }
+    /// Check if glyph is striked through.
+    pub fn is_strike(&self) -> bool {
+        self.style().contains(Style::STRIKE)
+    }
+
+    /// Check if both glyphs are printed with identical colors and style.
+    pub fn same_look(&self, other: &Glyph) -> bool {
+        self.appearance == other.appearance
+    }
+
+    pub(crate) fn with_appearance(character: char, appearance: Appearance) -> Self {
+        Glyph {
+            character,
+            appearance: split(appearance.pack()),
+        }
+    }
+
+    pub(crate) fn appearance(&self) -> Appearance {
+        let [low, high] = self.appearance;
+        Appearance::unpack(low as u64 | (high as u64) << 32)
+    }
+
+    fn modify<F: FnOnce(&mut Appearance)>(&mut self, change: F) {
+        let mut look = self.appearance();
+        change(&mut look);
+        self.appearance = split(look.pack());
     }
 }
 
+/// Split a packed appearance in halves, keeping glyph aligned to 4 bytes like it's char.
+fn split(packed: u64) -> [u32; 2] {
+    [packed as u32, (packed >> 32) as u32]
+}
+
 impl Default for Glyph {
     fn default() -> Self {
         Glyph {
             character: ' ',
-            color: Color::Basic(ColorName::White),
-            background: Color::Basic(ColorName::Black),
-            plain: false,
-            bright: false,
-            dim: false,
-            italic: false,
-            underline: false,
-            blink: false,
-            blink_fast: false,
-            reverse: false,
-            transparent: false,
-            strike: false,
+            appearance: split(style::DEFAULT.pack()),
         }
     }
 }
+
+impl fmt::Debug for Glyph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let look = self.appearance();
+        f.debug_struct("Glyph")
+            .field("character", &self.character)
+            .field("color", &look.color)
+            .field("background", &look.background)
+            .field("style", &look.style)
+            .finish()
+    }
+}
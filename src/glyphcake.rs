@@ -26,7 +26,11 @@ impl GlyphCake {
 
     /// Set a new glyph for given layer.
     pub fn update(&mut self, glyph: Glyph, layer: usize) {
-        let what_to_insert = if glyph.transparent { None } else { Some(glyph) };
+        let what_to_insert = if glyph.is_transparent() {
+            None
+        } else {
+            Some(glyph)
+        };
         if layer >= self.top_layer {
             for i in self.top_layer + 1..layer + 1 {
                 self.glyphs.insert(i, None);
@@ -56,12 +60,17 @@ impl GlyphCake {
         }
     }
 
+    /// Get a layer of top non-transparent glyph.
+    pub fn top_layer(&self) -> usize {
+        self.top_layer
+    }
+
     /// Get a glyph representing top non-transparent layer.
     pub fn get_glyph(&mut self) -> Glyph {
         self.modified = false;
         let glyph = self.glyphs.get(self.top_layer);
         if glyph.is_none() || glyph.unwrap().is_none() {
-            eprintln!("Cake has no glyphs!");
+            // Cake has no glyphs
             return Glyph::default();
         };
         glyph.unwrap().unwrap()
@@ -1,14 +1,16 @@
 use super::animation::Animation;
+use super::ansi::frame_to_text;
+use super::bundle;
 use super::color::Color;
-use super::error::AnimError;
+use super::error::{report, AnimError};
 use super::frame::from_file as frame_from_file;
 use super::pixel::Pixel;
 use super::time::Timestamp;
 use super::utilities::text_to_frame;
 use super::Glyph;
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
 
 use std::collections::HashMap;
 use std::mem::replace;
@@ -23,12 +25,56 @@ pub struct Graphic {
     pub invisible: bool,
     pub running_anim: Option<usize>,
     pub awaiting_anim: Option<(usize, Timestamp)>,
+    /// Layer a graphic is meant to be placed on, as stored in a bundle.
+    pub layer: Option<usize>,
     next_lib_id: usize,
     next_anim_id: usize,
     library: HashMap<usize, Vec<Glyph>>,
+    names: HashMap<usize, String>,
     pub animations: HashMap<usize, Animation>,
 }
 
+/// A snapshot of a graphic's state on screen, as returned by Manager::graphic_info.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GraphicInfo {
+    pub id: usize,
+    pub layer: usize,
+    pub offset: (isize, isize),
+    pub cols: usize,
+    pub rows: usize,
+    pub current_frame: usize,
+    /// Ids of all frames, in ascending order.
+    pub frames: Vec<usize>,
+    /// Ids of all animations, in ascending order.
+    pub animations: Vec<usize>,
+    /// Animation selected for playback.
+    pub running_anim: Option<usize>,
+    /// Is selected animation running, or paused.
+    pub animation_running: bool,
+    /// Animation to be started after current one ends.
+    pub awaiting_anim: Option<usize>,
+    pub invisible: bool,
+}
+
+impl GraphicInfo {
+    /// Screen area covered by graphic: left column, top row,
+    /// and right column and bottom row just outside of it.
+    pub fn bounds(&self) -> (isize, isize, isize, isize) {
+        (
+            self.offset.0,
+            self.offset.1,
+            self.offset.0 + self.cols as isize,
+            self.offset.1 + self.rows as isize,
+        )
+    }
+
+    /// Is given screen position covered by graphic.
+    pub fn contains(&self, col: isize, row: isize) -> bool {
+        let (left, top, right, bottom) = self.bounds();
+        (left..right).contains(&col) && (top..bottom).contains(&row)
+    }
+}
+
 impl Graphic {
     /// Create a new graphic of a given size with defined frames and animations.
     pub fn new(
@@ -44,8 +90,7 @@ impl Graphic {
             0
         };
         let mut next_anim_id = 0;
-        let a = if animations.is_some() {
-            let anim = animations.unwrap();
+        let a = if let Some(anim) = animations {
             if !anim.is_empty() {
                 next_anim_id = anim.keys().max().unwrap() + 1
             };
@@ -61,166 +106,236 @@ impl Graphic {
             invisible: false,
             running_anim: None,
             awaiting_anim: None,
+            layer: None,
             next_lib_id,
             next_anim_id,
             library,
+            names: HashMap::new(),
             animations: a,
         }
     }
 
-    /// Read a graphic from file.
-    pub fn from_file<P>(filename: P) -> Option<Self>
+    /// Read a graphic from a .txg file.
+    /// Frame files are resolved relative to that file's location.
+    /// Malformed lines are skipped and passed to error hook, like frames that can not be read.
+    pub fn from_file<P>(filename: P) -> Result<Self, AnimError>
     where
-        P: AsRef<Path> + std::fmt::Debug,
+        P: AsRef<Path>,
     {
-        let mut create_graphic = false;
+        let filename = filename.as_ref();
+        let text = fs::read_to_string(filename).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound | io::ErrorKind::PermissionDenied => {
+                AnimError::UnableToOpenFile(filename.to_path_buf())
+            }
+            _ => AnimError::UnableToReadFile(filename.to_path_buf()),
+        })?;
+        let base_path = filename.parent().unwrap_or_else(|| Path::new("."));
+        let resolver = |frame_file: &str| {
+            let frame_file = Path::new(frame_file);
+            if frame_file.is_absolute() {
+                frame_from_file(&frame_file)
+            } else {
+                frame_from_file(&base_path.join(frame_file))
+            }
+        };
+        // Malformed lines are skipped, so that the rest of a graphic still loads
+        let skip = |e: AnimError| {
+            report(e.in_file(filename));
+            Ok(())
+        };
+        Graphic::parse(&text, resolver, skip).map_err(|e| e.in_file(filename))
+    }
+
+    /// Read a graphic definition in .txg format from a reader.
+    /// See from_str for how frame references are resolved.
+    pub fn from_reader<R, F>(mut reader: R, resolver: F) -> Result<Self, AnimError>
+    where
+        R: Read,
+        F: FnMut(&str) -> Result<(usize, Vec<Glyph>), AnimError>,
+    {
+        let mut text = String::with_capacity(1024);
+        reader
+            .read_to_string(&mut text)
+            .map_err(|e| AnimError::UnableToRead(e.to_string()))?;
+        Graphic::from_str(&text, resolver)
+    }
+
+    /// Read a graphic definition in .txg format from text.
+    /// Every line should define a frame or an animation, like following:
+    /// frame first first.txf
+    /// animation loop run first:1000 second:500
+    /// Optional lines `layer 1` and `current first` set graphic's layer and start frame.
+    /// For every frame line resolver is called with frame's reference (first.txf above)
+    /// and should return frame's width together with it's glyphs,
+    /// e.g. using frame_from_str on a text embedded with include_str!.
+    pub fn from_str<F>(text: &str, resolver: F) -> Result<Self, AnimError>
+    where
+        F: FnMut(&str) -> Result<(usize, Vec<Glyph>), AnimError>,
+    {
+        Graphic::parse(text, resolver, Err)
+    }
+
+    /// Parse a .txg definition, passing every malformed line to skip,
+    /// which either gives up by returning an error, or lets that line be ignored.
+    fn parse<F, S>(text: &str, mut resolver: F, mut skip: S) -> Result<Self, AnimError>
+    where
+        F: FnMut(&str) -> Result<(usize, Vec<Glyph>), AnimError>,
+        S: FnMut(AnimError) -> Result<(), AnimError>,
+    {
+        let error = |line: usize, reason: String| AnimError::UnableToParseGraphic(line, reason);
         let mut running_anim = None;
         let mut invisible = false;
+        let mut layer = None;
         let mut current_frame = 0;
         let mut rows = 0;
         let mut cols = 0;
-        let hash = '#';
-        let colon = ':';
         let mut library: HashMap<usize, Vec<Glyph>> = HashMap::new();
-        let mut next_lib_id = 0;
-        let mut next_anim_id = 0;
-
         let mut animations: HashMap<usize, Animation> = HashMap::new();
         let mut names_mapping: HashMap<String, usize> = HashMap::new();
 
-        let mut read_lines = vec![];
-        let par = filename.as_ref().parent();
-        let base_path = if par.is_some() {
-            Path::new(par.to_owned().unwrap())
-        } else {
-            Path::new(".")
-        };
-
-        if let Ok(file) = File::open(&filename) {
-            for line in io::BufReader::new(file).lines() {
-                read_lines.push(line);
-            }
-        }
-        for line in read_lines.into_iter().flatten() {
-            if line.trim().starts_with(hash) {
-                continue;
-            }
-            if line.is_empty() {
-                continue;
-            }
-            let tokens: Vec<&str> = line.split_whitespace().collect();
+        let mut parse_line = |line_no: usize, tokens: Vec<&str>| -> Result<(), AnimError> {
             match tokens[0] {
                 "invisible" => {
                     invisible = true;
                 }
+                "layer" => {
+                    let number = tokens.get(1).and_then(|t| t.parse::<usize>().ok());
+                    layer = Some(number.ok_or_else(|| {
+                        error(
+                            line_no,
+                            "should be 'layer number #maybe comment'".to_string(),
+                        )
+                    })?);
+                }
+                "current" => {
+                    let name = tokens.get(1).copied().unwrap_or_default();
+                    current_frame = *names_mapping
+                        .get(name)
+                        .ok_or_else(|| error(line_no, format!("unable to find frame {}", name)))?;
+                }
                 "frame" => {
-                    if tokens.len() > 2 {
-                        let frame_name = tokens[1];
-                        let frame_file = Path::new(tokens[2]);
-                        let frame_result = if frame_file.is_absolute() {
-                            frame_from_file(&frame_file)
-                        } else {
-                            frame_from_file(&base_path.join(frame_file))
-                        };
-                        if let Some((cs, frame)) = frame_result {
-                            if cols > 0 {
-                                if cols != cs {
-                                    eprintln!("Unable to add frame that has cols: {}, when expected is {}", cs,cols);
-                                    continue;
-                                }
-                            } else {
-                                cols = cs;
-                                rows = frame.len() / cols;
-                            }
-
-                            names_mapping.insert(frame_name.to_owned(), next_lib_id);
-                            library.insert(next_lib_id, frame);
-                            create_graphic = true;
-                            next_lib_id += 1;
+                    if tokens.len() < 3 {
+                        return Err(error(
+                            line_no,
+                            "should be 'frame name filepath #maybe comment'".to_string(),
+                        ));
+                    }
+                    let frame_name = tokens[1];
+                    if names_mapping.contains_key(frame_name) {
+                        return Err(error(
+                            line_no,
+                            format!("frame {} defined twice", frame_name),
+                        ));
+                    }
+                    let (cs, frame) = resolver(tokens[2]).map_err(|e| {
+                        AnimError::UnableToReadFrame(line_no, frame_name.to_string(), Box::new(e))
+                    })?;
+                    if cols > 0 {
+                        if cols != cs || frame.len() != rows * cols {
+                            return Err(error(
+                                line_no,
+                                format!(
+                                    "frame {} is {}x{}, when expected is {}x{}",
+                                    frame_name,
+                                    cs,
+                                    frame.len() / cs.max(1),
+                                    cols,
+                                    rows
+                                ),
+                            ));
                         }
                     } else {
-                        eprintln!("Incorrect line(should be 'frame name filepath #maybe comment'): {} while building Graphic from file", line);
+                        cols = cs;
+                        rows = frame.len() / cols;
                     }
+                    let frame_id = library.len();
+                    names_mapping.insert(frame_name.to_owned(), frame_id);
+                    library.insert(frame_id, frame);
                 }
                 "animation" => {
                     let mut looping = false;
                     let mut running = false;
-                    let start_time = Timestamp::now();
                     let mut ordering: Vec<(usize, Timestamp)> = Vec::new();
-                    if tokens.len() > 2 {
-                        for t in &tokens[1..] {
-                            match *t {
-                                "loop" => {
-                                    looping = true;
-                                }
-                                "run" => {
-                                    if running_anim.is_none() {
-                                        running = true;
-                                    }
-                                }
-                                _ => {
-                                    if t.contains(hash) {
-                                        break;
-                                    }
-                                    if t.contains(colon) {
-                                        let frame_time: Vec<&str> = t.split(colon).collect();
-                                        if frame_time.len() != 2 {
-                                            eprint!("Unable to read animation definition from file, {} should be frame_id:time_ms  ",t);
-                                        } else if let Some(frame_id) =
-                                            names_mapping.get(frame_time[0])
-                                        {
-                                            if let Ok(msec) = frame_time[1].parse::<u32>() {
-                                                ordering.push((*frame_id, Timestamp::new(0, msec)))
-                                            } else {
-                                                eprint!(
-                                                    "Unable to read integer from {} (in {}) ",
-                                                    frame_time[1], t
-                                                );
-                                            }
-                                        } else {
-                                            eprint!(
-                                                "Unable to find frame with id {} ",
-                                                frame_time[0]
-                                            );
-                                        }
-                                    } else {
-                                        eprint!("Unable to read animation definition from file, {} is missing ':' ", t);
-                                    }
+                    for t in &tokens[1..] {
+                        match *t {
+                            "loop" => {
+                                looping = true;
+                            }
+                            "run" => {
+                                if running_anim.is_none() {
+                                    running = true;
                                 }
                             }
+                            _ => {
+                                let (name, time) = t.split_once(':').ok_or_else(|| {
+                                    error(line_no, format!("{} should be frame_name:time_ms", t))
+                                })?;
+                                let frame_id = names_mapping.get(name).ok_or_else(|| {
+                                    error(line_no, format!("unable to find frame {}", name))
+                                })?;
+                                let msec = time.parse::<u32>().map_err(|_| {
+                                    error(line_no, format!("unable to read time from {}", t))
+                                })?;
+                                ordering.push((*frame_id, Timestamp::new(0, msec)));
+                            }
                         }
-                        if running {
-                            current_frame = ordering
-                                .last()
-                                .expect("Running animation with no frame ordering defined")
-                                .0;
-                            running_anim = Some(next_anim_id);
-                        }
-                        let a = Animation::new(running, looping, ordering, start_time);
-                        animations.insert(next_anim_id, a);
-                        next_anim_id += 1;
-                    } else {
-                        eprintln!("Incorrect line(should be 'animation [loop] [run] {{frame_name:duration=}}+ #maybe comment'): {} while building Graphic from file", line);
                     }
+                    if ordering.is_empty() {
+                        return Err(error(
+                            line_no,
+                            "should be 'animation [loop] [run] {frame_name:duration}+ #maybe comment'"
+                                .to_string(),
+                        ));
+                    }
+                    let anim_id = animations.len();
+                    if running {
+                        current_frame = ordering.last().unwrap().0;
+                        running_anim = Some(anim_id);
+                    }
+                    let a = Animation::new(running, looping, ordering, Timestamp::now());
+                    animations.insert(anim_id, a);
+                }
+                other => {
+                    return Err(error(line_no, format!("unknown entry {:?}", other)));
                 }
-                &_ => {}
+            }
+            Ok(())
+        };
+        for (i, line) in text.lines().enumerate() {
+            let tokens: Vec<&str> = line
+                .split_whitespace()
+                .take_while(|t| !t.starts_with('#'))
+                .collect();
+            if tokens.is_empty() {
+                continue;
+            }
+            if let Err(e) = parse_line(i + 1, tokens) {
+                skip(e)?;
             }
         }
-        if create_graphic {
-            return Some(Graphic {
-                rows,
-                cols,
-                current_frame,
-                invisible,
-                running_anim,
-                awaiting_anim: None,
-                next_lib_id,
-                next_anim_id,
-                library,
-                animations,
-            });
+        if library.is_empty() {
+            return Err(error(
+                text.lines().count().max(1),
+                "no frames defined".to_string(),
+            ));
         }
-        None
+        Ok(Graphic {
+            rows,
+            cols,
+            current_frame,
+            invisible,
+            running_anim,
+            awaiting_anim: None,
+            layer,
+            next_lib_id: library.len(),
+            next_anim_id: animations.len(),
+            library,
+            names: names_mapping
+                .into_iter()
+                .map(|(name, id)| (id, name))
+                .collect(),
+            animations,
+        })
     }
 
     /// Convert a single frame into a graphic instance.
@@ -236,9 +351,11 @@ impl Graphic {
             invisible: false,
             running_anim: None,
             awaiting_anim: None,
+            layer: None,
             next_lib_id: 1,
             next_anim_id: 0,
             library,
+            names: HashMap::new(),
             animations: HashMap::new(),
         }
     }
@@ -256,9 +373,11 @@ impl Graphic {
             invisible: false,
             running_anim: None,
             awaiting_anim: None,
+            layer: None,
             next_lib_id: 1,
             next_anim_id: 0,
             library,
+            names: HashMap::new(),
             animations: HashMap::new(),
         }
     }
@@ -276,9 +395,11 @@ impl Graphic {
             invisible: false,
             running_anim: None,
             awaiting_anim: None,
+            layer: None,
             next_lib_id: 1,
             next_anim_id: 0,
             library,
+            names: HashMap::new(),
             animations: HashMap::new(),
         }
     }
@@ -437,7 +558,7 @@ impl Graphic {
         } else if let Some(frame) = self.library.get(&frame_id) {
             Ok(frame.clone())
         } else {
-            Err(AnimError::FrameNotFound)
+            Err(AnimError::FrameNotFound(frame_id))
         }
     }
 
@@ -504,17 +625,15 @@ impl Graphic {
     }
 
     /// Set style of all glyphs in current frame to specific value.
-    pub fn set_current_frame_style(&mut self, mut style: Glyph) {
+    pub fn set_current_frame_style(&mut self, style: Glyph) {
         let mut new_frame = Vec::with_capacity(self.cols * self.rows);
         let mut frame = self
             .library
             .remove(&self.current_frame)
             .expect("Current frame not defined in frame library.");
+        let style = style.style();
         for g in frame.iter_mut() {
-            style.set_char(g.character);
-            style.set_color(g.color);
-            style.set_background(g.background);
-            new_frame.push(style)
+            new_frame.push(Glyph::styled(g.character, g.color(), g.background(), style))
         }
         self.library.insert(self.current_frame, new_frame);
     }
@@ -528,9 +647,7 @@ impl Graphic {
     ) -> Vec<Pixel> {
         let mut changed = Vec::with_capacity(self.cols);
         if let Ok(glyphs) = self.get_frame(*frame_id) {
-            for (i, (old_glyph, new_glyph)) in
-                self.get_glyphs().iter().zip(glyphs.into_iter()).enumerate()
-            {
+            for (i, (old_glyph, new_glyph)) in self.get_glyphs().iter().zip(glyphs).enumerate() {
                 if force || new_glyph != *old_glyph {
                     changed.push(Pixel::new(
                         (offset.0 + (i % self.cols) as isize) as usize,
@@ -554,4 +671,115 @@ impl Graphic {
         }
         self.library.insert(frame_id, new_frame)
     }
+
+    /// Describe graphic's current state, as placed with given id, layer and offset.
+    pub fn info(&self, id: usize, layer: usize, offset: (isize, isize)) -> GraphicInfo {
+        let mut animations: Vec<usize> = self.animations.keys().copied().collect();
+        animations.sort_unstable();
+        GraphicInfo {
+            id,
+            layer,
+            offset,
+            cols: self.cols,
+            rows: self.rows,
+            current_frame: self.current_frame,
+            frames: self.frame_ids(),
+            animations,
+            running_anim: self.running_anim,
+            animation_running: self
+                .running_anim
+                .and_then(|aid| self.animations.get(&aid))
+                .is_some_and(|anim| anim.running),
+            awaiting_anim: self.awaiting_anim.map(|(aid, _when)| aid),
+            invisible: self.invisible,
+        }
+    }
+
+    /// Get ids of all frames stored in library, in ascending order.
+    pub fn frame_ids(&self) -> Vec<usize> {
+        let mut ids: Vec<usize> = self.library.keys().copied().collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Get a frame stored in library, regardless of graphic's visibility.
+    pub(crate) fn library_frame(&self, frame_id: usize) -> Option<&Vec<Glyph>> {
+        self.library.get(&frame_id)
+    }
+
+    /// Get a name of a frame, as it was defined in a file graphic was read from.
+    pub fn frame_name(&self, frame_id: usize) -> Option<&str> {
+        self.names.get(&frame_id).map(|name| name.as_str())
+    }
+
+    /// Name a frame, so that it can be referred to when graphic is written to a file.
+    pub fn set_frame_name(&mut self, frame_id: usize, name: &str) -> bool {
+        if !self.library.contains_key(&frame_id) {
+            return false;
+        }
+        self.names.insert(frame_id, name.to_owned());
+        true
+    }
+
+    /// Write a graphic with all of it's frames and animations into a single bundle file.
+    pub fn save_bundle<P: AsRef<Path>>(&self, filename: P) -> Result<(), AnimError> {
+        let filename = filename.as_ref();
+        fs::write(filename, self.to_bundle())
+            .map_err(|_| AnimError::UnableToWriteFile(filename.to_path_buf()))
+    }
+
+    /// Read a graphic from a bundle file.
+    pub fn load_bundle<P: AsRef<Path>>(filename: P) -> Result<Self, AnimError> {
+        let filename = filename.as_ref();
+        let text = fs::read_to_string(filename).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound | io::ErrorKind::PermissionDenied => {
+                AnimError::UnableToOpenFile(filename.to_path_buf())
+            }
+            _ => AnimError::UnableToReadFile(filename.to_path_buf()),
+        })?;
+        Graphic::from_bundle(&text).map_err(|e| e.in_file(filename))
+    }
+
+    /// Write a graphic into given directory as an index.txg file accompanied
+    /// by a .txf file for every frame, so that it can be read back with from_file.
+    /// Returns a path to written index file.
+    pub fn write_to_dir<P: AsRef<Path>>(&self, dir: P) -> Result<PathBuf, AnimError> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir).map_err(|_| AnimError::UnableToWriteFile(dir.to_path_buf()))?;
+        let ids = self.frame_ids();
+        let names = bundle::frame_names(self, &ids);
+        let mut index = String::new();
+        if self.invisible {
+            index.push_str("invisible\n");
+        }
+        if let Some(layer) = self.layer {
+            index.push_str(&format!("layer {}\n", layer));
+        }
+        for id in &ids {
+            let file_name = format!("{}.txf", names[id]);
+            let frame_file = dir.join(&file_name);
+            fs::write(&frame_file, frame_to_text(self.cols, &self.library[id]))
+                .map_err(|_| AnimError::UnableToWriteFile(frame_file))?;
+            index.push_str(&format!("frame {} {}\n", names[id], file_name));
+        }
+        index.push_str(&bundle::animations_text(self, &names));
+        // Written last, so that a running animation does not override it
+        if let Some(name) = names.get(&self.current_frame) {
+            index.push_str(&format!("current {}\n", name));
+        }
+        let index_file = dir.join("index.txg");
+        fs::write(&index_file, index)
+            .map_err(|_| AnimError::UnableToWriteFile(index_file.clone()))?;
+        Ok(index_file)
+    }
+
+    /// Serialize a graphic into bundle format.
+    pub fn to_bundle(&self) -> String {
+        bundle::write(self)
+    }
+
+    /// Create a graphic from a bundle, e.g. one embedded with include_str!.
+    pub fn from_bundle(text: &str) -> Result<Self, AnimError> {
+        bundle::read(text)
+    }
 }
@@ -1,73 +1,32 @@
+use super::error::AnimError;
 use super::key::Key;
 use std::collections::HashMap;
 use std::env;
 use std::process::Command;
 
 /// Ask OS how many rows and cols current terminal has.
-pub fn ask_os_for_rows_and_cols() -> (usize, usize) {
+pub fn ask_os_for_rows_and_cols() -> Result<(usize, usize), AnimError> {
     let filtered_env: HashMap<String, String> = env::vars()
         .filter(|(k, _)| k == "TERM" || k == "TZ" || k == "LANG" || k == "PATH")
         .collect();
-    let rows = match Command::new("tput")
-        .arg("lines")
-        .envs(&filtered_env)
-        .output()
-    {
-        Ok(data) => {
-            let output = String::from_utf8(data.stdout);
-            if output.is_ok() {
-                let output = output.unwrap();
-                let number = output.trim().parse::<usize>();
-                match number {
-                    Ok(a_number) => a_number,
-                    Err(e) => {
-                        eprintln!("\x1b[97;41;5mERR\x1b[m Unable to determine lines count from {}, using defaults\n{}", output, e);
-                        35
-                    }
-                }
-            } else {
-                35
-            }
-        }
-        Err(e) => {
-            eprintln!(
-                "\x1b[97;41;5mERR\x1b[m Unable to determine lines count, using defaults\n{:?}",
-                e
-            );
-            35
-        }
-    };
+    let rows = ask_tput("lines", &filtered_env)?;
+    let cols = ask_tput("cols", &filtered_env)?;
+    Ok((rows, cols))
+}
 
-    let cols = match Command::new("tput")
-        .arg("cols")
-        .envs(&filtered_env)
+/// Run tput with given argument, expecting a number in return.
+fn ask_tput(arg: &str, filtered_env: &HashMap<String, String>) -> Result<usize, AnimError> {
+    let error = |reason: String| AnimError::UnknownTerminalSize(reason);
+    let data = Command::new("tput")
+        .arg(arg)
+        .envs(filtered_env)
         .output()
-    {
-        Ok(data) => {
-            let output = String::from_utf8(data.stdout);
-            if output.is_ok() {
-                let output = output.unwrap();
-                let number = output.trim().parse::<usize>();
-                match number {
-                    Ok(a_number) => a_number,
-                    Err(e) => {
-                        eprintln!("\x1b[97;41;5mERR\x1b[m Unable to determine cols count from {}, using defaults\n{}", output, e);
-                        80
-                    }
-                }
-            } else {
-                80
-            }
-        }
-        Err(e) => {
-            eprintln!(
-                "\x1b[97;41;5mERR\x1b[m Unable to determine cols count, using defaults\n{:?}",
-                e
-            );
-            80
-        }
-    };
-    (rows, cols)
+        .map_err(|e| error(format!("tput {}: {}", arg, e)))?;
+    let output = String::from_utf8_lossy(&data.stdout);
+    output
+        .trim()
+        .parse::<usize>()
+        .map_err(|e| error(format!("tput {} returned {:?}: {}", arg, output.trim(), e)))
 }
 
 /// Helper function used by read_char that converts non-printable
@@ -672,7 +631,7 @@ pub fn map_key_to_char(key: &Key) -> Option<char> {
         Key::Star => Some('*'),
         Key::Plus => Some('+'),
         Key::Comma => Some(','),
-        Key::Dash => Some('^'),
+        Key::Dash => Some('-'),
         Key::Period => Some('.'),
         Key::Slash => Some('/'),
         Key::Zero => Some('0'),
@@ -721,7 +680,7 @@ pub fn map_key_to_char(key: &Key) -> Option<char> {
         Key::LeftBracket => Some('['),
         Key::BackSlash => Some('\\'),
         Key::RightBracket => Some(']'),
-        Key::Caret => Some('\r'),
+        Key::Caret => Some('^'),
         Key::Underscore => Some('_'),
         Key::BackTick => Some('`'),
         Key::A => Some('a'),
@@ -757,6 +716,32 @@ pub fn map_key_to_char(key: &Key) -> Option<char> {
         _ => None,
     }
 }
+#[cfg(feature = "async")]
+/// Length of a complete key sequence at the start of given bytes,
+/// None when it could still continue, like a lone Escape.
+pub fn sequence_len(bytes: &[u8]) -> Option<usize> {
+    match *bytes.first()? {
+        27 => match bytes.get(1)? {
+            b'[' => bytes[2..]
+                .iter()
+                .position(|b| (0x40..=0x7e).contains(b))
+                .map(|i| i + 3),
+            b'O' => (bytes.len() >= 3).then_some(3),
+            _ => Some(2),
+        },
+        byte if byte < 0xc0 => Some(1),
+        byte => {
+            let len = if byte >= 0xf0 {
+                4
+            } else if byte >= 0xe0 {
+                3
+            } else {
+                2
+            };
+            (bytes.len() >= len).then_some(len)
+        }
+    }
+}
 /// Convert numerical value received from keyboard into it's Key representation.
 pub fn map_bytes_to_key(bytes: Vec<u8>) -> Option<Key> {
     let how_many = bytes.len();
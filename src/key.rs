@@ -407,6 +407,8 @@ pub enum Key {
 
     AltUnicode(Vec<u8>),
     Unicode(Vec<u8>),
+    /// Screen was resized along with terminal, layouts should be applied again.
+    Resize,
 }
 
 impl fmt::Display for Key {
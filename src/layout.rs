@@ -0,0 +1,254 @@
+//! Splitting screen into rows and columns, so that whole UIs
+//! can be declared instead of computing offsets by hand.
+//!
+//! A Layout divides a Rect along one direction into chunks sized by constraints.
+//! Graphics can be placed in chunks and layouts can be nested in them,
+//! then `apply` moves every placed graphic to it's chunk.
+//! When terminal gets resized `Manager::read_key` returns `Key::Resize`,
+//! then `apply` is simply called again with the new area.
+
+use super::manager::Manager;
+
+/// A rectangular area of the screen, with x and y counted from 0.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub cols: usize,
+    pub rows: usize,
+}
+
+impl Rect {
+    pub fn new(x: usize, y: usize, cols: usize, rows: usize) -> Self {
+        Rect { x, y, cols, rows }
+    }
+
+    /// Area covering whole Manager's screen.
+    pub fn screen(mgr: &Manager) -> Self {
+        let (cols, rows) = mgr.screen_size();
+        Rect::new(0, 0, cols, rows)
+    }
+
+    /// Area shrunk by horizontal margin on left and right and vertical margin on top and bottom.
+    pub fn inner(&self, horizontal: usize, vertical: usize) -> Self {
+        let horizontal = horizontal.min(self.cols / 2);
+        let vertical = vertical.min(self.rows / 2);
+        Rect::new(
+            self.x + horizontal,
+            self.y + vertical,
+            self.cols - 2 * horizontal,
+            self.rows - 2 * vertical,
+        )
+    }
+
+    /// Top left corner of an area as an offset accepted by Manager.
+    pub fn offset(&self) -> (isize, isize) {
+        (self.x as isize, self.y as isize)
+    }
+}
+
+/// How big a chunk of a Layout should be.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Constraint {
+    /// Exactly that many cells.
+    Fixed(usize),
+    /// At least that many cells, growing to take free space.
+    Min(usize),
+    /// Free space, but no more than that many cells.
+    Max(usize),
+    /// Given percent of available space.
+    Percentage(usize),
+    /// Given fraction of available space.
+    Ratio(usize, usize),
+}
+
+/// Direction in which a Layout places it's chunks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// Chunks are columns placed from left to right.
+    Horizontal,
+    /// Chunks are rows placed from top to bottom.
+    Vertical,
+}
+
+/// What is placed within a chunk of a Layout.
+#[derive(Debug)]
+enum Slot {
+    Empty,
+    /// Graphic id, layer it is placed on and it's current offset.
+    Graphic(usize, usize, (isize, isize)),
+    Nested(Layout),
+}
+
+/// Division of an area into chunks, one for every constraint.
+/// Fixed, Percentage and Ratio chunks get their size first,
+/// the rest of space is shared between Min and Max chunks.
+/// When there is not enough space chunks at the end are shrunk.
+#[derive(Debug)]
+pub struct Layout {
+    pub direction: Direction,
+    pub constraints: Vec<Constraint>,
+    /// Space left empty on left and right side of an area.
+    pub horizontal_margin: usize,
+    /// Space left empty on top and bottom of an area.
+    pub vertical_margin: usize,
+    slots: Vec<Slot>,
+}
+
+impl Layout {
+    pub fn new(direction: Direction, constraints: Vec<Constraint>) -> Self {
+        let slots = constraints.iter().map(|_| Slot::Empty).collect();
+        Layout {
+            direction,
+            constraints,
+            horizontal_margin: 0,
+            vertical_margin: 0,
+            slots,
+        }
+    }
+
+    /// Layout with chunks placed side by side.
+    pub fn horizontal(constraints: Vec<Constraint>) -> Self {
+        Layout::new(Direction::Horizontal, constraints)
+    }
+
+    /// Layout with chunks placed one below another.
+    pub fn vertical(constraints: Vec<Constraint>) -> Self {
+        Layout::new(Direction::Vertical, constraints)
+    }
+
+    /// Leave the same margin on every side of an area.
+    pub fn with_margin(self, margin: usize) -> Self {
+        self.with_margins(margin, margin)
+    }
+
+    /// Leave separate margins on left and right, and on top and bottom of an area.
+    pub fn with_margins(mut self, horizontal: usize, vertical: usize) -> Self {
+        self.horizontal_margin = horizontal;
+        self.vertical_margin = vertical;
+        self
+    }
+
+    /// Place a graphic on given layer in chunk with given index.
+    /// Since graphics are moved relative to where they are,
+    /// offset must be the one graphic currently has on screen.
+    pub fn place(
+        mut self,
+        chunk: usize,
+        graphic_id: usize,
+        layer: usize,
+        offset: (isize, isize),
+    ) -> Self {
+        if let Some(slot) = self.slots.get_mut(chunk) {
+            *slot = Slot::Graphic(graphic_id, layer, offset);
+        }
+        self
+    }
+
+    /// Divide chunk with given index further with another layout.
+    pub fn nest(mut self, chunk: usize, layout: Layout) -> Self {
+        if let Some(slot) = self.slots.get_mut(chunk) {
+            *slot = Slot::Nested(layout);
+        }
+        self
+    }
+
+    /// Compute chunks of an area, one for every constraint.
+    pub fn split(&self, area: Rect) -> Vec<Rect> {
+        let area = area.inner(self.horizontal_margin, self.vertical_margin);
+        let total = match self.direction {
+            Direction::Horizontal => area.cols,
+            Direction::Vertical => area.rows,
+        };
+        let mut sizes: Vec<usize> = self
+            .constraints
+            .iter()
+            .map(|constraint| match *constraint {
+                Constraint::Fixed(size) | Constraint::Min(size) => size,
+                Constraint::Max(_) => 0,
+                Constraint::Percentage(percent) => total * percent.min(100) / 100,
+                Constraint::Ratio(_, 0) => 0,
+                Constraint::Ratio(num, den) => total * num.min(den) / den,
+            })
+            .collect();
+
+        let used: usize = sizes.iter().sum();
+        if used < total {
+            let mut free = total - used;
+            // Free space is shared equally by chunks that can grow, until none can
+            loop {
+                let growing: Vec<usize> = (0..sizes.len())
+                    .filter(|i| match self.constraints[*i] {
+                        Constraint::Min(_) => true,
+                        Constraint::Max(limit) => sizes[*i] < limit,
+                        _ => false,
+                    })
+                    .collect();
+                if free == 0 || growing.is_empty() {
+                    break;
+                }
+                let share = (free / growing.len()).max(1);
+                for i in growing {
+                    let limit = match self.constraints[i] {
+                        Constraint::Max(limit) => limit,
+                        _ => usize::MAX,
+                    };
+                    let grow = share.min(limit - sizes[i]).min(free);
+                    sizes[i] += grow;
+                    free -= grow;
+                }
+            }
+        } else {
+            let mut excess = used - total;
+            for size in sizes.iter_mut().rev() {
+                let cut = excess.min(*size);
+                *size -= cut;
+                excess -= cut;
+            }
+        }
+
+        let mut position = 0;
+        sizes
+            .into_iter()
+            .map(|size| {
+                let rect = match self.direction {
+                    Direction::Horizontal => Rect::new(area.x + position, area.y, size, area.rows),
+                    Direction::Vertical => Rect::new(area.x, area.y + position, area.cols, size),
+                };
+                position += size;
+                rect
+            })
+            .collect()
+    }
+
+    /// Move every placed graphic to top left corner of it's chunk within area.
+    /// Returns ids of placed graphics together with areas they were given,
+    /// so that graphics can be rebuilt to fit them.
+    pub fn apply(&mut self, mgr: &Manager, area: Rect) -> Vec<(usize, Rect)> {
+        let mut placed = Vec::new();
+        self.apply_into(mgr, area, &mut placed);
+        placed
+    }
+
+    fn apply_into(&mut self, mgr: &Manager, area: Rect, placed: &mut Vec<(usize, Rect)>) {
+        let chunks = self.split(area);
+        for (slot, rect) in self.slots.iter_mut().zip(chunks) {
+            match slot {
+                Slot::Empty => {}
+                Slot::Graphic(graphic_id, layer, offset) => {
+                    let target = rect.offset();
+                    if target != *offset {
+                        mgr.move_graphic(
+                            *graphic_id,
+                            *layer,
+                            (target.0 - offset.0, target.1 - offset.1),
+                        );
+                        *offset = target;
+                    }
+                    placed.push((*graphic_id, rect));
+                }
+                Slot::Nested(layout) => layout.apply_into(mgr, rect, placed),
+            }
+        }
+    }
+}
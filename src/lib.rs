@@ -14,7 +14,12 @@
 //! skins/themes and keybindings makes it more likely people will stay attached to those apps.
 //!
 //! # How to use it
-//! This library uses [termios](https://docs.rs/termios/latest/termios/) as it's only dependency, which allows you to use it on multiple OSes.
+//! This library uses [termios](https://docs.rs/termios/latest/termios/) and [libc](https://docs.rs/libc/latest/libc/) as it's only dependencies, which allows you to use it on multiple OSes.
+//!
+//! Terminal is restored to it's original state when [`Manager`] is terminated or dropped,
+//! but also when application panics or receives SIGINT or SIGTERM.
+//! Errors that can not be returned, like a message not reaching screen's thread,
+//! are not printed over the screen, but passed to a hook set with [`set_error_hook`].
 //!
 //! Graphic objects can be defined and loaded as a plaintext file. Graphic's building blocks - frames
 //! are also text files, with optional ANSI escape codes that allow using colors and various styles.
@@ -38,17 +43,22 @@
 //! // You can crank refresh_timeout down, but anything below 1ms won't make a difference,
 //! // other than high CPU usage.
 //! // With default 30ms you get as high as 33 FPS, probably enough for a terminal application.
-//! let refresh_timeout = Some(Duration::from_milis(10));  
-//! let mut mgr = Manager::new(capture_keyboard, cols, rows, glyph, refresh_timeout);
+//! let refresh_timeout = Some(Duration::from_millis(10));
+//! let macros = None;
+//! let mut mgr = Manager::new(capture_keyboard, cols, rows, glyph, refresh_timeout, macros);
 //! ```
 //!
 //! Please also note that in order to see the progress you are making with this library
 //! you need to keep your program running, since animaterm makes use of an alternate terminal buffer.
 //! After your program finishes you go back to original buffer, wiping out all the graphics you have
 //! placed on the screen so far.
+//! Command line tools that should leave their output behind can use [`Manager::new_inline`] instead,
+//! which renders into a given number of rows below shell prompt, rather than into alternate buffer.
 //!
 //! In order to keep your program running you can use a loop like this:
 //! ```no_run
+//! # use animaterm::prelude::*;
+//! # let mut mgr = Manager::new(true, None, None, None, None, None);
 //! let mut keep_running = true;
 //! while keep_running {
 //!     if let Some(key) = mgr.read_key() {
@@ -81,7 +91,10 @@
 //! # Examples
 //! Please also check the [`examples`](../../../examples) directory for working examples of how to use this library.
 //! ## Create a Graphic containing multiple Frames
-//! ```
+//! ```no_run
+//! # use animaterm::prelude::*;
+//! # use std::collections::HashMap;
+//! # let mut mgr = Manager::new(true, None, None, None, None, None);
 //! let cols = 10;
 //! let rows = 5;
 //! let start_frame = 0;
@@ -99,10 +112,16 @@
 //! let layer = 0;
 //! let offset = (15, 5);
 //! let graphic_id = mgr.add_graphic(gr, layer, offset).unwrap();
-//! mgr.set_graphic(graphic_id, start_frame);
+//! mgr.set_graphic(graphic_id, start_frame, false);
 //! ```
 //! ## Add an Animation to a Graphic
-//! ```
+//! ```no_run
+//! # use animaterm::prelude::*;
+//! # use std::collections::HashMap;
+//! # let mut mgr = Manager::new(true, None, None, None, None, None);
+//! # let graphic_id = 0;
+//! # let (cols, rows, start_frame) = (10, 5, 0);
+//! # let library = HashMap::from([(0, vec![Glyph::default(); 50]), (1, vec![Glyph::plain(); 50])]);
 //! // You can define some animations upon creation of a graphic:
 //! let running = false;
 //! let looping = true;
@@ -113,7 +132,7 @@
 //!     (start_frame + 1, Timestamp::new(seconds, miliseconds)),
 //! ];
 //! let start_time = Timestamp::now();
-//! let animation = Animation::new(running, looping, ordering, start_time);
+//! let animation = Animation::new(running, looping, ordering.clone(), start_time);
 //! let mut animations = HashMap::new();
 //! let anim_id = 0;
 //! animations.insert(anim_id, animation);
@@ -121,22 +140,23 @@
 //! let mut gr = Graphic::new(cols, rows, start_frame, library, Some(animations));
 //!
 //! // Or add a new animation to existing graphic
-//! let option_anim_id = gr.add_animation(Animation::new(running, looping, ordering, start_time));
+//! let option_anim_id = gr.add_animation(Animation::new(running, looping, ordering.clone(), start_time));
 //!
-//! // You can even create additional animation via a manager
-//! mgr.add_animation(
+//! // You can even create additional animation via a manager,
+//! // returned handle waits for animation's id
+//! let pending = mgr.add_animation(
 //!     graphic_id,
 //!     Animation::new(running, looping, ordering, Timestamp::now()),
 //! );
-//!
-//! let mut var_anim_id = None;
-//!  if let Ok(AnimOk::AnimationAdded(anim_id)) = mgr.read_result() {
-//!     var_anim_id = Some(anim_id);
-//! }
+//! let var_anim_id = pending.wait().ok();
 //! ```
 //! ## Take action according to Key press
 //! For more agile solution allowing user-defined key bindings see how [studio](../../../src/bin/studio/main.rs) implements user input loop.
-//! ```
+//! ```no_run
+//! # use animaterm::prelude::*;
+//! # let mut mgr = Manager::new(true, None, None, None, None, None);
+//! # let graphic_id = 0;
+//! # let layer = 0;
 //! let mut keep_running = true;
 //! while keep_running {
 //!     if let Some(key) = mgr.read_key() {
@@ -155,18 +175,30 @@
 //!  mgr.terminate();
 //! ```
 //! ## Switch selected Graphic to a different Frame
-//! ```
+//! ```no_run
+//! # use animaterm::prelude::*;
+//! # let mut mgr = Manager::new(true, None, None, None, None, None);
+//! # let graphic_id = 0;
+//! # let frame_id = 1;
 //! // Use force wisely, since it causes entire screen to be refreshed,
 //! // thus app is becoming less responsive.
 //! let force = true;
 //! mgr.set_graphic(graphic_id, frame_id, force)
 //! ```
 //! ## Switch selected Graphic to a different Animation
-//! ```
+//! ```no_run
+//! # use animaterm::prelude::*;
+//! # let mut mgr = Manager::new(true, None, None, None, None, None);
+//! # let graphic_id = 0;
+//! # let anim_id = 0;
 //! mgr.start_animation(graphic_id, anim_id);
 //! ```
 //! ## Pause selected Animation
-//! ```
+//! ```no_run
+//! # use animaterm::prelude::*;
+//! # let mut mgr = Manager::new(true, None, None, None, None, None);
+//! # let graphic_id = 0;
+//! # let frame_id = 1;
 //! //You can pause immediately
 //! mgr.pause_animation(graphic_id);
 //!
@@ -174,74 +206,127 @@
 //! mgr.pause_animation_on_frame(graphic_id, frame_id);
 //! ```
 //! ## Switch between Displays
-//! ```
+//! ```no_run
+//! # use animaterm::prelude::*;
+//! # let mut mgr = Manager::new(true, None, None, None, None, None);
 //! let keep_existing = true;
 //! let second_display_id = mgr.new_display(keep_existing);
 //! // default display has id = 0
 //! mgr.restore_display(0, keep_existing);
 //! ```
 //! ## Change Graphic layer
-//! ```
+//! ```no_run
+//! # use animaterm::prelude::*;
+//! # let mut mgr = Manager::new(true, None, None, None, None, None);
+//! # let graphic_id = 0;
+//! # let new_layer = 2;
 //! mgr.move_graphic(graphic_id, new_layer, (0, 0));
 //! ```
 //! ## Move Graphic
-//! ```
+//! ```no_run
+//! # use animaterm::prelude::*;
+//! # let mut mgr = Manager::new(true, None, None, None, None, None);
+//! # let graphic_id = 0;
+//! # let (layer, offset_cols, offset_rows) = (0, 3, 1);
 //! mgr.move_graphic(graphic_id, layer, (offset_cols, offset_rows));
 //! ```
 //! ## Update selected Glyph
-//! ```
+//! ```no_run
+//! # use animaterm::prelude::*;
+//! # let mut mgr = Manager::new(true, None, None, None, None, None);
+//! # let graphic_id = 0;
+//! # let mut a_graphic = Graphic::new(1, 1, 0, [(0, vec![Glyph::default()])].into(), None);
+//! # let (frame_id, new_glyph, col, row, graphic_offset) = (0, Glyph::plain(), 0, 0, (0, 0));
 //! // Change a Glyph for a selected Frame
-//! a_graphic.set_frame(frame_id);
+//! a_graphic.set_frame(&frame_id, graphic_offset, false);
 //! a_graphic.set_glyph(new_glyph, col, row, graphic_offset);
 //!
 //! // Change a Glyph for current Frame of an on Screen Graphic
 //! mgr.set_glyph(graphic_id, new_glyph, col, row);
 //! ```
 //! ## Make parts of a Graphic transparent
-//! ```
+//! ```no_run
+//! # use animaterm::prelude::*;
+//! # let mut mgr = Manager::new(true, None, None, None, None, None);
+//! # let graphic_id = 0;
+//! # let mut a_graphic = Graphic::new(1, 1, 0, [(0, vec![Glyph::default()])].into(), None);
+//! # let (frame_id, col, row, graphic_offset) = (0, 0, 0, (0, 0));
 //! // Make a transparent Glyph for a selected Frame
-//! a_graphic.set_frame(frame_id, graphic_offset);
+//! a_graphic.set_frame(&frame_id, graphic_offset, false);
 //! a_graphic.set_glyph(Glyph::transparent(), col, row, graphic_offset);
 //!
 //! // Change a Glyph to transparent for current Frame of an on Screen Graphic
-//! mgr.set_glyph(graphic_id, Glyph::transparent(), col, row, graphic_offset));
+//! mgr.set_glyph(graphic_id, Glyph::transparent(), col, row);
 //! ```
 //! ## Add cloned Frame
-//! ```
+//! ```no_run
+//! # use animaterm::prelude::*;
+//! # let mut mgr = Manager::new(true, None, None, None, None, None);
+//! # let graphic_id = 0;
+//! # let mut a_graphic = Graphic::new(1, 1, 0, [(0, vec![Glyph::default()])].into(), None);
+//! # let id = 0;
 //! // In both cases empty Frame becomes current for that Graphic
 //!
-//! // if source_frame_id is None current Frame will get cloned
-//! let source_frame_id = Some(id);
-//!
 //! // Add a new Frame to a graphic directly
-//! let frame_id_option = a_graphic.clone_frame(source_frame_id);
+//! let frame_id_option = a_graphic.clone_frame(id);
 //!
-//! // Or use a Manager to do so
-//! mgr.clone_frame(graphic_id);
-//! if let Ok(AnimOk::FrameAdded(graphic_id, frame_id)) = mgr.read_result() {
+//! // Or use a Manager to do so,
+//! // if source_frame_id is None current Frame will get cloned
+//! let source_frame_id = Some(id);
+//! if let Ok(frame_id) = mgr.clone_frame(graphic_id, source_frame_id).wait() {
 //!     let added_frame_id = frame_id;
 //! }
 //! ```
 //! ## Add new Frame
-//! ```
+//! ```no_run
+//! # use animaterm::prelude::*;
+//! # let mut mgr = Manager::new(true, None, None, None, None, None);
+//! # let graphic_id = 0;
+//! # let mut a_graphic = Graphic::new(1, 1, 0, [(0, vec![Glyph::default()])].into(), None);
 //! // In both cases empty Frame becomes current for that Graphic
 //!
 //! // Add a new Frame to a graphic directly
 //! let frame_id_option = a_graphic.empty_frame();
 //!
 //! // Or use a Manager to do so
-//! mgr.empty_frame(graphic_id);
-//! if let Ok(AnimOk::FrameAdded(graphic_id, frame_id)) = mgr.read_result() {
+//! if let Ok(frame_id) = mgr.empty_frame(graphic_id).wait() {
 //!     let added_frame_id = frame_id;
 //! }
 //! ```
+//! ## Use from async code
+//! With `async` feature enabled keys and routed events are available as
+//! [Streams](https://docs.rs/futures-core/latest/futures_core/stream/trait.Stream.html)
+//! and handles returned by queries are Futures, so no thread has to block on them.
+//! ```no_run
+//! # #[cfg(feature = "async")]
+//! # async fn run(mut mgr: animaterm::Manager, graphic_id: usize) -> Result<(), animaterm::AnimError> {
+//! # use futures_core::Stream;
+//! # use std::future::poll_fn;
+//! # use std::pin::Pin;
+//! let mut keys = mgr.keys();
+//! while let Some(key) = poll_fn(|cx| Pin::new(&mut keys).poll_next(cx)).await {
+//!     // ...
+//! }
+//! let glyph = mgr.get_glyph(graphic_id, 1, 1).await?;
+//! # Ok(())
+//! # }
+//! ```
 //!
 
+mod ansi;
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod bench;
+mod bundle;
 mod error;
-pub use error::AnimError;
+mod focus;
+pub use error::{remove_error_hook, set_error_hook, AnimError};
+pub use focus::{Focusable, Routed};
 mod manager;
 pub use manager::{Manager, Message};
 mod macros;
+mod pending;
+pub use pending::{Pending, Request};
 
 mod response;
 pub use response::AnimOk;
@@ -249,40 +334,63 @@ mod screen;
 mod time;
 pub use time::Timestamp;
 mod graphic;
-pub use graphic::Graphic;
+pub use graphic::{Graphic, GraphicInfo};
 mod animation;
 pub use animation::Animation;
 mod color;
 pub use color::{Color, ColorName};
+mod cursor;
+pub use cursor::CursorShape;
 pub mod glyph;
 mod pixel;
 pub use glyph::Glyph;
+mod style;
+pub use style::Style;
 mod display;
 mod glyphcake;
 mod helpers;
 mod key;
+pub mod layout;
 pub use helpers::str_to_key;
 pub use key::Key;
+#[cfg(feature = "async")]
+mod stream;
+mod terminal;
 mod tests;
 pub mod utilities;
+mod wake;
+#[cfg(feature = "async")]
+pub use stream::{Events, Keys};
+mod viewport;
 pub use display::Display;
+pub use viewport::Viewport;
 mod frame;
 pub use frame::from_file as frame_from_file;
+pub use frame::from_reader as frame_from_reader;
+pub use frame::from_str as frame_from_str;
 
 pub mod prelude {
     pub use crate::animation::Animation;
     pub use crate::color::Color;
     pub use crate::color::ColorName;
+    pub use crate::cursor::CursorShape;
     pub use crate::error::AnimError;
+    pub use crate::focus::{Focusable, Routed};
     pub use crate::frame_from_file;
+    pub use crate::frame_from_reader;
+    pub use crate::frame_from_str;
     pub use crate::glyph::Glyph;
-    pub use crate::graphic::Graphic;
+    pub use crate::graphic::{Graphic, GraphicInfo};
     pub use crate::helpers::map_bytes_to_key;
     pub use crate::helpers::map_key_to_char;
     pub use crate::helpers::map_private_char_to_key;
     pub use crate::key::Key;
+    pub use crate::layout::{Constraint, Direction, Layout, Rect};
     pub use crate::macros::MacroSequence;
     pub use crate::manager::Manager;
+    pub use crate::pending::{Pending, Request};
     pub use crate::response::AnimOk;
+    pub use crate::style::Style;
     pub use crate::time::Timestamp;
+    pub use crate::viewport::Viewport;
 }
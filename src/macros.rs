@@ -1,6 +1,9 @@
+use crate::error::report;
 use crate::helpers::map_bytes_to_key;
+use crate::AnimError;
 
 use super::key::Key;
+use super::wake::KeyWaker;
 use std::collections::HashMap;
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::thread;
@@ -34,6 +37,8 @@ pub struct Macros {
     macros: HashMap<Key, MacroSequence>,
     pub key_recv: Receiver<Key>,
     terminate_send: Sender<()>,
+    /// Woken whenever a running macro produces a key or ends.
+    pub waker: KeyWaker,
 }
 
 impl Macros {
@@ -61,6 +66,7 @@ impl Macros {
                 macros,
                 key_recv,
                 terminate_send,
+                waker: KeyWaker::default(),
             }
         } else {
             Macros {
@@ -71,6 +77,7 @@ impl Macros {
                 macros: HashMap::new(),
                 key_recv,
                 terminate_send,
+                waker: KeyWaker::default(),
             }
         }
     }
@@ -80,26 +87,26 @@ impl Macros {
             if self.is_record_key(key) {
                 self.recording = Some((None, MacroSequence::empty(), Instant::now()))
             } else {
-                println!("Unexpected key upon recording start: {}", key);
+                report(AnimError::UnexpectedRecordKey(key.clone()));
             }
         } else if let Some((rec_key, MacroSequence(looped, mut sequence), timestamp)) =
             self.recording.take()
         {
             if self.is_record_key(key) {
-                if rec_key.is_none() {
-                    self.recording = Some((
-                        rec_key,
-                        MacroSequence::new(!looped, sequence),
-                        Instant::now(),
-                    ));
-                } else {
+                if let Some(rec_key) = rec_key {
                     if sequence.is_empty() {
-                        let _ = self.macros.remove(&rec_key.unwrap());
+                        let _ = self.macros.remove(&rec_key);
                     } else {
                         self.macros
-                            .insert(rec_key.unwrap(), MacroSequence::new(looped, sequence));
+                            .insert(rec_key, MacroSequence::new(looped, sequence));
                     }
                     self.recording = None;
+                } else {
+                    self.recording = Some((
+                        rec_key,
+                        MacroSequence::new(!looped, sequence),
+                        Instant::now(),
+                    ));
                 }
             } else if rec_key.is_none() {
                 self.recording = Some((
@@ -125,13 +132,11 @@ impl Macros {
     pub fn run(&mut self, key: &Key) -> bool {
         if self.macros.contains_key(key) {
             match self.running {
-                None => {}
-                Some(ref running_key) => {
-                    if running_key == key {
-                        self.stop();
-                        return true;
-                    }
+                Some(ref running_key) if running_key == key => {
+                    self.stop();
+                    return true;
                 }
+                _ => {}
             }
             self.stop();
             self.running = Some(key.clone());
@@ -140,6 +145,7 @@ impl Macros {
             let MacroSequence(looped, sequence) = self.macros.get(key).cloned().unwrap();
             let (terminate_send, terminate_recv) = channel();
             self.terminate_send = terminate_send;
+            let waker = self.waker.clone();
             thread::spawn(move || {
                 if looped {
                     'external: loop {
@@ -153,6 +159,7 @@ impl Macros {
                             if key_send.send(key.clone()).is_err() {
                                 break;
                             }
+                            waker.wake();
                             thread::sleep(*sleep_time);
                         }
                     }
@@ -167,10 +174,14 @@ impl Macros {
                         if key_send.send(key.clone()).is_err() {
                             break;
                         }
+                        waker.wake();
                         thread::sleep(*sleep_time);
                     }
                     drop(terminate_recv);
                 }
+                // Let a waiting task notice that macro has ended
+                drop(key_send);
+                waker.wake();
             });
             true
         } else {
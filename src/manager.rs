@@ -3,69 +3,121 @@ use crate::macros::MacroSequence;
 
 use super::animation::Animation;
 use super::color::Color;
-use super::error::AnimError;
+use super::cursor::CursorShape;
+use super::error::{report, AnimError};
+use super::focus::{FocusRing, Focusable, Routed};
 use super::glyph::Glyph;
-use super::graphic::Graphic;
+use super::graphic::{Graphic, GraphicInfo};
 use super::helpers::map_bytes_to_key;
+#[cfg(feature = "async")]
+use super::helpers::sequence_len;
 use super::key::Key;
 use super::macros::Macros;
+use super::pending::{self, Pending, Request};
 use super::response::AnimOk::{self, *};
 use super::screen::Screen;
+use super::terminal;
+use super::viewport::Viewport;
+use super::wake::KeyWaker;
 use super::Timestamp;
 
 use std::cmp::max;
-use std::io;
 use std::io::Read;
-use std::mem::replace;
 use std::path::Path;
-use std::sync::mpsc;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
 use std::time::Duration;
+#[cfg(feature = "async")]
+use std::{
+    task::{Context, Poll},
+    time::Instant,
+};
+
+/// How often keyboard thread checks whether it should pause or stop.
+const KEYBOARD_POLL_TIMEOUT: Duration = Duration::from_millis(50);
+
+// States of keyboard thread, requested by Manager
+const KEYBOARD_RUNNING: u8 = 0;
+const KEYBOARD_PAUSE: u8 = 1;
+// Set by keyboard thread itself once it no longer reads stdin
+const KEYBOARD_PAUSED: u8 = 2;
+const KEYBOARD_STOP: u8 = 3;
 
 /// Manager uses this messages internally to communicate with Screen that is running in a separate thread.
 pub enum Message {
     Finish,
-    EmptyFrame(usize),
+    EmptyFrame(usize, Request),
     ClearArea(usize, (usize, usize), (usize, usize)),
-    CloneFrame(usize, Option<usize>),
-    AddAnimation(usize, Animation),
+    CloneFrame(usize, Option<usize>, Request),
+    AddAnimation(usize, Animation, Request),
     StartAnimation(usize, usize),
     EnqueueAnimation(usize, usize, Timestamp),
     PauseAnimation(usize),
     PauseAnimationOnFrame(usize, usize),
     StopAnimation(usize),
     RestartAnimation(usize, usize, Timestamp),
-    AddGraphic(Graphic, usize, (isize, isize)),
+    AddGraphic(Graphic, usize, (isize, isize), Request),
     SetGlyph(usize, Glyph, usize, usize),
-    GetGlyph(usize, usize, usize),
+    GetGlyph(usize, usize, usize, Request),
     SetGraphic(usize, usize, bool),
     SetGraphicColor(usize, Color),
     SetGraphicBackground(usize, Color),
     SetGraphicStyle(usize, Glyph),
     SetInvisible(usize, bool),
-    SwapFrame(usize, usize, Vec<Glyph>),
+    SwapFrame(usize, usize, Vec<Glyph>, Request),
     MoveGraphic(usize, usize, (isize, isize)),
     MoveCursor(usize, usize),
-    DeleteGraphic(usize),
+    ShowCursor(bool),
+    GetGraphicInfo(usize, Request),
+    ListGraphics(Request),
+    SetCursorShape(CursorShape),
+    Suspend(Request),
+    Resume(Request),
+    DeleteGraphic(usize, Request),
     NewDisplay(usize, bool),
     RestoreDisplay(usize, bool),
-    PrintGraphic(usize, bool),
-    PrintScreen,
-    PrintScreenSection((usize, usize), usize, usize),
+    PrintGraphic(usize, bool, Request),
+    PrintScreen(Request),
+    PrintScreenSection((usize, usize), usize, usize, Request),
+    DimBelow(Option<usize>),
+    AddViewport(Viewport, usize, (isize, isize), Request),
+    ScrollViewport(usize, (isize, isize)),
+    ScrollViewportTo(usize, (usize, usize)),
+    SetViewportSource(usize, Graphic),
+    /// Apply all given messages, then composite and flush screen once.
+    Batch(Vec<Message>),
+    /// Hold printing of all following messages until a matching EndBatch.
+    BeginBatch,
+    EndBatch,
 }
 
 /// This object is responsible for orchestrating behavior of all screens and graphical elements defined.
 /// It also allows for reading user input as char.
 pub struct Manager {
-    scrn_size: (usize, usize),
-    join_handle: thread::JoinHandle<()>,
+    // updated by screen thread when terminal gets resized
+    scrn_size: Arc<Mutex<(usize, usize)>>,
+    // screen was resized and caller has not been told with Key::Resize yet
+    resized: Arc<AtomicBool>,
+    // taken when Manager shuts down
+    join_handle: Option<thread::JoinHandle<()>>,
+    key_thread: Option<thread::JoinHandle<()>>,
+    keyboard_state: Arc<AtomicU8>,
     //    next_id: usize,
     next_screen_id: usize,
     sender: mpsc::Sender<Message>,
     key_receiver: Option<mpsc::Receiver<u8>>,
     key_recv_timeout: Duration,
-    result_receiver: Option<mpsc::IntoIter<Result<AnimOk, AnimError>>>,
     macros: Macros,
+    focus: FocusRing,
+    // wakes a task awaiting keys when keyboard or a macro produces one
+    #[cfg(feature = "async")]
+    key_waker: KeyWaker,
+    // bytes received but not yet turned into keys, when reading asynchronously
+    #[cfg(feature = "async")]
+    key_buffer: Vec<u8>,
+    #[cfg(feature = "async")]
+    key_buffer_time: Instant,
 }
 
 impl Manager {
@@ -97,240 +149,257 @@ impl Manager {
         screen_refresh_timeout: Option<Duration>,
         macros: Option<Vec<(Key, MacroSequence)>>,
     ) -> Self {
-        let mut screen = Screen::new(cols, rows, glyph);
+        let screen = Screen::new(cols, rows, glyph);
+        Manager::start(screen, capture_keyboard, screen_refresh_timeout, macros)
+    }
+
+    /// Create a Manager rendering inline, below shell prompt, instead of using alternate buffer.
+    /// It occupies given number of rows and terminal's full width,
+    /// and when terminated it's final state is left in place, in terminal's scrollback.
+    /// Other arguments have the same meaning as in Manager::new.
+    pub fn new_inline(
+        capture_keyboard: bool,
+        rows: usize,
+        glyph: Option<Glyph>,
+        screen_refresh_timeout: Option<Duration>,
+        macros: Option<Vec<(Key, MacroSequence)>>,
+    ) -> Self {
+        let screen = Screen::new_inline(rows, glyph);
+        Manager::start(screen, capture_keyboard, screen_refresh_timeout, macros)
+    }
+
+    /// Take over terminal with given screen and start screen and keyboard threads.
+    pub(crate) fn start(
+        mut screen: Screen,
+        capture_keyboard: bool,
+        screen_refresh_timeout: Option<Duration>,
+        macros: Option<Vec<(Key, MacroSequence)>>,
+    ) -> Self {
         let cols = screen.cols;
         let rows = screen.rows;
         screen.initialize();
         screen.clear_screen();
         let (sender, receiver) = mpsc::channel();
-        let (result_sender, result_receiver) = mpsc::channel();
+        let scrn_size = Arc::new(Mutex::new((cols, rows)));
+        let resized = Arc::new(AtomicBool::new(false));
+        let key_waker = KeyWaker::default();
 
         // current granularity of Timestamp structure is 1ms
         let mut refresh_timeout = Duration::from_millis(30);
         if let Some(dur) = screen_refresh_timeout {
             refresh_timeout = dur;
         }
+        let size = scrn_size.clone();
+        let screen_resized = resized.clone();
+        let waker = key_waker.clone();
         let join_handle = thread::spawn(move || {
             let mut finish = false;
             while !finish {
-                if let Ok(value) = receiver.recv_timeout(refresh_timeout) {
-                    match value {
-                        Message::Finish => {
-                            finish = true;
-                        }
-                        Message::AddAnimation(gid, anim) => {
-                            let add_result = screen.add_animation(gid, anim);
-                            if let Some(id) = add_result {
-                                if result_sender.send(Result::Ok(AnimationAdded(id))).is_err() {
-                                    eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send AnimationAdded message.")
-                                }
-                            } else if result_sender
-                                .send(Result::Err(AnimError::FailAddingAnimation(gid)))
-                                .is_err()
-                            {
-                                eprintln!(
-                        "\x1b[97;41;5mERR\x1b[m Unable to send FailAddingAnimation message.")
-                            }
-                            // };
-                        }
-                        Message::StartAnimation(gid, aid) => {
-                            screen.start_animation(&gid, aid);
-                        }
-                        Message::PauseAnimation(gid) => {
-                            screen.pause_animation(gid);
-                        }
-                        Message::PauseAnimationOnFrame(gid, fid) => {
-                            screen.pause_animation_on_frame(&gid, fid);
-                        }
-                        Message::StopAnimation(gid) => {
-                            screen.stop_animation(&gid);
-                        }
-                        Message::RestartAnimation(gid, aid, when) => {
-                            screen.restart_animation(gid, aid, when);
-                        }
-                        Message::EnqueueAnimation(gid, aid, when) => {
-                            screen.enqueue_animation(&gid, aid, when);
-                        }
-                        Message::AddGraphic(gr, layer, offset) => {
-                            let graphic_id = screen.add_graphic(gr, layer, offset);
-                            if result_sender
-                                .send(Result::Ok(GraphicAdded(graphic_id)))
-                                .is_err()
-                            {
-                                eprintln!(
-                                    "\x1b[97;41;5mERR\x1b[m Failed to send GraphicAdded message"
-                                );
-                            }
-                        }
-                        Message::MoveGraphic(gid, layer, offset) => {
-                            screen.move_graphic(gid, layer, offset);
-                        }
-                        Message::SetGlyph(gid, glyph, col, row) => {
-                            screen.set_glyph(gid, glyph, col, row);
-                        }
-                        Message::SwapFrame(gid, fid, new_frame) => {
-                            if let Some(old_frame) = screen.swap_frame(gid, fid, new_frame) {
-                                let _ =
-                                    result_sender.send(Result::Ok(AnimOk::FrameSwapped(old_frame)));
-                            } else {
-                                let _ = result_sender
-                                    .send(Result::Err(AnimError::FailAddingFrame(fid)));
-                            }
-                        }
-                        Message::GetGlyph(gid, col, row) => {
-                            let result = screen.get_glyph(gid, col, row);
-                            if let Some(glyph) = result {
-                                if result_sender
-                                    .send(Result::Ok(GlyphRetrieved(gid, glyph)))
-                                    .is_err()
-                                {
-                                    eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send GlyphRetrieved message")
-                                }
-                            } else if result_sender
-                                .send(Result::Err(AnimError::FailGettingGlyph(gid)))
-                                .is_err()
-                            {
-                                eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send FailGettingGlyph message")
-                            };
-                        }
-                        Message::SetGraphic(graphic_id, frame_id, force) => {
-                            screen.set_graphic(&graphic_id, &frame_id, force);
-                        }
-                        Message::SetGraphicColor(gid, color) => {
-                            screen.set_graphic_color(gid, color);
-                        }
-                        Message::SetGraphicBackground(gid, color) => {
-                            screen.set_graphic_background(gid, color);
-                        }
-                        Message::SetGraphicStyle(gid, style) => {
-                            screen.set_graphic_style(gid, style);
-                        }
-                        Message::SetInvisible(gid, invisible) => {
-                            screen.set_invisible(gid, invisible);
-                        }
-                        Message::DeleteGraphic(gid) => {
-                            screen.delete_graphic(&gid);
-                        }
-                        Message::PrintScreen => {
-                            let text = screen.print_screen();
-                            if result_sender.send(Result::Ok(PrintScreen(text))).is_err() {
-                                eprintln!(
-                                    "\x1b[97;41;5mERR\x1b[m Unable to send PrintScreen message"
-                                )
-                            };
-                        }
-                        Message::PrintScreenSection(offset, cols, rows) => {
-                            let text = screen.print_screen_section(offset, cols, rows);
-                            if result_sender.send(Result::Ok(PrintScreen(text))).is_err() {
-                                eprintln!(
-                                    "\x1b[97;41;5mERR\x1b[m Unable to send PrintScreen message for section"
-                                )
-                            };
-                        }
-                        Message::PrintGraphic(gid, skip_border) => {
-                            let text = screen.print_graphic(gid, skip_border);
-                            if result_sender.send(Result::Ok(PrintScreen(text))).is_err() {
-                                eprintln!(
-                                    "\x1b[97;41;5mERR\x1b[m Unable to send PrintScreen message for Graphic"
-                                )
-                            };
-                        }
-                        Message::EmptyFrame(gid) => {
-                            let result = screen.empty_frame(gid);
-                            if let Some(id) = result {
-                                if result_sender.send(Result::Ok(FrameAdded(gid, id))).is_err() {
-                                    eprintln!(
-                                        "\x1b[97;41;5mERR\x1b[m Unable to send FrameAdded message"
-                                    )
-                                }
-                            } else if result_sender
-                                .send(Result::Err(AnimError::FailAddingFrame(gid)))
-                                .is_err()
-                            {
-                                eprintln!(
-                                    "\x1b[97;41;5mERR\x1b[m Unable to send FailAddingFrame message"
-                                )
-                            };
-                        }
-                        Message::ClearArea(layer, offset, size) => {
-                            let start_x: usize = max(0, offset.0) as usize;
-                            let start_y: usize = max(0, offset.1) as usize;
-                            screen.clear_area(layer, start_x, start_y, size.0, size.1);
-                        }
-                        Message::CloneFrame(gid, fid) => {
-                            let result = screen.clone_frame(gid, fid);
-                            if let Some(id) = result {
-                                if result_sender.send(Result::Ok(FrameAdded(gid, id))).is_err() {
-                                    eprintln!(
-                                        "\x1b[97;41;5mERR\x1b[m Unable to send FrameAdded message"
-                                    )
-                                }
-                            } else if result_sender
-                                .send(Result::Err(AnimError::FailAddingFrame(gid)))
-                                .is_err()
-                            {
-                                eprintln!(
-                                    "\x1b[97;41;5mERR\x1b[m Unable to send FailAddingFrame message"
-                                )
-                            };
-                        }
-                        Message::NewDisplay(display_id, keep_existing) => {
-                            // TODO deal with display_id - should it be provided by the user?
-                            let _display_id = screen.new_display(display_id, keep_existing);
-                            //result_sender.send(Result::Ok(DisplayCreated(display_id)));
-                        }
-                        Message::RestoreDisplay(display_id, keep_existing) => {
-                            // if let Some(stored_display_id) =
-                            screen.restore_display(display_id, keep_existing);
-                            // {
-                            //     result_sender.send(Result::Ok(DisplayRestored(stored_display_id)));
-                            // }
-                        }
-                        Message::MoveCursor(x, y) => {
-                            screen.print_all(vec![(x, y, Glyph::transparent())])
-                        }
+                if let Ok(message) = receiver.recv_timeout(refresh_timeout) {
+                    finish = handle_message(&mut screen, message);
+                }
+                if terminal::take_repaint() {
+                    screen.repaint();
+                }
+                if terminal::take_resized() && screen.follow_terminal_size() {
+                    if let Ok(mut size) = size.lock() {
+                        *size = (screen.cols, screen.rows);
                     }
+                    screen_resized.store(true, Ordering::SeqCst);
+                    waker.wake();
+                }
+                if !screen.is_suspended() {
+                    screen.update_graphics();
                 }
-                screen.update_graphics();
             }
             screen.cleanup();
         });
+        let keyboard_state = Arc::new(AtomicU8::new(KEYBOARD_RUNNING));
         let mut key_receiver = None;
+        let mut key_thread = None;
         if capture_keyboard {
+            let waker = key_waker.clone();
+            let state = keyboard_state.clone();
             let (key_sender, key_rcver) = mpsc::sync_channel(1024);
-            let mut reader = io::stdin();
-
-            let mut buffer = [0; 1]; // read exactly one byte
-                                     // print!("Hit a key! ");
-                                     //let mut keys_read = HashSet::with_capacity(32);
-                                     //let mut i = 0;
-            let _kb_join_handle = thread::spawn(move || {
-                let mut finish = false;
-                while !finish {
-                    if reader.read_exact(&mut buffer).is_err() {
-                        eprintln!("\x1b[97;41;5mERR\x1b[m Unable to read to buffer")
+            key_thread = Some(thread::spawn(move || {
+                loop {
+                    match state.load(Ordering::SeqCst) {
+                        KEYBOARD_STOP => break,
+                        KEYBOARD_RUNNING => {}
+                        paused => {
+                            // Stdin belongs to someone else until resumed
+                            if paused == KEYBOARD_PAUSE {
+                                let _ = state.compare_exchange(
+                                    KEYBOARD_PAUSE,
+                                    KEYBOARD_PAUSED,
+                                    Ordering::SeqCst,
+                                    Ordering::SeqCst,
+                                );
+                            }
+                            thread::sleep(KEYBOARD_POLL_TIMEOUT);
+                            continue;
+                        }
                     }
-                    if buffer[0] > 0 && key_sender.send(buffer[0]).is_err() {
-                        finish = true;
+                    // Waiting with a timeout, so that a request to pause or stop is noticed
+                    match terminal::read_byte(KEYBOARD_POLL_TIMEOUT) {
+                        Ok(Some(byte)) if byte > 0 => {
+                            if key_sender.send(byte).is_err() {
+                                break;
+                            }
+                            waker.wake();
+                        }
+                        Ok(_) => waker.idle(),
+                        Err(e) => {
+                            report(AnimError::UnableToRead(e.to_string()));
+                            break;
+                        }
                     }
                 }
-            });
+            }));
             key_receiver = Some(key_rcver);
         }
-        let macros = Macros::new(macros);
+        let mut macros = Macros::new(macros);
+        macros.waker = key_waker.clone();
         Manager {
-            scrn_size: (cols, rows),
-            join_handle,
+            scrn_size,
+            resized,
+            join_handle: Some(join_handle),
+            key_thread,
+            keyboard_state,
             //next_id: 0,
             next_screen_id: 1,
             sender,
             key_receiver,
             key_recv_timeout: Duration::from_millis(16),
-            result_receiver: Some(result_receiver.into_iter()),
             macros,
+            focus: FocusRing::default(),
+            #[cfg(feature = "async")]
+            key_waker,
+            #[cfg(feature = "async")]
+            key_buffer: Vec::new(),
+            #[cfg(feature = "async")]
+            key_buffer_time: Instant::now(),
         }
     }
 
+    /// Register a widget to receive keys passed to route_key when focused.
+    /// Keys it does not consume bubble up to it's parent.
+    /// First widget accepting focus gets focused. Returns widget's id.
+    pub fn register_widget(&mut self, widget: Box<dyn Focusable>, parent: Option<usize>) -> usize {
+        let accepts_focus = widget.accepts_focus();
+        let id = self.focus.add(widget, parent);
+        if self.focus.focused.is_none() && accepts_focus {
+            self.set_focus(id);
+        } else {
+            self.with_widget(id, |widget, mgr| widget.set_focused(mgr, false));
+        }
+        id
+    }
+
+    /// Remove a widget from focus management and give it back.
+    /// When it was focused, focus moves to it's parent.
+    pub fn unregister_widget(&mut self, id: usize) -> Option<Box<dyn Focusable>> {
+        let parent = self.focus.chain(id).get(1).copied();
+        let was_focused = self.focus.focused == Some(id);
+        let widget = self.focus.remove(id);
+        if was_focused {
+            if let Some(parent) = parent {
+                self.set_focus(parent);
+            } else if let Some(next) = self.focus.next(true) {
+                self.set_focus(next);
+            }
+        }
+        widget
+    }
+
+    /// Id of focused widget.
+    pub fn focused_widget(&self) -> Option<usize> {
+        self.focus.focused
+    }
+
+    /// Move focus to widget with given id.
+    pub fn set_focus(&mut self, id: usize) {
+        if !self.focus.contains(id) || self.focus.focused == Some(id) {
+            return;
+        }
+        if let Some(old) = self.focus.focused.replace(id) {
+            self.with_widget(old, |widget, mgr| widget.set_focused(mgr, false));
+        }
+        self.with_widget(id, |widget, mgr| widget.set_focused(mgr, true));
+    }
+
+    /// Move focus to next widget, as Tab does.
+    pub fn focus_next(&mut self) -> Option<usize> {
+        let next = self.focus.next(true)?;
+        self.set_focus(next);
+        Some(next)
+    }
+
+    /// Move focus to previous widget, as Shift-Tab does.
+    pub fn focus_previous(&mut self) -> Option<usize> {
+        let previous = self.focus.next(false)?;
+        self.set_focus(previous);
+        Some(previous)
+    }
+
+    /// Pass a key to focused widget, bubbling it up to it's parents until one consumes it.
+    /// Unconsumed Tab and Shift-Tab move focus.
+    pub fn route_key(&mut self, key: Key) -> Routed {
+        if let Some(focused) = self.focus.focused {
+            for id in self.focus.chain(focused) {
+                if self.with_widget(id, |widget, mgr| widget.handle_key(mgr, &key)) == Some(true) {
+                    return Routed::Handled(id);
+                }
+            }
+        }
+        let moved = match key {
+            Key::Tab => self.focus_next(),
+            Key::ShiftTab => self.focus_previous(),
+            _ => None,
+        };
+        match moved {
+            Some(id) => Routed::FocusMoved(id),
+            None => Routed::Unhandled(key),
+        }
+    }
+
+    /// Access a registered widget of given type.
+    pub fn widget<T: Focusable>(&self, id: usize) -> Option<&T> {
+        let widget: &dyn std::any::Any = self.focus.widget(id)?;
+        widget.downcast_ref()
+    }
+
+    /// Access a registered widget of given type for modification.
+    pub fn widget_mut<T: Focusable>(&mut self, id: usize) -> Option<&mut T> {
+        let widget: &mut dyn std::any::Any = self.focus.widget_mut(id)?;
+        widget.downcast_mut()
+    }
+
+    /// Call f with a registered widget of given type and this Manager,
+    /// e.g. to redraw a widget after changing it.
+    pub fn update_widget<T, R, F>(&mut self, id: usize, f: F) -> Option<R>
+    where
+        T: Focusable,
+        F: FnOnce(&mut T, &mut Manager) -> R,
+    {
+        self.with_widget(id, |widget, mgr| {
+            let widget: &mut dyn std::any::Any = widget;
+            widget.downcast_mut().map(|widget| f(widget, mgr))
+        })
+        .flatten()
+    }
+
+    /// Call f with a registered widget taken out, so that it can use this Manager.
+    fn with_widget<R, F>(&mut self, id: usize, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut dyn Focusable, &mut Manager) -> R,
+    {
+        let mut widget = self.focus.take(id)?;
+        let result = f(widget.as_mut(), self);
+        self.focus.put(id, widget);
+        Some(result)
+    }
+
     /// In case one has his own logic for serving raw user input from
     /// the keyboard, both for specific case or for all cases.
     pub fn get_key_receiver(&mut self) -> Option<mpsc::Receiver<u8>> {
@@ -340,7 +409,7 @@ impl Manager {
     /// Use this method to restore or provide your own key receiver for Manager to take
     /// responsibility for interpreting raw user input.
     pub fn set_key_receiver(&mut self, receiver: mpsc::Receiver<u8>) -> Option<mpsc::Receiver<u8>> {
-        replace(&mut self.key_receiver, Some(receiver))
+        self.key_receiver.replace(receiver)
     }
 
     /// Modify how long should Manager wait for bytestream coming from keyboard.
@@ -355,34 +424,116 @@ impl Manager {
         self.sender.clone()
     }
 
-    fn read_bytes(&self) -> Option<Vec<u8>> {
+    /// Is keyboard captured, so that keys can be read.
+    pub fn captures_keyboard(&self) -> bool {
+        self.key_receiver.is_some()
+    }
+
+    /// Read bytes of a single key, or None when no key came in time.
+    /// Once keyboard thread is gone key receiver is dropped,
+    /// so that callers can tell that with captures_keyboard.
+    fn read_bytes(&mut self) -> Option<Vec<u8>> {
         let mut keys_read: Vec<u8> = Vec::with_capacity(10);
         if let Some(key_rcvr) = &self.key_receiver {
             let mut all_bytes_read = false;
-            if let Ok(first_byte) = key_rcvr.recv_timeout(self.key_recv_timeout) {
-                keys_read.push(first_byte);
-                if first_byte != 27 && first_byte < 128 {
-                    all_bytes_read = true
+            let first_byte = match key_rcvr.recv_timeout(self.key_recv_timeout) {
+                Ok(first_byte) => first_byte,
+                Err(mpsc::RecvTimeoutError::Timeout) => return None,
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    self.key_receiver = None;
+                    report(AnimError::KeyboardClosed);
+                    return None;
                 }
-                while !all_bytes_read {
-                    match key_rcvr.recv_timeout(self.key_recv_timeout) {
-                        Ok(byte) => keys_read.push(byte),
-                        Err(_error) => {
-                            all_bytes_read = true;
-                        }
+            };
+            keys_read.push(first_byte);
+            if first_byte != 27 && first_byte < 128 {
+                all_bytes_read = true
+            }
+            while !all_bytes_read {
+                match key_rcvr.recv_timeout(self.key_recv_timeout) {
+                    Ok(byte) => keys_read.push(byte),
+                    Err(_error) => {
+                        all_bytes_read = true;
                     }
                 }
-            } else {
-                return None;
             }
         } else {
-            eprintln!("mgr has no key receiver!")
+            report(AnimError::KeyboardNotCaptured);
         }
         Some(keys_read)
     }
 
     /// Use this method to get a Key value of what user pressed on his keyboard.
     pub fn read_key(&mut self) -> Option<Key> {
+        if self.take_resized() {
+            return Some(Key::Resize);
+        }
+        if let Some(key) = self.macro_key() {
+            return Some(key);
+        }
+        let keys_read = self.read_bytes()?;
+        let key = map_bytes_to_key(keys_read)?;
+        self.filter_key(key)
+    }
+
+    /// Poll for a Key, registering task's waker to be woken when one arrives.
+    #[cfg(feature = "async")]
+    pub(crate) fn poll_key(&mut self, cx: &mut Context<'_>) -> Poll<Option<Key>> {
+        // Registering first, so that a key arriving while checking is not missed
+        self.key_waker.register(cx.waker());
+        loop {
+            if self.take_resized() {
+                return Poll::Ready(Some(Key::Resize));
+            }
+            if let Some(key) = self.macro_key() {
+                return Poll::Ready(Some(key));
+            }
+            let mut disconnected = true;
+            if let Some(key_rcvr) = &self.key_receiver {
+                loop {
+                    match key_rcvr.try_recv() {
+                        Ok(byte) => {
+                            self.key_buffer.push(byte);
+                            self.key_buffer_time = Instant::now();
+                        }
+                        Err(mpsc::TryRecvError::Empty) => {
+                            disconnected = false;
+                            break;
+                        }
+                        Err(mpsc::TryRecvError::Disconnected) => break,
+                    }
+                }
+            }
+            if self.key_buffer.is_empty() {
+                return if disconnected && self.macros.running.is_none() {
+                    Poll::Ready(None)
+                } else {
+                    Poll::Pending
+                };
+            }
+            let keys_read = if let Some(len) = sequence_len(&self.key_buffer) {
+                self.key_buffer.drain(..len).collect()
+            } else if !disconnected && self.key_buffer_time.elapsed() < self.key_recv_timeout {
+                // Rest of a sequence may still be coming, same as in read_key
+                // a gap of key_recv_timeout ends it, keyboard thread wakes us once it's quiet
+                self.key_waker.wake_when_idle();
+                return Poll::Pending;
+            } else {
+                std::mem::take(&mut self.key_buffer)
+            };
+            if let Some(key) = map_bytes_to_key(keys_read).and_then(|key| self.filter_key(key)) {
+                return Poll::Ready(Some(key));
+            }
+        }
+    }
+
+    /// Was screen resized since caller was last told about it.
+    fn take_resized(&self) -> bool {
+        self.resized.swap(false, Ordering::SeqCst)
+    }
+
+    /// Next key produced by a running macro, if there is one.
+    fn macro_key(&mut self) -> Option<Key> {
         if self.macros.running.is_some() && self.macros.recording.is_none() {
             let key_from_macro = self.macros.key_recv.try_recv();
             match key_from_macro {
@@ -401,29 +552,31 @@ impl Manager {
             // Stop a running macro when recording a new one
             self.macros.stop();
         }
-        let read_result = self.read_bytes();
-        if let Some(keys_read) = read_result {
-            if let Some(key) = map_bytes_to_key(keys_read) {
-                if self.macros.enabled {
-                    if self.macros.is_record_key(&key) || self.macros.recording.is_some() {
-                        self.macros.record(&key);
-                        return Some(key);
-                    } else if !self.macros.run(&key) {
-                        return Some(key);
-                    }
-                } else {
-                    return Some(key);
-                }
+        None
+    }
+
+    /// Pass a key pressed by user through macros.
+    /// Returns that key unless it has started or stopped a macro.
+    fn filter_key(&mut self, key: Key) -> Option<Key> {
+        if self.macros.enabled {
+            if self.macros.is_record_key(&key) || self.macros.recording.is_some() {
+                self.macros.record(&key);
+                Some(key)
+            } else if !self.macros.run(&key) {
+                Some(key)
+            } else {
+                None
             }
+        } else {
+            Some(key)
         }
-        None
     }
 
     /// Use this method to get a String of what user has entered up to Enter key.
     pub fn read_line(&mut self) -> String {
         let mut all_bytes: Vec<u8> = Vec::with_capacity(128);
         let mut enter_pressed = false;
-        while !enter_pressed {
+        while !enter_pressed && self.captures_keyboard() {
             if let Some(mut keys_read) = self.read_bytes() {
                 if keys_read.len() == 1 && keys_read[0] == 10 {
                     enter_pressed = true;
@@ -460,154 +613,104 @@ impl Manager {
         }
     }
 
-    /// Use this method in case you want Manager to take back servicing results on his actions.
-    pub fn set_result_iter(
-        &mut self,
-        receiver: mpsc::IntoIter<Result<AnimOk, AnimError>>,
-    ) -> Option<mpsc::IntoIter<Result<AnimOk, AnimError>>> {
-        replace(&mut self.result_receiver, Some(receiver))
-    }
-
-    /// Use this method to get next available result of Manager's action.
-    pub fn read_result(&mut self) -> Result<AnimOk, AnimError> {
-        if let Some(receiver) = &mut self.result_receiver {
-            if let Some(result) = receiver.next() {
-                return result;
-            } else {
-                return Ok(AnimOk::AllResultsRead);
-            }
-        }
-        Err(AnimError::ResultReceiverNotSet)
-    }
-
     /// Returns width & height of current screen.
+    /// It follows terminal's size, unless it was given explicitly when Manager was created.
     pub fn screen_size(&self) -> (usize, usize) {
-        self.scrn_size
+        *self.scrn_size.lock().unwrap_or_else(|e| e.into_inner())
     }
 
     /// Adds a new Animation for a Graphic. Make sure Graphic has all
     /// frames required by the Animation defined.
-    pub fn add_animation(&mut self, graphic_id: usize, anim: Animation) {
-        if self
-            .sender
-            .send(Message::AddAnimation(graphic_id, anim))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send AddAnimation message")
-        };
+    /// Returned handle provides id of added animation.
+    pub fn add_animation(&mut self, graphic_id: usize, anim: Animation) -> Pending<usize> {
+        let (request, pending) = Request::new(pending::id);
+        self.send(
+            Message::AddAnimation(graphic_id, anim, request),
+            "AddAnimation",
+        );
+        pending
     }
 
     /// Start an animation for a graphic.
     pub fn start_animation(&self, graph_id: usize, anim_id: usize) {
-        if self
-            .sender
-            .send(Message::StartAnimation(graph_id, anim_id))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send StartAnimation message")
-        };
+        self.send(Message::StartAnimation(graph_id, anim_id), "StartAnimation");
     }
 
     /// Start another animation for given graphic after current one ends.
     pub fn enqueue_animation(&self, graph_id: usize, anim_id: usize, when: Timestamp) {
-        if self
-            .sender
-            .send(Message::EnqueueAnimation(graph_id, anim_id, when))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send EnqueueAnimation message")
-        };
+        self.send(
+            Message::EnqueueAnimation(graph_id, anim_id, when),
+            "EnqueueAnimation",
+        );
     }
 
     /// Pause a running animation from given graphic.
     pub fn pause_animation(&self, graphic_id: usize) {
-        if self
-            .sender
-            .send(Message::PauseAnimation(graphic_id))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send Pauseanimation message")
-        };
+        self.send(Message::PauseAnimation(graphic_id), "PauseAnimation");
     }
 
     /// Pause a running animation from given graphic when given frame is being displayed.    
     pub fn pause_animation_on_frame(&self, graphic_id: usize, frame_id: usize) {
-        if self
-            .sender
-            .send(Message::PauseAnimationOnFrame(graphic_id, frame_id))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send PauseAnimationOnFrame message")
-        };
+        self.send(
+            Message::PauseAnimationOnFrame(graphic_id, frame_id),
+            "PauseAnimationOnFrame",
+        );
     }
 
     /// Stop animation for given graphic
     pub fn stop_animation(&self, graph_id: usize) {
-        if self.sender.send(Message::StopAnimation(graph_id)).is_err() {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send StopAnimation message")
-        };
+        self.send(Message::StopAnimation(graph_id), "StopAnimation");
     }
 
     /// Restart animation for given graphic when the right time comes.
     pub fn restart_animation(&self, graphic_id: usize, anim_id: usize, when: Timestamp) {
-        if self
-            .sender
-            .send(Message::RestartAnimation(graphic_id, anim_id, when))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send RestartAnimation message")
-        };
+        self.send(
+            Message::RestartAnimation(graphic_id, anim_id, when),
+            "RestartAnimation",
+        );
     }
 
     /// Move a graphic left or right on the screen, optionally changing which layer it is placed on.
     pub fn move_graphic(&self, graphic_id: usize, layer: usize, offset: (isize, isize)) {
-        if self
-            .sender
-            .send(Message::MoveGraphic(graphic_id, layer, offset))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send MoveGraphic message")
-        };
+        self.send(
+            Message::MoveGraphic(graphic_id, layer, offset),
+            "MoveGraphic",
+        );
     }
 
     /// Clear an area on selected layer
     pub fn clear_area(&self, layer: usize, start: (usize, usize), size: (usize, usize)) {
-        if self
-            .sender
-            .send(Message::ClearArea(layer, start, size))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send ClearArea message")
-        };
+        self.send(Message::ClearArea(layer, start, size), "ClearArea");
     }
     /// Make a graphic invisible.
     pub fn set_invisible(&self, gid: usize, invisible: bool) {
-        if self
-            .sender
-            .send(Message::SetInvisible(gid, invisible))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send SetInvisible message")
-        };
+        self.send(Message::SetInvisible(gid, invisible), "SetInvisible");
     }
 
     /// Set glyph for given graphic in specified location to provided value.
     pub fn set_glyph(&self, gid: usize, glyph: Glyph, col: usize, row: usize) {
-        if self
-            .sender
-            .send(Message::SetGlyph(gid, glyph, col, row))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send SetGlyph message")
-        };
+        self.send(Message::SetGlyph(gid, glyph, col, row), "SetGlyph");
+    }
+
+    /// Ask for a snapshot of a graphic's position, frame and animation state.
+    pub fn graphic_info(&self, gid: usize) -> Pending<GraphicInfo> {
+        let (request, pending) = Request::new(pending::info);
+        self.send(Message::GetGraphicInfo(gid, request), "GetGraphicInfo");
+        pending
+    }
+
+    /// Ask for snapshots of all graphics on current display, ordered by layer and id.
+    pub fn graphics(&self) -> Pending<Vec<GraphicInfo>> {
+        let (request, pending) = Request::new(pending::infos);
+        self.send(Message::ListGraphics(request), "ListGraphics");
+        pending
     }
 
     /// Request Manager to produce what Glyph is currently set for given graphic in specified location.
-    /// Use read_result to get that Glyph.
-    pub fn get_glyph(&self, gid: usize, col: usize, row: usize) {
-        if self.sender.send(Message::GetGlyph(gid, col, row)).is_err() {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send GetGlyph message")
-        };
+    pub fn get_glyph(&self, gid: usize, col: usize, row: usize) -> Pending<Glyph> {
+        let (request, pending) = Request::new(pending::glyph);
+        self.send(Message::GetGlyph(gid, col, row, request), "GetGlyph");
+        pending
     }
 
     /// Use this method to load a graphic from plain text file.
@@ -624,60 +727,79 @@ impl Manager {
     where
         P: AsRef<Path> + std::fmt::Debug,
     {
-        if let Some(graphic) = Graphic::from_file(filename) {
-            Ok(AnimOk::GraphicCreated(graphic))
-        } else {
-            Err(AnimError::UnableToBuildGraphicFromFile)
-        }
+        Graphic::from_file(filename).map(AnimOk::GraphicCreated)
     }
 
-    /// Add an empty frame to a graphic.
-    pub fn empty_frame(&self, gid: usize) {
-        if self.sender.send(Message::EmptyFrame(gid)).is_err() {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send EmptyFrame message")
-        };
+    /// Load a graphic defined the same way as in load_graphic_from_file, but from a reader.
+    /// Resolver is called with every frame's reference and should return that frame.
+    pub fn load_graphic_from_reader<R, F>(
+        &self,
+        reader: R,
+        resolver: F,
+    ) -> Result<AnimOk, AnimError>
+    where
+        R: Read,
+        F: FnMut(&str) -> Result<(usize, Vec<Glyph>), AnimError>,
+    {
+        Graphic::from_reader(reader, resolver).map(AnimOk::GraphicCreated)
     }
 
-    /// Add a cloned frame to a graphic.
-    pub fn clone_frame(&self, graphic_id: usize, frame_id: Option<usize>) {
-        if self
-            .sender
-            .send(Message::CloneFrame(graphic_id, frame_id))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send ClearArea message")
-        };
+    /// Load a graphic defined the same way as in load_graphic_from_file, but from text.
+    /// Resolver is called with every frame's reference and should return that frame.
+    pub fn load_graphic_from_str<F>(&self, text: &str, resolver: F) -> Result<AnimOk, AnimError>
+    where
+        F: FnMut(&str) -> Result<(usize, Vec<Glyph>), AnimError>,
+    {
+        Graphic::from_str(text, resolver).map(AnimOk::GraphicCreated)
     }
 
-    /// Create a new clean display, optionally keeping current one.
+    /// Add an empty frame to a graphic, returned handle provides id of that frame.
+    pub fn empty_frame(&self, gid: usize) -> Pending<usize> {
+        let (request, pending) = Request::new(pending::id);
+        self.send(Message::EmptyFrame(gid, request), "EmptyFrame");
+        pending
+    }
+
+    /// Add a cloned frame to a graphic, returned handle provides id of that frame.
+    pub fn clone_frame(&self, graphic_id: usize, frame_id: Option<usize>) -> Pending<usize> {
+        let (request, pending) = Request::new(pending::id);
+        self.send(
+            Message::CloneFrame(graphic_id, frame_id, request),
+            "CloneFrame",
+        );
+        pending
+    }
+
+    /// Place terminal's cursor at given column and row, both starting from 1.
+    /// Cursor stays there after every screen update, when shown with show_cursor.
     pub fn move_cursor(&self, x: usize, y: usize) {
-        if self.sender.send(Message::MoveCursor(x, y)).is_err() {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send MoveCursor message")
-        };
+        self.send(Message::MoveCursor(x, y), "MoveCursor");
+    }
+
+    /// Show or hide terminal's cursor, it is hidden by default.
+    pub fn show_cursor(&self, visible: bool) {
+        self.send(Message::ShowCursor(visible), "ShowCursor");
     }
+
+    /// Change shape of terminal's cursor, e.g. to a blinking bar for text editing.
+    pub fn set_cursor_shape(&self, shape: CursorShape) {
+        self.send(Message::SetCursorShape(shape), "SetCursorShape");
+    }
+
     /// Create a new clean display, optionally keeping current one.
     pub fn new_display(&mut self, keep_existing: bool) -> usize {
         let new_id = self.next_screen_id;
         self.next_screen_id += 1;
-        if self
-            .sender
-            .send(Message::NewDisplay(new_id, keep_existing))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send NewDisplay message")
-        };
+        self.send(Message::NewDisplay(new_id, keep_existing), "NewDisplay");
         new_id
     }
 
     /// Set display to a different one.
     pub fn restore_display(&mut self, display_id: usize, keep_existing: bool) {
-        if self
-            .sender
-            .send(Message::RestoreDisplay(display_id, keep_existing))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send RestoreDisplay message")
-        };
+        self.send(
+            Message::RestoreDisplay(display_id, keep_existing),
+            "RestoreDisplay",
+        );
     }
 
     /// Add a graphic to current display.
@@ -687,67 +809,66 @@ impl Manager {
         layer: usize,
         offset: (isize, isize),
     ) -> Option<usize> {
-        // let gid = self.next_id;
-        // self.next_id += 1;
-        if self
-            .sender
-            .send(Message::AddGraphic(gr, layer, offset))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send AddGraphic message")
-        };
-        //TODO gid should be returned by Screen
-        let result = self.read_result();
-        if let Ok(AnimOk::GraphicAdded(gid)) = result {
-            return Some(gid);
-        } else {
-            eprintln!("Unable to read GraphicAdded message");
-        }
-        None
+        let (request, pending) = Request::new(pending::id);
+        self.send(
+            Message::AddGraphic(gr, layer, offset, request),
+            "AddGraphic",
+        );
+        pending.wait().ok()
+    }
+
+    /// Place a viewport on current display, returning id of graphic presenting it.
+    /// That id can be used like any other graphic's, e.g. to move or hide a viewport.
+    pub fn add_viewport(
+        &mut self,
+        viewport: Viewport,
+        layer: usize,
+        offset: (isize, isize),
+    ) -> Option<usize> {
+        let (request, pending) = Request::new(pending::id);
+        self.send(
+            Message::AddViewport(viewport, layer, offset, request),
+            "AddViewport",
+        );
+        pending.wait().ok()
+    }
+
+    /// Scroll a viewport by given number of columns and rows, negative values scroll back.
+    pub fn scroll_viewport(&self, gid: usize, delta: (isize, isize)) {
+        self.send(Message::ScrollViewport(gid, delta), "ScrollViewport");
+    }
+
+    /// Scroll a viewport so that given column and row of it's source is in top left corner.
+    pub fn scroll_viewport_to(&self, gid: usize, position: (usize, usize)) {
+        self.send(Message::ScrollViewportTo(gid, position), "ScrollViewportTo");
+    }
+
+    /// Replace graphic shown through a viewport, e.g. when a log has grown.
+    pub fn set_viewport_source(&self, gid: usize, source: Graphic) {
+        self.send(Message::SetViewportSource(gid, source), "SetViewportSource");
     }
 
     /// Set a graphic to display a particular frame.
     pub fn set_graphic(&self, gid: usize, fid: usize, force: bool) {
-        if self
-            .sender
-            .send(Message::SetGraphic(gid, fid, force))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send SetGraphic message")
-        };
+        self.send(Message::SetGraphic(gid, fid, force), "SetGraphic");
     }
 
     /// Set color of all glyphs under current graphic's frame to given value.
     pub fn set_graphic_color(&self, gid: usize, color: Color) {
-        if self
-            .sender
-            .send(Message::SetGraphicColor(gid, color))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send SetGraphicColor message")
-        };
+        self.send(Message::SetGraphicColor(gid, color), "SetGraphicColor");
     }
 
     /// Set background color of all glyphs under current graphic's frame to given value.
     pub fn set_graphic_background(&self, gid: usize, color: Color) {
-        if self
-            .sender
-            .send(Message::SetGraphicBackground(gid, color))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send SetGraphicBackground message")
-        };
+        self.send(
+            Message::SetGraphicBackground(gid, color),
+            "SetGraphicBackground",
+        );
     }
 
     /// Set style of all glyphs under current graphic's frame to given value.
     pub fn set_graphic_style(&self, gid: usize, glyph: Glyph) {
-        if self
-            .sender
-            .send(Message::SetGraphicStyle(gid, glyph))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send SetGraphicStyle message")
-        };
+        self.send(Message::SetGraphicStyle(gid, glyph), "SetGraphicStyle");
     }
 
     /// Change an existing Frame in Graphic's library
@@ -757,62 +878,330 @@ impl Manager {
         f_id: usize,
         new_frame: Vec<Glyph>,
     ) -> Option<Vec<Glyph>> {
-        if self
-            .sender
-            .send(Message::SwapFrame(g_id, f_id, new_frame))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send SwapFrame message")
-        };
-        let result = self.read_result();
-        if let Ok(AnimOk::FrameSwapped(old_frame)) = result {
-            return Some(old_frame);
-        }
-        None
+        let (request, pending) = Request::new(pending::frame);
+        self.send(
+            Message::SwapFrame(g_id, f_id, new_frame, request),
+            "SwapFrame",
+        );
+        pending.wait().ok()
     }
-    /// Delete a graphic from current display.
-    pub fn delete_graphic(&self, gid: usize) {
-        if self.sender.send(Message::DeleteGraphic(gid)).is_err() {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send DeleteGraphic message")
-        };
+    /// Print everything placed on layers below given one dimmed,
+    /// e.g. to bring attention to a dialog. None stops dimming.
+    pub fn dim_below(&self, layer: Option<usize>) {
+        self.send(Message::DimBelow(layer), "DimBelow");
+    }
+
+    /// Delete a graphic with it's animations, from current display or a stored one.
+    /// Returned handle provides id of deleted graphic once it is gone.
+    /// Id can be given to a new graphic later on, but with a different generation,
+    /// so that using a stale id never affects that new graphic.
+    pub fn delete_graphic(&self, gid: usize) -> Pending<usize> {
+        let (request, pending) = Request::new(pending::id);
+        self.send(Message::DeleteGraphic(gid, request), "DeleteGraphic");
+        pending
     }
 
     /// Request Manager to provide a String of given graphic for manipulation or permanent storage.
-    pub fn print_graphic(&self, gid: usize, skip_border: bool) {
-        if self
-            .sender
-            .send(Message::PrintGraphic(gid, skip_border))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send PrintGraphic message")
-        };
+    pub fn print_graphic(&self, gid: usize, skip_border: bool) -> Pending<Vec<String>> {
+        let (request, pending) = Request::new(pending::text);
+        self.send(
+            Message::PrintGraphic(gid, skip_border, request),
+            "PrintGraphic",
+        );
+        pending
     }
 
     /// Request Manager to provide a String of entire screen for manipulation or permanent storage.
-    pub fn print_screen(&self) {
-        if self.sender.send(Message::PrintScreen).is_err() {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send PrintScreen message")
-        };
+    pub fn print_screen(&self) -> Pending<Vec<String>> {
+        let (request, pending) = Request::new(pending::text);
+        self.send(Message::PrintScreen(request), "PrintScreen");
+        pending
     }
 
     /// Request Manager to provide a String of selected screen section for manipulation or permanent storage.
-    pub fn print_screen_section(&self, offset: (usize, usize), cols: usize, rows: usize) {
-        if self
-            .sender
-            .send(Message::PrintScreenSection(offset, cols, rows))
-            .is_err()
-        {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send PrintScreenSection message")
-        };
+    pub fn print_screen_section(
+        &self,
+        offset: (usize, usize),
+        cols: usize,
+        rows: usize,
+    ) -> Pending<Vec<String>> {
+        let (request, pending) = Request::new(pending::text);
+        self.send(
+            Message::PrintScreenSection(offset, cols, rows, request),
+            "PrintScreenSection",
+        );
+        pending
     }
 
     /// Restore terminal to regular buffer when application is about to quit.
-    pub fn terminate(self) {
-        if self.sender.send(Message::Finish).is_err() {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Unable to send Finish message")
-        };
-        if self.join_handle.join().is_err() {
-            eprintln!("\x1b[97;41;5mERR\x1b[m Failed to join thread")
-        };
+    /// Dropping a Manager does the same.
+    pub fn terminate(mut self) {
+        self.shutdown();
+    }
+
+    /// Give terminal back to user, e.g. before running an external editor:
+    /// original terminal settings and main buffer are restored,
+    /// keys are no longer read and nothing is drawn.
+    /// Graphics can still be modified, all of it shows up once resume is called.
+    pub fn suspend(&mut self) {
+        if self.key_thread.is_some() {
+            self.keyboard_state.store(KEYBOARD_PAUSE, Ordering::SeqCst);
+            // Waiting for keyboard thread to let go of stdin
+            while self.keyboard_state.load(Ordering::SeqCst) == KEYBOARD_PAUSE
+                && self.key_thread.as_ref().is_some_and(|t| !t.is_finished())
+            {
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+        let (request, pending) = Request::new(pending::suspended);
+        self.send(Message::Suspend(request), "Suspend");
+        let _ = pending.wait();
+    }
+
+    /// Take terminal back over after suspend, repainting current display.
+    pub fn resume(&mut self) {
+        let (request, pending) = Request::new(pending::suspended);
+        self.send(Message::Resume(request), "Resume");
+        let _ = pending.wait();
+        self.keyboard_state
+            .store(KEYBOARD_RUNNING, Ordering::SeqCst);
+    }
+
+    /// Apply every change made within given closure at once, so that screen
+    /// gets composited and flushed a single time, without intermediate states showing.
+    /// Messages reach screen in order they were sent, so queries made within
+    /// closure can be waited for, they see changes made before them.
+    pub fn batch<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut Manager) -> R,
+    {
+        self.send(Message::BeginBatch, "BeginBatch");
+        // Ending batch even if f panics, so that screen does not stop printing for good
+        let _end = BatchEnd(self.sender.clone());
+        f(self)
+    }
+
+    /// Send a message to Screen's thread, reporting a failure to error hook.
+    fn send(&self, message: Message, kind: &'static str) {
+        if self.sender.send(message).is_err() {
+            report(AnimError::MessageNotSent(kind));
+        }
+    }
+
+    /// Stop screen and keyboard threads, waiting for them to finish.
+    fn shutdown(&mut self) {
+        if let Some(join_handle) = self.join_handle.take() {
+            self.send(Message::Finish, "Finish");
+            if join_handle.join().is_err() {
+                report(AnimError::ThreadFailed("screen"));
+            };
+        }
+        self.keyboard_state.store(KEYBOARD_STOP, Ordering::SeqCst);
+        if let Some(key_thread) = self.key_thread.take() {
+            if key_thread.join().is_err() {
+                report(AnimError::ThreadFailed("keyboard"));
+            };
+        }
+        // In case screen thread has died without cleaning up
+        terminal::restore();
+    }
+}
+
+/// Tells Screen a batch has ended once dropped.
+struct BatchEnd(mpsc::Sender<Message>);
+
+impl Drop for BatchEnd {
+    fn drop(&mut self) {
+        if self.0.send(Message::EndBatch).is_err() {
+            report(AnimError::MessageNotSent("EndBatch"));
+        }
+    }
+}
+
+impl Drop for Manager {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Apply a message on Screen's thread, returning true when it is time to finish.
+fn handle_message(screen: &mut Screen, message: Message) -> bool {
+    match message {
+        Message::Finish => return true,
+        Message::Batch(messages) => {
+            screen.begin_batch();
+            let mut finish = false;
+            for message in messages {
+                finish |= handle_message(screen, message);
+            }
+            screen.end_batch();
+            return finish;
+        }
+        Message::BeginBatch => screen.begin_batch(),
+        Message::EndBatch => screen.end_batch(),
+        Message::AddAnimation(gid, anim, request) => {
+            let result = match screen.add_animation(gid, anim) {
+                Some(id) => Ok(AnimationAdded(id)),
+                None => Err(screen.graphic_error(gid, AnimError::FailAddingAnimation(gid))),
+            };
+            request.respond(result);
+        }
+        Message::StartAnimation(gid, aid) => {
+            screen.start_animation(&gid, aid);
+        }
+        Message::PauseAnimation(gid) => {
+            screen.pause_animation(gid);
+        }
+        Message::PauseAnimationOnFrame(gid, fid) => {
+            screen.pause_animation_on_frame(&gid, fid);
+        }
+        Message::StopAnimation(gid) => {
+            screen.stop_animation(&gid);
+        }
+        Message::RestartAnimation(gid, aid, when) => {
+            screen.restart_animation(gid, aid, when);
+        }
+        Message::EnqueueAnimation(gid, aid, when) => {
+            screen.enqueue_animation(&gid, aid, when);
+        }
+        Message::AddGraphic(gr, layer, offset, request) => {
+            let result = screen
+                .add_graphic(gr, layer, offset)
+                .map(GraphicAdded)
+                .ok_or(AnimError::TooManyGraphics);
+            request.respond(result);
+        }
+        Message::AddViewport(viewport, layer, offset, request) => {
+            let result = screen
+                .add_viewport(viewport, layer, offset)
+                .map(GraphicAdded)
+                .ok_or(AnimError::TooManyGraphics);
+            request.respond(result);
+        }
+        Message::ScrollViewport(gid, delta) => {
+            screen.scroll_viewport(gid, delta);
+        }
+        Message::ScrollViewportTo(gid, position) => {
+            screen.scroll_viewport_to(gid, position);
+        }
+        Message::SetViewportSource(gid, source) => {
+            screen.set_viewport_source(gid, source);
+        }
+        Message::MoveGraphic(gid, layer, offset) => {
+            screen.move_graphic(gid, layer, offset);
+        }
+        Message::SetGlyph(gid, glyph, col, row) => {
+            screen.set_glyph(gid, glyph, col, row);
+        }
+        Message::SwapFrame(gid, fid, new_frame, request) => {
+            let result = match screen.swap_frame(gid, fid, new_frame) {
+                Some(old_frame) => Ok(FrameSwapped(old_frame)),
+                None => Err(screen.graphic_error(gid, AnimError::FrameNotFound(fid))),
+            };
+            request.respond(result);
+        }
+        Message::GetGlyph(gid, col, row, request) => {
+            let result = match screen.get_glyph(gid, col, row) {
+                Some(glyph) => Ok(GlyphRetrieved(gid, glyph)),
+                None => Err(screen.graphic_error(gid, AnimError::FailGettingGlyph(gid))),
+            };
+            request.respond(result);
+        }
+        Message::SetGraphic(graphic_id, frame_id, force) => {
+            screen.set_graphic(&graphic_id, &frame_id, force);
+        }
+        Message::SetGraphicColor(gid, color) => {
+            screen.set_graphic_color(gid, color);
+        }
+        Message::SetGraphicBackground(gid, color) => {
+            screen.set_graphic_background(gid, color);
+        }
+        Message::SetGraphicStyle(gid, style) => {
+            screen.set_graphic_style(gid, style);
+        }
+        Message::SetInvisible(gid, invisible) => {
+            screen.set_invisible(gid, invisible);
+        }
+        Message::DeleteGraphic(gid, request) => {
+            let result = if screen.delete_graphic(&gid) {
+                Ok(GraphicDeleted(gid))
+            } else {
+                Err(AnimError::UnknownGraphic(gid))
+            };
+            request.respond(result);
+        }
+        Message::DimBelow(layer) => {
+            screen.dim_below(layer);
+        }
+        Message::PrintScreen(request) => {
+            request.respond(Ok(PrintScreen(screen.print_screen())));
+        }
+        Message::PrintScreenSection(offset, cols, rows, request) => {
+            let text = screen.print_screen_section(offset, cols, rows);
+            request.respond(Ok(PrintScreen(text)));
+        }
+        Message::PrintGraphic(gid, skip_border, request) => {
+            let text = screen.print_graphic(gid, skip_border);
+            request.respond(Ok(PrintScreen(text)));
+        }
+        Message::EmptyFrame(gid, request) => {
+            let result = match screen.empty_frame(gid) {
+                Some(id) => Ok(FrameAdded(gid, id)),
+                None => Err(screen.graphic_error(gid, AnimError::FailAddingFrame(gid))),
+            };
+            request.respond(result);
+        }
+        Message::ClearArea(layer, offset, size) => {
+            let start_x: usize = max(0, offset.0) as usize;
+            let start_y: usize = max(0, offset.1) as usize;
+            screen.clear_area(layer, start_x, start_y, size.0, size.1);
+        }
+        Message::CloneFrame(gid, fid, request) => {
+            let result = match screen.clone_frame(gid, fid) {
+                Some(id) => Ok(FrameAdded(gid, id)),
+                None => Err(screen.graphic_error(gid, AnimError::FailAddingFrame(gid))),
+            };
+            request.respond(result);
+        }
+        Message::NewDisplay(display_id, keep_existing) => {
+            // TODO deal with display_id - should it be provided by the user?
+            let _display_id = screen.new_display(display_id, keep_existing);
+            //result_sender.send(Result::Ok(DisplayCreated(display_id)));
+        }
+        Message::RestoreDisplay(display_id, keep_existing) => {
+            // if let Some(stored_display_id) =
+            screen.restore_display(display_id, keep_existing);
+            // {
+            //     result_sender.send(Result::Ok(DisplayRestored(stored_display_id)));
+            // }
+        }
+        Message::Suspend(request) => {
+            screen.suspend();
+            request.respond(Ok(Suspended(true)));
+        }
+        Message::Resume(request) => {
+            screen.resume();
+            request.respond(Ok(Suspended(false)));
+        }
+        Message::MoveCursor(x, y) => {
+            screen.place_cursor(x, y);
+        }
+        Message::GetGraphicInfo(gid, request) => {
+            let result = match screen.graphic_info(gid) {
+                Some(info) => Ok(AnimOk::GraphicInfo(info)),
+                None => Err(AnimError::UnknownGraphic(gid)),
+            };
+            request.respond(result);
+        }
+        Message::ListGraphics(request) => {
+            request.respond(Ok(GraphicsListed(screen.graphics_info())));
+        }
+        Message::ShowCursor(visible) => {
+            screen.show_cursor(visible);
+        }
+        Message::SetCursorShape(shape) => {
+            screen.set_cursor_shape(shape);
+        }
     }
+    false
 }
@@ -0,0 +1,218 @@
+use super::error::AnimError;
+use super::glyph::Glyph;
+use super::graphic::GraphicInfo;
+use super::response::AnimOk;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::task::Waker;
+use std::time::{Duration, Instant};
+
+// Ids are unique among all requests, no matter which Manager or sender they come from
+static NEXT_REQUEST_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Where a response is put, shared by a request and it's handle.
+#[derive(Debug, Default)]
+struct Slot {
+    result: Option<Result<AnimOk, AnimError>>,
+    // Request was answered or dropped, nothing more will come
+    closed: bool,
+    // Task awaiting response as a future
+    waker: Option<Waker>,
+}
+
+type Shared = Arc<(Mutex<Slot>, Condvar)>;
+
+/// Part of a Message that expects a response, telling Screen where to send it.
+/// Every request gets a unique correlation id, shared with it's Pending handle.
+#[derive(Debug)]
+pub struct Request {
+    id: usize,
+    shared: Shared,
+}
+
+impl Request {
+    /// Create a new request together with a handle for receiving it's response.
+    /// Extract picks wanted value out of a successful response.
+    pub fn new<T>(extract: fn(AnimOk) -> Option<T>) -> (Request, Pending<T>) {
+        let id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+        let shared = Shared::default();
+        (
+            Request {
+                id,
+                shared: shared.clone(),
+            },
+            Pending {
+                id,
+                shared,
+                extract,
+            },
+        )
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Send response to whoever is holding Pending handle.
+    pub fn respond(self, result: Result<AnimOk, AnimError>) {
+        lock(&self.shared).result = Some(result);
+        // Dropping self notifies waiting handle
+    }
+}
+
+impl Drop for Request {
+    fn drop(&mut self) {
+        let mut slot = lock(&self.shared);
+        slot.closed = true;
+        if let Some(waker) = slot.waker.take() {
+            waker.wake();
+        }
+        self.shared.1.notify_all();
+    }
+}
+
+/// A handle for a response to a single request sent to Screen.
+/// Only response to that particular request is ever received through it.
+/// With `async` feature enabled it is also a Future resolving to that response.
+#[derive(Debug)]
+pub struct Pending<T> {
+    id: usize,
+    shared: Shared,
+    extract: fn(AnimOk) -> Option<T>,
+}
+
+impl<T> Pending<T> {
+    /// Correlation id of request this handle is waiting for.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Block until response arrives.
+    pub fn wait(self) -> Result<T, AnimError> {
+        let mut slot = lock(&self.shared);
+        while !slot.closed {
+            slot = self
+                .shared
+                .1
+                .wait(slot)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+        self.convert(slot.result.take())
+    }
+
+    /// Block until response arrives, but no longer than given time.
+    /// Returns None on timeout, so that it can be waited for again.
+    pub fn wait_timeout(&self, timeout: Duration) -> Option<Result<T, AnimError>> {
+        let deadline = Instant::now() + timeout;
+        let mut slot = lock(&self.shared);
+        while !slot.closed {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return None;
+            }
+            slot = self
+                .shared
+                .1
+                .wait_timeout(slot, left)
+                .map(|(slot, _)| slot)
+                .unwrap_or_else(|poisoned| poisoned.into_inner().0);
+        }
+        Some(self.convert(slot.result.take()))
+    }
+
+    /// Get response if it has already arrived, without blocking.
+    pub fn try_get(&self) -> Option<Result<T, AnimError>> {
+        let mut slot = lock(&self.shared);
+        if slot.closed {
+            Some(self.convert(slot.result.take()))
+        } else {
+            None
+        }
+    }
+
+    fn convert(&self, result: Option<Result<AnimOk, AnimError>>) -> Result<T, AnimError> {
+        match result {
+            Some(result) => (self.extract)(result?).ok_or(AnimError::NoResponse(self.id)),
+            None => Err(AnimError::NoResponse(self.id)),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> std::future::Future for Pending<T> {
+    type Output = Result<T, AnimError>;
+
+    fn poll(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        let mut slot = lock(&self.shared);
+        if slot.closed {
+            let result = slot.result.take();
+            drop(slot);
+            return std::task::Poll::Ready(self.convert(result));
+        }
+        slot.waker = Some(cx.waker().clone());
+        std::task::Poll::Pending
+    }
+}
+
+// A panic while holding the lock can not leave a Slot in an invalid state
+fn lock(shared: &Shared) -> MutexGuard<'_, Slot> {
+    shared
+        .0
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub(crate) fn glyph(ok: AnimOk) -> Option<Glyph> {
+    match ok {
+        AnimOk::GlyphRetrieved(_gid, glyph) => Some(glyph),
+        _ => None,
+    }
+}
+
+pub(crate) fn id(ok: AnimOk) -> Option<usize> {
+    match ok {
+        AnimOk::AnimationAdded(id)
+        | AnimOk::GraphicAdded(id)
+        | AnimOk::GraphicDeleted(id)
+        | AnimOk::FrameAdded(_, id) => Some(id),
+        _ => None,
+    }
+}
+
+pub(crate) fn text(ok: AnimOk) -> Option<Vec<String>> {
+    match ok {
+        AnimOk::PrintScreen(text) => Some(text),
+        _ => None,
+    }
+}
+
+pub(crate) fn frame(ok: AnimOk) -> Option<Vec<Glyph>> {
+    match ok {
+        AnimOk::FrameSwapped(frame) => Some(frame),
+        _ => None,
+    }
+}
+
+pub(crate) fn suspended(ok: AnimOk) -> Option<bool> {
+    match ok {
+        AnimOk::Suspended(suspended) => Some(suspended),
+        _ => None,
+    }
+}
+
+pub(crate) fn info(ok: AnimOk) -> Option<GraphicInfo> {
+    match ok {
+        AnimOk::GraphicInfo(info) => Some(info),
+        _ => None,
+    }
+}
+
+pub(crate) fn infos(ok: AnimOk) -> Option<Vec<GraphicInfo>> {
+    match ok {
+        AnimOk::GraphicsListed(infos) => Some(infos),
+        _ => None,
+    }
+}
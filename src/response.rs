@@ -1,7 +1,9 @@
 use super::Glyph;
 use super::Graphic;
+use super::GraphicInfo;
 #[derive(Debug)]
 /// List of successful results after performing an action.
+#[allow(clippy::large_enum_variant)]
 pub enum AnimOk {
     AnimationAdded(usize),
     FrameAdded(usize, usize),
@@ -10,7 +12,12 @@ pub enum AnimOk {
     DisplayRestored(usize),
     GlyphRetrieved(usize, Glyph),
     GraphicAdded(usize),
+    GraphicDeleted(usize),
     GraphicCreated(Graphic),
     PrintScreen(Vec<String>),
     FrameSwapped(Vec<Glyph>),
+    /// Terminal was given back to user (true) or taken back over (false).
+    Suspended(bool),
+    GraphicInfo(GraphicInfo),
+    GraphicsListed(Vec<GraphicInfo>),
 }
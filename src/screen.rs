@@ -1,10 +1,15 @@
 use super::animation::Animation;
 use super::color::Color;
+use super::cursor::CursorShape;
 use super::display::Display;
-use super::graphic::Graphic;
+use super::error::{report, AnimError};
+use super::graphic::{Graphic, GraphicInfo};
 use super::helpers::ask_os_for_rows_and_cols;
 use super::pixel::Pixel;
+use super::style::{self, Appearance};
+use super::terminal;
 use super::time::Timestamp;
+use super::viewport::Viewport;
 use super::Glyph;
 use std::cmp::{max, min};
 use std::collections::HashMap;
@@ -15,6 +20,79 @@ use termios::{tcsetattr, Termios, ECHO, ICANON, TCSANOW};
 
 struct ShelvedItem(Display, HashMap<usize, (Graphic, usize, (isize, isize))>);
 
+// Lower half of a graphic id is a slot, reused once it's graphic gets deleted,
+// upper half is slot's generation, so that a stale id never refers to a newer graphic
+const SLOT_BITS: u32 = usize::BITS / 2;
+const SLOT_MASK: usize = (1 << SLOT_BITS) - 1;
+
+/// Source of graphic ids, reusing ids of deleted graphics with their generation advanced.
+#[derive(Debug, Default)]
+pub struct GraphicIds {
+    // generation of every slot ever used
+    generations: Vec<usize>,
+    // slots of deleted graphics, ready for reuse
+    free_slots: Vec<usize>,
+}
+
+impl GraphicIds {
+    /// Get an id for a new graphic, None once every slot is taken.
+    pub fn next_id(&mut self) -> Option<usize> {
+        if let Some(slot) = self.free_slots.pop() {
+            let generation = (self.generations[slot] + 1) & SLOT_MASK;
+            self.generations[slot] = generation;
+            Some(slot | generation << SLOT_BITS)
+        } else if self.generations.len() <= SLOT_MASK {
+            self.generations.push(0);
+            Some(self.generations.len() - 1)
+        } else {
+            None
+        }
+    }
+
+    /// Make id's slot available again, unless id is stale.
+    pub fn free(&mut self, id: usize) {
+        let slot = id & SLOT_MASK;
+        if self.generations.get(slot) == Some(&(id >> SLOT_BITS))
+            && !self.free_slots.contains(&slot)
+        {
+            self.free_slots.push(slot);
+        }
+    }
+}
+
+/// Tracks batches of changes being applied, so that printing is deferred until all end.
+#[derive(Debug, Default)]
+pub struct BatchState {
+    // how many batches are being applied
+    depth: usize,
+    // did any change in them require printing everything
+    force: bool,
+}
+
+impl BatchState {
+    pub fn begin(&mut self) {
+        self.depth += 1;
+    }
+
+    /// Is a batch being applied, remembering whether it needs everything printed.
+    pub fn defer(&mut self, force: bool) -> bool {
+        if self.depth > 0 {
+            self.force |= force;
+        }
+        self.depth > 0
+    }
+
+    /// Once outermost batch ends, tell whether everything should be printed.
+    pub fn end(&mut self) -> Option<bool> {
+        self.depth = self.depth.saturating_sub(1);
+        if self.depth == 0 {
+            Some(replace(&mut self.force, false))
+        } else {
+            None
+        }
+    }
+}
+
 pub struct Screen {
     pub rows: usize,
     pub cols: usize,
@@ -28,35 +106,44 @@ pub struct Screen {
     shelve: HashMap<usize, ShelvedItem>,
     shelve_id: usize,
     time: Timestamp,
-    next_available_id: usize,
+    graphic_ids: GraphicIds,
     // animations: HashMap<usize, (Animation, usize, (usize, usize))>,
     graphics: HashMap<usize, (Graphic, usize, (isize, isize))>,
     stdin: i32,
-    stdout: io::Stdout,
-    termios_orig: Termios,
-    termios: Termios,
-    c_color: Color,
-    c_background: Color,
+    output: Box<dyn Write + Send>,
+    // terminal's original settings, None when not attached to a terminal
+    termios: Option<Termios>,
     c_x: usize,
     c_y: usize,
-    // c_plain: bool,
-    c_bright: bool,
-    c_dim: bool,
-    c_italic: bool,
-    c_underline: bool,
-    c_blink: bool,
-    c_blink_fast: bool,
-    c_reverse: bool,
-    c_transparent: bool,
-    c_strike: bool,
-    chars_sent: usize,
-    chars_refresh: usize,
+    // appearance last sent to terminal, None when unknown
+    c_look: Option<Appearance>,
+    out: String,
+    // glyphs on layers below this one are printed dimmed
+    dim_below: Option<usize>,
+    // viewports by id of graphic presenting them
+    viewports: HashMap<usize, Viewport>,
+    // terminal was given back to user
+    suspended: bool,
+    // rendering into a region below cursor instead of alternate buffer
+    inline: bool,
+    // dimensions that were not given explicitly follow terminal when it gets resized
+    follow_cols: bool,
+    follow_rows: bool,
+    // where terminal's cursor should be kept, and how it should look
+    cursor: Option<(usize, usize)>,
+    cursor_visible: bool,
+    cursor_shape: CursorShape,
+    cursor_changed: bool,
+    batch: BatchState,
 }
 
 impl Screen {
     /// Create a new Screen instance with given dimentions and fills it with provided glyph.
     pub fn new(cols: Option<usize>, rows: Option<usize>, glyph: Option<Glyph>) -> Self {
-        let (new_rows, new_cols) = ask_os_for_rows_and_cols();
+        let (new_rows, new_cols) = ask_os_for_rows_and_cols().unwrap_or_else(|e| {
+            report(e);
+            (35, 80)
+        });
         let final_rows = if let Some(rows) = rows {
             rows
         } else {
@@ -70,48 +157,105 @@ impl Screen {
         let stdin = 0; // couldn't get std::os::unix::io::FromRawFd to work
                        // on /dev/stdin or /dev/tty
         let termios = Termios::from_fd(stdin).expect("Could not get Termios instance from stdin.");
-        let new_termios = termios; // make a mutable copy of termios
-                                   // that we will modify
-        let c_x = final_cols;
-        let c_y = final_rows;
-        let mut dglyph = Glyph::default();
-        if glyph.is_some() {
-            dglyph = glyph.unwrap();
-        }
-        let display = Display::new(0, dglyph, final_cols, final_rows);
+        let mut screen = Screen::with_output(final_cols, final_rows, glyph, Box::new(io::stdout()));
+        screen.termios = Some(termios);
+        screen.follow_cols = cols.is_none();
+        screen.follow_rows = rows.is_none();
+        screen
+    }
+
+    /// Create a Screen of given size writing to given output instead of stdout.
+    /// Terminal's settings are left untouched, since it is not attached to one.
+    pub(crate) fn with_output(
+        cols: usize,
+        rows: usize,
+        glyph: Option<Glyph>,
+        output: Box<dyn Write + Send>,
+    ) -> Self {
+        // Cursor position is not known yet
+        let c_x = 0;
+        let c_y = 0;
+        let dglyph = glyph.unwrap_or_default();
+        let display = Display::new(0, dglyph, cols, rows);
         Screen {
-            rows: final_rows,
-            cols: final_cols,
+            rows,
+            cols,
             display,
             shelve: HashMap::new(),
             shelve_id: 0,
             time: Timestamp::now(),
-            next_available_id: 0,
+            graphic_ids: GraphicIds::default(),
             // animations: HashMap::with_capacity(5),
             graphics: HashMap::with_capacity(5),
-            stdin,
-            stdout: io::stdout(),
-            termios_orig: termios,
-            termios: new_termios,
-            c_color: dglyph.color,
-            c_background: dglyph.background,
+            stdin: 0,
+            output,
+            termios: None,
             c_x,
             c_y,
-            // c_plain: dglyph.plain,
-            c_bright: dglyph.bright,
-            c_dim: dglyph.dim,
-            c_italic: dglyph.italic,
-            c_blink: dglyph.blink,
-            c_blink_fast: dglyph.blink_fast,
-            c_underline: dglyph.underline,
-            c_reverse: dglyph.reverse,
-            c_transparent: dglyph.transparent,
-            c_strike: dglyph.strike,
-            chars_sent: 0,
-            chars_refresh: 100,
+            c_look: None,
+            out: String::with_capacity(4096),
+            dim_below: None,
+            viewports: HashMap::new(),
+            suspended: false,
+            inline: false,
+            follow_cols: false,
+            follow_rows: false,
+            cursor: None,
+            cursor_visible: false,
+            cursor_shape: CursorShape::Default,
+            cursor_changed: false,
+            batch: BatchState::default(),
         }
     }
 
+    /// Create a Screen occupying given number of rows below cursor's line,
+    /// as wide as terminal is, instead of using alternate buffer.
+    pub fn new_inline(rows: usize, glyph: Option<Glyph>) -> Self {
+        Screen::new(None, None, glyph).into_inline(rows, glyph)
+    }
+
+    /// Turn a Screen into one occupying given number of rows below cursor's line.
+    pub(crate) fn into_inline(mut self, rows: usize, glyph: Option<Glyph>) -> Self {
+        self.rows = rows.clamp(1, self.rows);
+        self.display = Display::new(0, glyph.unwrap_or_default(), self.cols, self.rows);
+        self.inline = true;
+        // Region keeps it's height, only it's width follows terminal
+        self.follow_rows = false;
+        self
+    }
+
+    /// Follow terminal's new size, keeping dimensions that were given explicitly.
+    /// Returns true when screen's size has changed.
+    pub(crate) fn follow_terminal_size(&mut self) -> bool {
+        let Some((cols, rows)) = terminal::size() else {
+            return false;
+        };
+        let cols = if self.follow_cols { cols } else { self.cols };
+        let rows = if self.follow_rows { rows } else { self.rows };
+        if (cols, rows) == (self.cols, self.rows) {
+            return false;
+        }
+        self.resize(cols, rows);
+        true
+    }
+
+    /// Change size of screen and all it's displays, keeping what still fits
+    /// and printing current display again.
+    pub fn resize(&mut self, cols: usize, rows: usize) {
+        self.cols = cols.max(1);
+        self.rows = rows.max(1);
+        resize_display(&mut self.display, self.cols, self.rows, &self.graphics);
+        for ShelvedItem(display, graphics) in self.shelve.values_mut() {
+            resize_display(display, self.cols, self.rows, graphics);
+        }
+        if let Some((x, y)) = self.cursor {
+            self.cursor = Some((x.min(self.cols), y.min(self.rows)));
+            self.cursor_changed = true;
+        }
+        self.clear_screen();
+        self.present(true);
+    }
+
     /// Swap current display with a new one. If required old display can be stored for later use.
     pub fn new_display(&mut self, display_id: usize, keep_existing: bool) -> usize {
         let new_display = Display::new(display_id, Glyph::default(), self.cols, self.rows);
@@ -124,6 +268,8 @@ impl Screen {
                 .insert(old_display.id, ShelvedItem(old_display, old_graphics));
             //return_id = Some(self.shelve_id);
             self.shelve_id += 1;
+        } else {
+            self.discard_graphics(old_graphics);
         }
         self.clear_screen();
         display_id
@@ -142,19 +288,91 @@ impl Screen {
             if keep_existing {
                 self.shelve
                     .insert(old_display.id, ShelvedItem(old_display, old_graphics));
+            } else {
+                self.discard_graphics(old_graphics);
             }
-            let to_print = self.refresh(true);
-            self.print_all(to_print);
+            self.present(true);
         }
         return_id
     }
 
-    /// Add a new graphic to screen's current display.
-    pub fn add_graphic(&mut self, graphic: Graphic, layer: usize, offset: (isize, isize)) -> usize {
-        let graphic_id = self.next_available_id;
-        self.next_available_id += 1;
+    /// Release ids and viewports of graphics belonging to a dropped display.
+    fn discard_graphics(&mut self, graphics: HashMap<usize, (Graphic, usize, (isize, isize))>) {
+        for graphic_id in graphics.into_keys() {
+            self.viewports.remove(&graphic_id);
+            self.graphic_ids.free(graphic_id);
+        }
+    }
+
+    /// Add a new graphic to screen's current display, None when out of graphic ids.
+    pub fn add_graphic(
+        &mut self,
+        graphic: Graphic,
+        layer: usize,
+        offset: (isize, isize),
+    ) -> Option<usize> {
+        let graphic_id = self.graphic_ids.next_id()?;
         self.graphics.insert(graphic_id, (graphic, layer, offset));
-        graphic_id
+        Some(graphic_id)
+    }
+
+    /// Add a viewport to screen's current display, as a graphic of viewport's size.
+    pub fn add_viewport(
+        &mut self,
+        viewport: Viewport,
+        layer: usize,
+        offset: (isize, isize),
+    ) -> Option<usize> {
+        let frame = viewport.render();
+        let mut library = HashMap::with_capacity(2);
+        library.insert(0, frame.clone());
+        library.insert(1, frame);
+        let graphic = Graphic::new(viewport.cols, viewport.rows, 0, library, None);
+        let graphic_id = self.add_graphic(graphic, layer, offset)?;
+        self.viewports.insert(graphic_id, viewport);
+        self.set_graphic(&graphic_id, &0, true);
+        Some(graphic_id)
+    }
+
+    /// Scroll a viewport by given number of columns and rows.
+    pub fn scroll_viewport(&mut self, graphic_id: usize, delta: (isize, isize)) {
+        if let Some(viewport) = self.viewports.get_mut(&graphic_id) {
+            if viewport.scroll_by(delta) {
+                self.redraw_viewport(graphic_id);
+            }
+        }
+    }
+
+    /// Scroll a viewport to given column and row of it's source.
+    pub fn scroll_viewport_to(&mut self, graphic_id: usize, position: (usize, usize)) {
+        if let Some(viewport) = self.viewports.get_mut(&graphic_id) {
+            if viewport.scroll_to(position) {
+                self.redraw_viewport(graphic_id);
+            }
+        }
+    }
+
+    /// Replace graphic shown through a viewport.
+    pub fn set_viewport_source(&mut self, graphic_id: usize, source: Graphic) {
+        if let Some(viewport) = self.viewports.get_mut(&graphic_id) {
+            viewport.set_source(source);
+            self.redraw_viewport(graphic_id);
+        }
+    }
+
+    /// Render viewport into a frame of it's graphic that is not being displayed,
+    /// then switch to that frame, so that only changed glyphs get printed.
+    fn redraw_viewport(&mut self, graphic_id: usize) {
+        let Some(viewport) = self.viewports.get(&graphic_id) else {
+            return;
+        };
+        let frame = viewport.render();
+        if let Some((graphic, _layer, _offset)) = self.graphics.get_mut(&graphic_id) {
+            let next = 1 - graphic.current_frame.min(1);
+            if graphic.swap_frame(next, frame).is_some() {
+                self.set_graphic(&graphic_id, &next, false);
+            }
+        }
     }
 
     /// Move a graphic to new layer and/or screen offset.
@@ -234,19 +452,32 @@ impl Screen {
                 self.update(vec![(pixels, *layer)]);
             }
         }
-        let to_print = self.refresh(false);
-        self.print_all(to_print);
+        self.present(false);
     }
 
     /// Delete a graphic from display.
-    pub fn delete_graphic(&mut self, graphic_id: &usize) {
-        let mut clear_info = None;
-        if let Some((graphic, layer, offset)) = self.graphics.get_mut(graphic_id) {
-            clear_info = Some((*layer, offset.0, offset.1, graphic.cols, graphic.rows));
-        }
-        if let Some(c) = clear_info {
-            self.clear_area(c.0, max(c.1, 0) as usize, max(c.2, 0) as usize, c.3, c.4);
+    /// Graphic is looked for on current display and on shelved ones,
+    /// returns false when there is no such graphic.
+    pub fn delete_graphic(&mut self, graphic_id: &usize) -> bool {
+        self.viewports.remove(graphic_id);
+        if let Some((graphic, layer, offset)) = self.graphics.remove(graphic_id) {
+            let (x, y) = (max(offset.0, 0) as usize, max(offset.1, 0) as usize);
+            self.clear_area(layer, x, y, graphic.cols, graphic.rows);
+        } else if let Some(ShelvedItem(display, graphics)) = self
+            .shelve
+            .values_mut()
+            .find(|ShelvedItem(_display, graphics)| graphics.contains_key(graphic_id))
+        {
+            if let Some((graphic, layer, offset)) = graphics.remove(graphic_id) {
+                let (x, y) = (max(offset.0, 0) as usize, max(offset.1, 0) as usize);
+                let to_clear = area_pixels(x, y, graphic.cols, graphic.rows);
+                update_display(display, self.cols, self.rows, vec![(to_clear, layer)]);
+            }
+        } else {
+            return false;
         }
+        self.graphic_ids.free(*graphic_id);
+        true
     }
 
     /// Set a graphic to given frame.
@@ -256,8 +487,7 @@ impl Screen {
             results.push((graphic.set_frame(frame_id, *offset, force), *layer));
         }
         self.update(results);
-        let to_print = self.refresh(force);
-        self.print_all(to_print);
+        self.present(force);
     }
 
     /// Get a vector of strings representing current display's contents. Each string represents a line.
@@ -314,8 +544,7 @@ impl Screen {
             results.push((graphic.set_frame(&curr_frame, *offset, true), *layer));
         }
         self.update(results);
-        let to_print = self.refresh(false);
-        self.print_all(to_print);
+        self.present(false);
     }
 
     /// Set all glyphs of graphic's frame to given background color.
@@ -327,8 +556,7 @@ impl Screen {
             results.push((graphic.set_frame(&curr_frame, *offset, true), *layer));
         }
         self.update(results);
-        let to_print = self.refresh(false);
-        self.print_all(to_print);
+        self.present(false);
     }
 
     /// Set all glyphs of graphic's frame to given style.
@@ -340,8 +568,7 @@ impl Screen {
             results.push((graphic.set_frame(&curr_frame, *offset, true), *layer));
         }
         self.update(results);
-        let to_print = self.refresh(false);
-        self.print_all(to_print);
+        self.present(false);
     }
 
     /// Set visibility for given graphic.
@@ -352,8 +579,7 @@ impl Screen {
             self.graphics.insert(graphic_id, (graphic, layer, offset));
             self.set_graphic(&graphic_id, &fid, false);
             self.update(vec![(pixels, layer)]);
-            let to_print = self.refresh(false);
-            self.print_all(to_print);
+            self.present(false);
         }
     }
 
@@ -365,8 +591,7 @@ impl Screen {
             self.graphics.insert(graphic_id, (graphic, layer, offset));
             self.set_graphic(&graphic_id, &fid, false);
             self.update(vec![(pixel, layer)]);
-            let to_print = self.refresh(false);
-            self.print_all(to_print);
+            self.present(false);
         }
     }
 
@@ -378,6 +603,34 @@ impl Screen {
         None
     }
 
+    /// Describe state of a graphic on current display.
+    pub fn graphic_info(&self, graphic_id: usize) -> Option<GraphicInfo> {
+        self.graphics
+            .get(&graphic_id)
+            .map(|(graphic, layer, offset)| graphic.info(graphic_id, *layer, *offset))
+    }
+
+    /// Describe all graphics on current display, ordered by layer and id.
+    pub fn graphics_info(&self) -> Vec<GraphicInfo> {
+        let mut infos: Vec<GraphicInfo> = self
+            .graphics
+            .iter()
+            .map(|(id, (graphic, layer, offset))| graphic.info(*id, *layer, *offset))
+            .collect();
+        infos.sort_unstable_by_key(|info| (info.layer, info.id));
+        infos
+    }
+
+    /// Error to return when an operation on given graphic has failed,
+    /// telling apart a graphic that does not exist.
+    pub fn graphic_error(&self, graphic_id: usize, error: AnimError) -> AnimError {
+        if self.graphics.contains_key(&graphic_id) {
+            error
+        } else {
+            AnimError::UnknownGraphic(graphic_id)
+        }
+    }
+
     /// Insert an empty frame to a graphic.
     pub fn empty_frame(&mut self, graphic_id: usize) -> Option<usize> {
         if let Some((mut graphic, layer, offset)) = self.graphics.remove(&graphic_id) {
@@ -522,14 +775,39 @@ impl Screen {
             }
         }
         self.update(pixels);
-        let to_print = self.refresh(false);
-        self.print_all(to_print);
+        self.present(false);
     }
 
     /// Clear entire screen.
     pub fn clear_screen(&mut self) {
-        print!("\x1b[0m\x1b[21;22;23;24;25;26;27;29;37;40m\x1b[1;1Hm");
-        println!("\x1b[H{:<1$}", "", self.cols * self.rows);
+        if self.suspended {
+            return;
+        }
+        // Whole display gets printed once batch ends
+        if self.batch.defer(true) {
+            return;
+        }
+        if self.inline {
+            self.out.push_str("\x1b[0m");
+            // Cursor could be parked anywhere in the region
+            self.move_to(1, 1);
+            for row in 1..self.rows + 1 {
+                if row > 1 {
+                    self.out.push_str("\x1b[B");
+                }
+                self.out.push_str("\x1b[2K");
+            }
+            self.c_x = 1;
+            self.c_y = self.rows;
+            self.flush_out();
+            return;
+        }
+        self.out
+            .push_str("\x1b[0m\x1b[21;22;23;24;25;26;27;29;37;40m\x1b[H");
+        self.out
+            .push_str(&format!("{:<1$}\r\n", "", self.cols * self.rows));
+        self.c_x = 0;
+        self.c_y = 0;
         self.flush_out();
     }
 
@@ -542,18 +820,33 @@ impl Screen {
         width: usize,
         height: usize,
     ) {
-        let mut to_clear = Vec::with_capacity(width * height);
-        let gplain = Glyph::transparent();
-        for x in start_x..start_x + width + 1 {
-            for y in start_y..start_y + height + 1 {
-                to_clear.push(Pixel::new(x, y, gplain));
-            }
-        }
+        let to_clear = area_pixels(start_x, start_y, width, height);
         self.update(vec![(to_clear, layer)]);
-        let to_print = self.refresh(false);
+        self.present(false);
+    }
+
+    /// Print what has changed on current display, or all of it when forced.
+    /// While a batch is being applied nothing is printed until it ends.
+    fn present(&mut self, force: bool) {
+        if self.batch.defer(force) {
+            return;
+        }
+        let to_print = self.refresh(force);
         self.print_all(to_print);
     }
 
+    /// Start applying many changes as one, they show up on screen together once batch ends.
+    pub fn begin_batch(&mut self) {
+        self.batch.begin();
+    }
+
+    /// Finish applying a batch of changes, printing their outcome at once.
+    pub fn end_batch(&mut self) {
+        if let Some(force) = self.batch.end() {
+            self.present(force);
+        }
+    }
+
     /// Get a vector of localized glyphs to be refreshed (or entire screen if using force).
     fn refresh(&mut self, force: bool) -> Vec<(usize, usize, Glyph)> {
         let mut cap = 64;
@@ -563,12 +856,26 @@ impl Screen {
         let mut to_print = Vec::with_capacity(cap);
         for gcake in self.display.array.iter_mut() {
             if gcake.modified || force {
-                to_print.push((gcake.col, gcake.row, gcake.get_glyph()));
+                let mut glyph = gcake.get_glyph();
+                if let Some(layer) = self.dim_below {
+                    if gcake.top_layer() < layer {
+                        glyph.set_dim(true);
+                    }
+                }
+                to_print.push((gcake.col, gcake.row, glyph));
             }
         }
         to_print
     }
 
+    /// Print glyphs placed on layers below given one dimmed, or stop dimming with None.
+    pub fn dim_below(&mut self, layer: Option<usize>) {
+        if self.dim_below != layer {
+            self.dim_below = layer;
+            self.present(true);
+        }
+    }
+
     /// Print all provided Glyphs on screen.
     pub fn print_all(&mut self, glyphs: Vec<(usize, usize, Glyph)>) {
         for (x, y, g) in glyphs {
@@ -578,20 +885,75 @@ impl Screen {
     }
 
     fn flush_out(&mut self) {
-        self.stdout.lock().flush().expect("Flushing stdout failed.");
-        self.c_x = self.cols;
-        self.c_y = self.rows;
-        self.c_color = Color::white();
-        self.c_background = Color::black();
-        self.c_blink = false;
-        self.c_blink_fast = false;
-        self.c_bright = false;
-        self.c_dim = false;
-        self.c_italic = false;
-        self.c_strike = false;
-        self.c_transparent = false;
-        self.c_reverse = false;
-        self.c_underline = false;
+        if self.batch.defer(false) {
+            return;
+        }
+        if !self.out.is_empty() || self.cursor_changed {
+            if self.cursor_visible {
+                // Hiding cursor while it jumps around
+                self.out.insert_str(0, "\x1b[?25l");
+            }
+            self.park_cursor();
+        }
+        // Terminal belongs to someone else, whole display gets repainted on resume
+        if !self.suspended {
+            self.output
+                .write_all(self.out.as_bytes())
+                .expect("Writing to stdout failed.");
+            self.output.flush().expect("Flushing stdout failed.");
+        }
+        self.out.clear();
+        self.cursor_changed = false;
+        self.c_look = None;
+    }
+
+    /// Move cursor to where it should stay between flushes and show it when visible.
+    /// Inline region is addressed relative to it's top left corner,
+    /// so cursor goes back there unless placed elsewhere.
+    fn park_cursor(&mut self) {
+        let resting = match self.cursor {
+            Some(position) if self.cursor_visible => Some(position),
+            _ if self.inline => Some((1, 1)),
+            _ => None,
+        };
+        if let Some((x, y)) = resting {
+            self.move_to(x, y);
+            if self.inline {
+                terminal::set_inline_cursor_row(y);
+            }
+        } else {
+            // Where printing has left cursor is not known for sure, e.g. after a wide char
+            self.c_x = 0;
+            self.c_y = 0;
+        }
+        if self.cursor_visible {
+            self.out.push_str("\x1b[?25h");
+        }
+    }
+
+    /// Place terminal's cursor at given column and row, both starting from 1.
+    /// It is kept there after every screen update, when visible.
+    pub fn place_cursor(&mut self, x: usize, y: usize) {
+        self.cursor = Some((x.clamp(1, self.cols), y.clamp(1, self.rows)));
+        self.cursor_changed = true;
+        self.flush_out();
+    }
+
+    /// Show or hide terminal's cursor.
+    pub fn show_cursor(&mut self, visible: bool) {
+        self.cursor_visible = visible;
+        if !visible {
+            self.out.push_str("\x1b[?25l");
+        }
+        self.cursor_changed = true;
+        self.flush_out();
+    }
+
+    pub fn set_cursor_shape(&mut self, shape: CursorShape) {
+        self.cursor_shape = shape;
+        self.out.push_str(&shape.sequence());
+        self.cursor_changed = true;
+        self.flush_out();
     }
 
     /// Convert a vector of localized glyphs into a vector of strings each representing a line of text.
@@ -600,6 +962,7 @@ impl Screen {
         let mut line_text = String::new();
         let mut last_line = 10;
         let mut first_glyph = true;
+        let mut previous = style::DEFAULT;
         for (_x, y, glyph) in glyphs {
             if y != last_line {
                 if !line_text.is_empty() {
@@ -609,11 +972,20 @@ impl Screen {
                 }
                 last_line = y;
             }
-            let modifier = self.gformat_out(glyph, first_glyph);
+            let look = glyph.appearance();
+            let modifier = if first_glyph {
+                let modifier = look.sgr_from(&style::DEFAULT);
+                if modifier.is_empty() {
+                    "0".to_string()
+                } else {
+                    format!("0;{}", modifier)
+                }
+            } else {
+                look.sgr_from(&previous)
+            };
+            previous = look;
             if !modifier.is_empty() {
                 line_text.push_str(&format!("\x1b[{}m{}", modifier, glyph.character));
-                // line_text.push_str(&modifier);
-                // line_text.push('m');
             } else {
                 line_text.push(glyph.character);
             }
@@ -629,18 +1001,7 @@ impl Screen {
 
     /// Update a pixel to new value.
     pub fn update(&mut self, pixels: Vec<(Vec<Pixel>, usize)>) {
-        for (ps, layer) in pixels {
-            for p in ps {
-                if p.x >= self.cols || p.y >= self.rows {
-                    continue;
-                }
-                let x = p.x; //.saturating_sub(1);
-                let y = p.y; //.saturating_sub(1);
-                let index = x + (y * self.cols);
-                let cake = self.display.array.get_mut(index).expect("WTF?!");
-                cake.update(p.g, layer);
-            }
-        }
+        update_display(&mut self.display, self.cols, self.rows, pixels);
     }
 
     /// Print a glyph on screent in given location.
@@ -648,316 +1009,169 @@ impl Screen {
         if x > self.cols || y > self.rows {
             return;
         }
-        let mut formated = String::new();
         if self.c_x != x || self.c_y != y {
-            formated.push_str(&format!("\x1b[{};{}H", y, x));
+            self.move_to(x, y);
         };
         self.c_x = x + 1;
         self.c_y = y;
-        let modifier = self.gformat(glyph);
-        if !modifier.is_empty() {
-            formated.push_str("\x1b[");
-            formated.push_str(&modifier);
-            formated.push('m');
-        }
-
-        print!("{}{}", formated, glyph.character);
-    }
-
-    /// Get a string representing given glyph for writing into a text file.
-    fn gformat_out(&mut self, glyph: Glyph, first_glyph: bool) -> String {
-        let mut modifier = String::new(); //"\x1b[".to_string();
-                                          // let mut add_modifier = false;
-                                          // Plain = 0,
-        let mut set_plain_on_exit = false;
-        let mut push_colors = false;
-
-        if first_glyph {
-            //self.c_plain = true;
-            self.c_bright = false;
-            self.c_dim = false;
-            self.c_italic = false;
-            self.c_underline = false;
-            self.c_blink = false;
-            self.c_blink_fast = false;
-            self.c_reverse = false;
-            self.c_transparent = false;
-            self.c_strike = false;
-            self.c_color = Color::white();
-            self.c_background = Color::black();
-            modifier.push_str("0;");
-        }
-        if self.c_bright && !glyph.bright {
-            self.c_bright = false;
-            if glyph.dim {
-                if !self.c_dim {
-                    self.c_dim = true;
-                    modifier.push_str("2;");
-                }
-            } else {
-                modifier.push_str("22;");
-            }
-            push_colors = true;
-        } else if !self.c_bright && glyph.bright {
-            self.c_bright = true;
-            if self.c_dim {
-                self.c_dim = false;
-                // modifier.push_str("22;");
+        let mut look = glyph.appearance();
+        // Transparency only matters for composing, terminal should not conceal anything
+        look.set_transparent(false);
+        if self.c_look != Some(look) {
+            self.out.push_str("\x1b[");
+            let start = self.out.len();
+            // Once terminal's state is known only attributes that differ are sent
+            match self.c_look {
+                Some(previous) => look.push_sgr_from(&previous, &mut self.out),
+                None => look.push_sgr(&mut self.out),
             }
-            modifier.push_str("1;");
-            push_colors = true;
-        }
-        if self.c_dim && !glyph.dim {
-            self.c_dim = false;
-            if glyph.bright {
-                modifier.push_str("1;");
-                self.c_bright = true;
+            if self.out.len() == start {
+                self.out.truncate(start - 2);
             } else {
-                modifier.push_str("22;");
-            }
-            push_colors = true;
-        } else if !self.c_dim && glyph.dim {
-            self.c_dim = true;
-            modifier.push_str("2;");
-            push_colors = true;
-        }
-        if self.c_italic && !glyph.italic {
-            self.c_italic = false;
-            modifier.push_str("23;");
-            push_colors = true;
-        } else if !self.c_italic && glyph.italic {
-            self.c_italic = true;
-            push_colors = true;
-            modifier.push_str("3;");
-        }
-        if self.c_underline && !glyph.underline {
-            self.c_underline = false;
-            modifier.push_str("24;");
-            push_colors = true;
-        } else if !self.c_underline && glyph.underline {
-            self.c_underline = true;
-            modifier.push_str("4;");
-            push_colors = true;
-        }
-        if self.c_blink && !glyph.blink {
-            self.c_blink = false;
-            push_colors = true;
-        } else if !self.c_blink && glyph.blink {
-            self.c_blink = true;
-            modifier.push_str("5;");
-            push_colors = true;
-        }
-        if self.c_blink_fast && !glyph.blink_fast {
-            self.c_blink_fast = false;
-            push_colors = true;
-        } else if !self.c_blink_fast && glyph.blink_fast {
-            self.c_blink_fast = true;
-            modifier.push_str("6;");
-            push_colors = true;
-        }
-        if !glyph.blink && !glyph.blink_fast && (self.c_blink || self.c_blink_fast) {
-            modifier.push_str("25;");
-            push_colors = true;
-        }
-        if self.c_reverse && !glyph.reverse {
-            self.c_reverse = false;
-            modifier.push_str("27;");
-            push_colors = true;
-        } else if !self.c_reverse && glyph.reverse {
-            self.c_reverse = true;
-            modifier.push_str("7;");
-            push_colors = true;
-        }
-        if self.c_transparent && !glyph.transparent {
-            self.c_transparent = false;
-            push_colors = true;
-            modifier.push_str("28;");
-        } else if !self.c_transparent && glyph.transparent {
-            self.c_transparent = true;
-            modifier.push_str("8;");
-            push_colors = true;
-        }
-        if self.c_strike && !glyph.strike {
-            self.c_strike = false;
-            modifier.push_str("29;");
-            push_colors = true;
-        } else if !self.c_strike && glyph.strike {
-            self.c_strike = true;
-            modifier.push_str("9;");
-            push_colors = true;
-        }
-        if self.c_color != glyph.color || push_colors {
-            match glyph.color {
-                Color::Basic(color) => modifier.push_str(&format!("3{};", color as u8)),
-                Color::EightBit(color) => modifier.push_str(&format!("38;5;{};", color)),
-                Color::Grayscale(brightness) => modifier.push_str(&format!("38;5;{};", brightness)),
-                Color::Truecolor(red, green, blue) => {
-                    modifier.push_str(&format!("38;2;{};{};{};", red, green, blue))
-                }
-            }
-            self.c_color = glyph.color;
-        };
-        if self.c_background != glyph.background || push_colors {
-            //modifier.push_str(&format!("4{}", glyph.background as u8));
-            match glyph.background {
-                Color::Basic(color) => modifier.push_str(&format!("4{}", color as u8)),
-                Color::EightBit(color) => modifier.push_str(&format!("48;5;{}", color)),
-                Color::Grayscale(brightness) => modifier.push_str(&format!("48;5;{}", brightness)),
-                Color::Truecolor(red, green, blue) => {
-                    modifier.push_str(&format!("48;2;{};{};{}", red, green, blue))
-                }
-            }
-
-            self.c_background = glyph.background;
-        };
-        self.chars_sent += modifier.len();
-        if self.chars_sent >= self.chars_refresh {
-            self.chars_sent = 0;
-            set_plain_on_exit = true;
-        }
-        if set_plain_on_exit {
-            self.c_bright = true;
-            self.c_dim = true;
-            self.c_italic = true;
-            self.c_underline = true;
-            self.c_blink = true;
-            self.c_blink_fast = true;
-            self.c_reverse = true;
-            self.c_transparent = true;
-            self.c_strike = true;
-            self.c_color = Color::white();
-            self.c_background = Color::black();
-        }
-        modifier
-    }
-
-    /// Get a string of a given glyph to print on screen.
-    fn gformat(&mut self, glyph: Glyph) -> String {
-        let mut modifier = String::new(); //"\x1b[".to_string();
-                                          // let mut add_modifier = false;
-                                          // Plain = 0,
-                                          // if self.c_plain && !glyph.plain {
-                                          //     self.c_plain = false;
-                                          // } else if !self.c_plain && glyph.plain {
-                                          //     self.c_plain = true;
-                                          //     modifier.push_str("0;");
-                                          // }
-        if !glyph.bright {
-            if self.c_bright {
-                self.c_bright = false;
-                modifier.push_str("22;");
-            }
-        } else {
-            if self.c_dim {
-                self.c_dim = false;
-            }
-            if !self.c_bright {
-                self.c_bright = true;
-                modifier.push_str("1;");
-            }
-        }
-        if !glyph.dim {
-            if self.c_dim {
-                self.c_dim = false;
-                modifier.push_str("22;");
-            }
-        } else {
-            if self.c_bright {
-                self.c_bright = false;
-            }
-            if !self.c_dim {
-                // println!("<1234");
-                self.c_dim = true;
-                modifier.push_str("2;");
+                self.out.push('m');
             }
+            self.c_look = Some(look);
         }
-        if !self.c_dim && !self.c_bright {
-            modifier.push_str("22;");
-        }
-        if !glyph.italic {
-            // self.c_italic = false;
-            modifier.push_str("23;");
-        } else {
-            //self.c_italic = true;
-            modifier.push_str("3;");
-        }
-        if !glyph.underline {
-            // self.c_underline = false;
-            modifier.push_str("24;");
-        } else {
-            //self.c_underline = true;
-            modifier.push_str("4;");
-        }
-        if !glyph.blink {
-            self.c_blink = false;
+        self.out.push(glyph.character);
+    }
+
+    /// Move cursor to given column and row.
+    fn move_to(&mut self, x: usize, y: usize) {
+        if self.inline {
+            let movement = relative_move(self.c_y, (x, y));
+            self.out.push_str(&movement);
         } else {
-            //if !self.c_blink {
-            modifier.push_str("5;");
-            self.c_blink = true;
-            //}
+            self.out.push_str(&format!("\x1b[{};{}H", y, x));
         }
-        if !glyph.blink_fast {
-            self.c_blink_fast = false;
+        self.c_x = x;
+        self.c_y = y;
+    }
+
+    /// Initialize required parameters for library to work as expected.
+    pub fn initialize(&mut self) {
+        if let Some(termios) = self.termios {
+            let mut modified = termios;
+            modified.c_lflag &= !(ICANON | ECHO); // no echo and canonical mode
+            let inline_rows = if self.inline { self.rows } else { 0 };
+            terminal::guard(termios, modified, inline_rows);
+            tcsetattr(self.stdin, TCSANOW, &modified)
+                .expect("Failed setting modified Termios buffer during initialization.");
+            // Use separate buffer or make room for inline region, clear it and disable cursor
+            terminal::enter();
+        }
+        if self.inline {
+            self.c_x = 1;
+            self.c_y = 1;
         } else {
-            //if !self.c_blink_fast {
-            self.c_blink_fast = true;
-            modifier.push_str("6;");
-            //            }
+            self.c_x = 0;
+            self.c_y = 0;
         }
-        if !self.c_blink && !self.c_blink_fast {
-            modifier.push_str("25;");
-        }
-        if !glyph.reverse {
-            // self.c_reverse = false;
-            modifier.push_str("27;");
-        } else {
-            //self.c_reverse = true;
-            modifier.push_str("7;");
+    }
+
+    /// Restore original settings of users terminal.
+    pub fn cleanup(self) {
+        terminal::restore();
+    }
+
+    /// Give terminal back to user until resume is called.
+    /// Screen is still updated while suspended, but nothing gets printed.
+    pub fn suspend(&mut self) {
+        if !self.suspended {
+            self.flush_out();
+            self.suspended = true;
+            terminal::restore();
         }
-        if !glyph.strike {
-            // self.c_strike = false;
-            modifier.push_str("29;");
-        } else {
-            //self.c_strike = true;
-            modifier.push_str("9;");
-        }
-        match glyph.color {
-            Color::Basic(color) => modifier.push_str(&format!("3{};", color as u8)),
-            Color::EightBit(color) => modifier.push_str(&format!("38;5;{};", color)),
-            Color::Grayscale(brightness) => modifier.push_str(&format!("38;5;{};", brightness)),
-            Color::Truecolor(red, green, blue) => {
-                modifier.push_str(&format!("38;2;{};{};{};", red, green, blue))
-            }
+    }
+
+    /// Take terminal back over and repaint current display.
+    pub fn resume(&mut self) {
+        if self.suspended {
+            self.suspended = false;
+            self.initialize();
+            self.repaint();
         }
-        match glyph.background {
-            Color::Basic(color) => modifier.push_str(&format!("4{}", color as u8)),
-            Color::EightBit(color) => modifier.push_str(&format!("48;5;{}", color)),
-            Color::Grayscale(brightness) => modifier.push_str(&format!("48;5;{}", brightness)),
-            Color::Truecolor(red, green, blue) => {
-                modifier.push_str(&format!("48;2;{};{};{}", red, green, blue))
-            }
+    }
+
+    pub fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+
+    /// Print entire current display again.
+    pub fn repaint(&mut self) {
+        if self.cursor_shape != CursorShape::Default {
+            self.out.push_str(&self.cursor_shape.sequence());
         }
+        self.present(true);
+    }
+}
 
-        modifier
+/// Escape sequence moving cursor from given row to given column and row,
+/// without knowing where on screen those are.
+pub fn relative_move(from_row: usize, (col, row): (usize, usize)) -> String {
+    let vertical = if row > from_row {
+        format!("\x1b[{}B", row - from_row)
+    } else if row < from_row {
+        format!("\x1b[{}A", from_row - row)
+    } else {
+        String::new()
+    };
+    format!("{}\x1b[{}G", vertical, col)
+}
+
+/// Give a display new size, moving over cells that still fit
+/// and placing graphics again, so that parts uncovered by growing show up.
+fn resize_display(
+    display: &mut Display,
+    cols: usize,
+    rows: usize,
+    graphics: &HashMap<usize, (Graphic, usize, (isize, isize))>,
+) {
+    let old = replace(
+        display,
+        Display::new(display.id, Glyph::default(), cols, rows),
+    );
+    for cake in old.array {
+        if cake.col <= cols && cake.row <= rows {
+            let index = cake.col - 1 + (cake.row - 1) * cols;
+            display.array[index] = cake;
+        }
     }
+    let pixels = graphics
+        .values()
+        .map(|(graphic, layer, offset)| (graphic.get_pixels(*offset), *layer))
+        .collect();
+    update_display(display, cols, rows, pixels);
+}
 
-    /// Initialize required parameters for library to work as expected.
-    pub fn initialize(&mut self) {
-        self.termios.c_lflag &= !(ICANON | ECHO); // no echo and canonical mode
-        tcsetattr(self.stdin, TCSANOW, &self.termios)
-            .expect("Failed setting modified Termios buffer during initialization.");
-        print!("\x1b[?1049h"); // use separate buffer
-        print!("\x1b[2J"); // clear screen
-        print!("\x1b[?25l"); // disable cursor
+/// Update pixels of a display of given size, on given layers.
+fn update_display(
+    display: &mut Display,
+    cols: usize,
+    rows: usize,
+    pixels: Vec<(Vec<Pixel>, usize)>,
+) {
+    for (ps, layer) in pixels {
+        for p in ps {
+            if p.x >= cols || p.y >= rows {
+                continue;
+            }
+            let x = p.x; //.saturating_sub(1);
+            let y = p.y; //.saturating_sub(1);
+            let index = x + (y * cols);
+            let cake = display.array.get_mut(index).expect("WTF?!");
+            cake.update(p.g, layer);
+        }
     }
+}
 
-    /// Restore original settings of users terminal.
-    pub fn cleanup(self) {
-        print!("\x1b[?25h"); // enable cursor
-        print!("\x1b[2J"); // clear screen
-        print!("\x1b[?1049l"); // disable separate buffer
-        tcsetattr(self.stdin, TCSANOW, &self.termios_orig)
-            .expect("Failed to restore original Termios buffer."); // reset the stdin to
-                                                                   // original termios data
+/// Transparent pixels covering an area, used for clearing it.
+fn area_pixels(start_x: usize, start_y: usize, width: usize, height: usize) -> Vec<Pixel> {
+    let mut to_clear = Vec::with_capacity(width * height);
+    let gplain = Glyph::transparent();
+    for x in start_x..start_x + width + 1 {
+        for y in start_y..start_y + height + 1 {
+            to_clear.push(Pixel::new(x, y, gplain));
+        }
     }
+    to_clear
 }
@@ -0,0 +1,55 @@
+use super::focus::Routed;
+use super::key::Key;
+use super::manager::Manager;
+use futures_core::Stream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Keys pressed by user, as an asynchronous stream.
+/// Keys go through macros the same way as with `Manager::read_key`.
+/// Stream ends when Manager does not capture keyboard and no macro is running.
+pub struct Keys<'a> {
+    mgr: &'a mut Manager,
+}
+
+impl Stream for Keys<'_> {
+    type Item = Key;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Key>> {
+        self.get_mut().mgr.poll_key(cx)
+    }
+}
+
+/// Keys pressed by user after being routed through registered widgets,
+/// as an asynchronous stream of what happened to each of them.
+pub struct Events<'a> {
+    mgr: &'a mut Manager,
+}
+
+impl Stream for Events<'_> {
+    type Item = Routed;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Routed>> {
+        let mgr = &mut self.get_mut().mgr;
+        match mgr.poll_key(cx) {
+            Poll::Ready(Some(key)) => Poll::Ready(Some(mgr.route_key(key))),
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl Manager {
+    /// Stream of keys pressed by user, for use in async code.
+    /// Task awaiting next key is woken when one arrives, no thread is blocked meanwhile.
+    /// A lone Escape is told apart from a longer sequence once keyboard thread goes quiet,
+    /// with a receiver set through set_key_receiver only once more bytes arrive.
+    pub fn keys(&mut self) -> Keys<'_> {
+        Keys { mgr: self }
+    }
+
+    /// Stream of results of routing keys through registered widgets, for use in async code.
+    pub fn events(&mut self) -> Events<'_> {
+        Events { mgr: self }
+    }
+}
@@ -0,0 +1,261 @@
+use super::color::Color;
+use std::ops::{BitOr, BitOrAssign};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+/// A set of text attributes a glyph is printed with, stored as bit flags.
+pub struct Style(u16);
+
+impl Style {
+    pub const NONE: Style = Style(0);
+    pub const PLAIN: Style = Style(1);
+    pub const BRIGHT: Style = Style(1 << 1);
+    pub const DIM: Style = Style(1 << 2);
+    pub const ITALIC: Style = Style(1 << 3);
+    pub const UNDERLINE: Style = Style(1 << 4);
+    pub const BLINK: Style = Style(1 << 5);
+    pub const BLINK_FAST: Style = Style(1 << 6);
+    pub const REVERSE: Style = Style(1 << 7);
+    pub const TRANSPARENT: Style = Style(1 << 8);
+    pub const STRIKE: Style = Style(1 << 9);
+    const ALL: u16 = (1 << 10) - 1;
+
+    /// Get raw bits of this style.
+    pub fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Create a style from raw bits, unknown bits are dropped.
+    pub fn from_bits(bits: u16) -> Self {
+        Style(bits & Style::ALL)
+    }
+
+    /// Check if all flags of other are also set in this style.
+    pub fn contains(self, other: Style) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Check if no flag is set.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Enable or disable given flags.
+    pub fn set(&mut self, other: Style, enabled: bool) {
+        if enabled {
+            self.0 |= other.0;
+        } else {
+            self.0 &= !other.0;
+        }
+    }
+}
+
+impl BitOr for Style {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Style(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for Style {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Everything that describes how a character is printed, except for the character itself.
+pub struct Appearance {
+    pub color: Color,
+    pub background: Color,
+    pub style: Style,
+}
+
+impl Appearance {
+    pub const fn new(color: Color, background: Color, style: Style) -> Self {
+        Appearance {
+            color,
+            background,
+            style,
+        }
+    }
+
+    /// Pack into a single word: 26 bits for foreground, 26 bits for background
+    /// and 10 bits for style flags.
+    pub(crate) fn pack(&self) -> u64 {
+        self.color.pack() as u64
+            | (self.background.pack() as u64) << 26
+            | (self.style.bits() as u64) << 52
+    }
+
+    /// Restore an appearance packed with pack.
+    pub(crate) fn unpack(packed: u64) -> Self {
+        Appearance {
+            color: Color::unpack((packed & 0x3ff_ffff) as u32),
+            background: Color::unpack(((packed >> 26) & 0x3ff_ffff) as u32),
+            style: Style::from_bits((packed >> 52) as u16),
+        }
+    }
+
+    pub fn has(&self, style: Style) -> bool {
+        self.style.contains(style)
+    }
+
+    pub fn set_transparent(&mut self, transparent: bool) {
+        self.style.set(Style::TRANSPARENT, transparent);
+    }
+
+    pub fn set_bright(&mut self, bright: bool) {
+        self.style.set(Style::BRIGHT, bright);
+        if bright {
+            self.style.set(Style::DIM, false);
+        }
+    }
+
+    pub fn set_dim(&mut self, dim: bool) {
+        self.style.set(Style::DIM, dim);
+        if dim {
+            self.style.set(Style::BRIGHT, false);
+        }
+    }
+
+    pub fn set_plain(&mut self, plain: bool) {
+        if plain {
+            *self = PLAIN;
+        } else {
+            self.style.set(Style::PLAIN, false);
+        }
+    }
+
+    pub fn set_italic(&mut self, italic: bool) {
+        self.style.set(Style::ITALIC, italic);
+    }
+
+    pub fn set_underline(&mut self, underline: bool) {
+        self.style.set(Style::UNDERLINE, underline);
+    }
+
+    pub fn set_blink(&mut self, blink: bool) {
+        self.style.set(Style::BLINK, blink);
+        if blink {
+            self.style.set(Style::BLINK_FAST, false);
+        }
+    }
+
+    pub fn set_blinkfast(&mut self, blink: bool) {
+        self.style.set(Style::BLINK_FAST, blink);
+        if blink {
+            self.style.set(Style::BLINK, false);
+        }
+    }
+
+    pub fn set_reverse(&mut self, reverse: bool) {
+        self.style.set(Style::REVERSE, reverse);
+    }
+
+    pub fn set_strike(&mut self, strike: bool) {
+        self.style.set(Style::STRIKE, strike);
+    }
+
+    /// Append SGR parameters that fully describe this appearance regardless of
+    /// terminal's current state: a reset followed by enabled attributes and both colors.
+    pub(crate) fn push_sgr(&self, modifier: &mut String) {
+        modifier.push_str("0;");
+        if self.has(Style::BRIGHT) {
+            modifier.push_str("1;");
+        } else if self.has(Style::DIM) {
+            modifier.push_str("2;");
+        }
+        if self.has(Style::BLINK) {
+            modifier.push_str("5;");
+        } else if self.has(Style::BLINK_FAST) {
+            modifier.push_str("6;");
+        }
+        for (flag, on) in [
+            (Style::ITALIC, "3;"),
+            (Style::UNDERLINE, "4;"),
+            (Style::REVERSE, "7;"),
+            (Style::TRANSPARENT, "8;"),
+            (Style::STRIKE, "9;"),
+        ] {
+            if self.has(flag) {
+                modifier.push_str(on);
+            }
+        }
+        self.color.push_sgr(modifier, false);
+        modifier.push(';');
+        self.background.push_sgr(modifier, true);
+    }
+
+    /// SGR parameters required to switch terminal from previous appearance to this one.
+    /// Empty string is returned when nothing needs to change.
+    pub fn sgr_from(&self, previous: &Appearance) -> String {
+        let mut modifier = String::new();
+        self.push_sgr_from(previous, &mut modifier);
+        modifier
+    }
+
+    /// Append parameters returned by sgr_from to modifier, nothing when appearances are equal.
+    pub(crate) fn push_sgr_from(&self, previous: &Appearance, modifier: &mut String) {
+        let start = modifier.len();
+        let intensity = Style::BRIGHT | Style::DIM;
+        let blinking = Style::BLINK | Style::BLINK_FAST;
+        let mut attributes_changed = false;
+        if self.style.bits() & intensity.bits() != previous.style.bits() & intensity.bits() {
+            modifier.push_str("22;");
+            if self.has(Style::BRIGHT) {
+                modifier.push_str("1;");
+            } else if self.has(Style::DIM) {
+                modifier.push_str("2;");
+            }
+            attributes_changed = true;
+        }
+        if self.style.bits() & blinking.bits() != previous.style.bits() & blinking.bits() {
+            modifier.push_str("25;");
+            if self.has(Style::BLINK) {
+                modifier.push_str("5;");
+            } else if self.has(Style::BLINK_FAST) {
+                modifier.push_str("6;");
+            }
+            attributes_changed = true;
+        }
+        for (flag, on, off) in [
+            (Style::ITALIC, "3;", "23;"),
+            (Style::UNDERLINE, "4;", "24;"),
+            (Style::REVERSE, "7;", "27;"),
+            (Style::TRANSPARENT, "8;", "28;"),
+            (Style::STRIKE, "9;", "29;"),
+        ] {
+            if self.has(flag) != previous.has(flag) {
+                modifier.push_str(if self.has(flag) { on } else { off });
+                attributes_changed = true;
+            }
+        }
+        if attributes_changed || self.color != previous.color {
+            self.color.push_sgr(modifier, false);
+            modifier.push(';');
+        }
+        if attributes_changed || self.background != previous.background {
+            self.background.push_sgr(modifier, true);
+        }
+        if modifier.len() > start && modifier.ends_with(';') {
+            modifier.pop();
+        }
+    }
+}
+
+pub const DEFAULT: Appearance = Appearance::new(
+    Color::Basic(super::ColorName::White),
+    Color::Basic(super::ColorName::Black),
+    Style::NONE,
+);
+pub const PLAIN: Appearance = Appearance::new(
+    Color::Basic(super::ColorName::White),
+    Color::Basic(super::ColorName::Black),
+    Style::PLAIN,
+);
+pub const TRANSPARENT: Appearance = Appearance::new(
+    Color::Basic(super::ColorName::White),
+    Color::Basic(super::ColorName::Black),
+    Style::TRANSPARENT,
+);
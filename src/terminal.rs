@@ -0,0 +1,233 @@
+//! Bringing user's terminal back to it's original state
+//! no matter how an application ends: regularly, by panicking or by a signal.
+//! Also when it is stopped with Ctrl-Z, taking it back over once continued,
+//! and noticing when it gets resized.
+
+use std::io::Write;
+use std::panic;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Once, OnceLock};
+use termios::{tcsetattr, Termios, TCSANOW};
+
+/// Terminal settings from before any Manager has modified them.
+static ORIGINAL: OnceLock<Termios> = OnceLock::new();
+/// Terminal settings used while a Manager is running.
+static MODIFIED: OnceLock<Termios> = OnceLock::new();
+/// Is terminal currently modified and in need of restoring.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+/// Was terminal restored because process got stopped.
+static STOPPED: AtomicBool = AtomicBool::new(false);
+/// Should screen be repainted, since process was continued.
+static REPAINT: AtomicBool = AtomicBool::new(false);
+/// Was terminal resized, so that screen should follow it's new size.
+static RESIZED: AtomicBool = AtomicBool::new(false);
+/// Rows of an inline region below cursor's line, or 0 when alternate buffer is used.
+static INLINE_ROWS: AtomicUsize = AtomicUsize::new(0);
+/// Row of an inline region cursor was left in, starting from 1.
+static INLINE_CURSOR_ROW: AtomicUsize = AtomicUsize::new(1);
+static INSTALL_HANDLERS: Once = Once::new();
+
+// Show cursor with default shape, clear screen and leave alternate buffer
+const RESET: &[u8] = b"\x1b[0 q\x1b[?25h\x1b[2J\x1b[?1049l";
+// Enter alternate buffer, clear it and hide cursor
+const ENTER: &[u8] = b"\x1b[?1049h\x1b[2J\x1b[?25l";
+// Reset look, go to a new line below inline region and show cursor with default shape
+const INLINE_RESET: &[u8] = b"\x1b[0m\r\n\x1b[0 q\x1b[?25h";
+
+const STDIN: i32 = 0;
+const STDOUT: i32 = 1;
+
+/// Remember terminal's original and modified settings and make sure original ones
+/// are restored when application panics, receives SIGINT or SIGTERM, or is stopped.
+/// Inline rows tell how tall an inline region is, 0 means alternate buffer is used.
+/// Call before terminal is modified.
+pub fn guard(original: Termios, modified: Termios, inline_rows: usize) {
+    let _ = ORIGINAL.set(original);
+    let _ = MODIFIED.set(modified);
+    INLINE_ROWS.store(inline_rows, Ordering::SeqCst);
+    ACTIVE.store(true, Ordering::SeqCst);
+    INSTALL_HANDLERS.call_once(|| {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            restore();
+            previous_hook(info);
+        }));
+        install(libc::SIGINT, on_signal);
+        install(libc::SIGTERM, on_signal);
+        install(libc::SIGTSTP, on_stop);
+        install(libc::SIGCONT, on_continue);
+        install(libc::SIGWINCH, on_resize);
+    });
+}
+
+fn install(signal: libc::c_int, handler: extern "C" fn(libc::c_int)) {
+    // Safety: handlers only call functions that are async-signal-safe
+    unsafe {
+        libc::signal(signal, handler as libc::sighandler_t);
+    }
+}
+
+/// Enter alternate buffer, or make room for an inline region below cursor,
+/// leaving cursor in it's top left corner.
+pub fn enter() {
+    let _ = std::io::stdout().flush();
+    write_enter();
+}
+
+/// Only async-signal-safe calls are allowed in here.
+fn write_enter() {
+    let rows = INLINE_ROWS.load(Ordering::SeqCst);
+    if rows == 0 {
+        write_bytes(ENTER);
+        return;
+    }
+    INLINE_CURSOR_ROW.store(1, Ordering::SeqCst);
+    // Scrolling terminal if needed, then going back up
+    for _ in 1..rows {
+        write_bytes(b"\n");
+    }
+    for _ in 1..rows {
+        write_bytes(b"\x1b[A");
+    }
+    write_bytes(b"\r\x1b[?25l");
+}
+
+fn write_bytes(bytes: &[u8]) {
+    // Safety: bytes is a valid buffer of given length
+    unsafe {
+        libc::write(STDOUT, bytes.as_ptr() as *const libc::c_void, bytes.len());
+    }
+}
+
+/// Remember which row of an inline region cursor was left in.
+pub fn set_inline_cursor_row(row: usize) {
+    INLINE_CURSOR_ROW.store(row, Ordering::SeqCst);
+}
+
+/// Was process continued after being stopped, so that screen needs repainting.
+pub fn take_repaint() -> bool {
+    REPAINT.swap(false, Ordering::SeqCst)
+}
+
+/// Was terminal resized since last time this was asked.
+pub fn take_resized() -> bool {
+    RESIZED.swap(false, Ordering::SeqCst)
+}
+
+/// Current cols and rows of terminal, None when stdout is not a terminal.
+pub fn size() -> Option<(usize, usize)> {
+    // Safety: winsize is plain data, filled by ioctl when it succeeds
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    let result = unsafe { libc::ioctl(STDOUT, libc::TIOCGWINSZ, &mut size) };
+    if result != 0 || size.ws_col == 0 || size.ws_row == 0 {
+        return None;
+    }
+    Some((size.ws_col as usize, size.ws_row as usize))
+}
+
+/// Leave alternate buffer, or inline region with it's last state in place,
+/// and restore original terminal settings, unless that was already done.
+pub fn restore() {
+    if !ACTIVE.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    // Whatever is still buffered belongs to alternate screen
+    let _ = std::io::stdout().flush();
+    reset();
+}
+
+/// Only async-signal-safe calls are allowed in here.
+fn reset() {
+    let rows = INLINE_ROWS.load(Ordering::SeqCst);
+    if rows == 0 {
+        write_bytes(RESET);
+    } else {
+        for _ in INLINE_CURSOR_ROW.load(Ordering::SeqCst)..rows {
+            write_bytes(b"\x1b[B");
+        }
+        write_bytes(INLINE_RESET);
+    }
+    if let Some(original) = ORIGINAL.get() {
+        let _ = tcsetattr(STDIN, TCSANOW, original);
+    }
+}
+
+extern "C" fn on_signal(signal: libc::c_int) {
+    if ACTIVE.swap(false, Ordering::SeqCst) {
+        reset();
+    }
+    // Let default action terminate the process, with proper exit status
+    // Safety: both calls are async-signal-safe
+    unsafe {
+        libc::signal(signal, libc::SIG_DFL);
+        libc::raise(signal);
+    }
+}
+
+extern "C" fn on_stop(signal: libc::c_int) {
+    if ACTIVE.swap(false, Ordering::SeqCst) {
+        reset();
+        STOPPED.store(true, Ordering::SeqCst);
+    }
+    // Default action stops the process once this handler returns
+    // Safety: both calls are async-signal-safe
+    unsafe {
+        libc::signal(signal, libc::SIG_DFL);
+        libc::raise(signal);
+    }
+}
+
+extern "C" fn on_continue(_signal: libc::c_int) {
+    install(libc::SIGTSTP, on_stop);
+    if !STOPPED.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    if let Some(modified) = MODIFIED.get() {
+        let _ = tcsetattr(STDIN, TCSANOW, modified);
+    }
+    write_enter();
+    ACTIVE.store(true, Ordering::SeqCst);
+    REPAINT.store(true, Ordering::SeqCst);
+}
+
+extern "C" fn on_resize(_signal: libc::c_int) {
+    RESIZED.store(true, Ordering::SeqCst);
+}
+
+/// Read a single byte from stdin, waiting for it no longer than given time.
+/// Returns None on timeout and an error when stdin is closed.
+pub fn read_byte(timeout: std::time::Duration) -> std::io::Result<Option<u8>> {
+    let mut poll_fd = libc::pollfd {
+        fd: STDIN,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    // Safety: poll_fd is a valid array of one pollfd
+    let ready = unsafe { libc::poll(&mut poll_fd, 1, timeout.as_millis() as libc::c_int) };
+    if ready < 0 {
+        let error = std::io::Error::last_os_error();
+        return if error.kind() == std::io::ErrorKind::Interrupted {
+            Ok(None)
+        } else {
+            Err(error)
+        };
+    }
+    if ready == 0 {
+        return Ok(None);
+    }
+    // Reading directly, since buffered Stdin could take bytes poll would not know about
+    let mut byte = 0u8;
+    // Safety: byte is a valid buffer of length 1
+    match unsafe { libc::read(STDIN, &mut byte as *mut u8 as *mut libc::c_void, 1) } {
+        1 => Ok(Some(byte)),
+        0 => Err(std::io::ErrorKind::UnexpectedEof.into()),
+        _ => {
+            let error = std::io::Error::last_os_error();
+            if error.kind() == std::io::ErrorKind::Interrupted {
+                Ok(None)
+            } else {
+                Err(error)
+            }
+        }
+    }
+}
@@ -1,7 +1,9 @@
 #![cfg(test)]
 use super::glyphcake::GlyphCake;
 use super::Glyph;
+use super::Graphic;
 use super::Timestamp;
+use std::collections::HashMap;
 #[test]
 fn overflow_ms_to_sec() {
     let t0 = Timestamp::new(0, 0);
@@ -73,15 +75,15 @@ fn glyph_cake() {
 fn update_glyph_from_str() {
     let mut g = Glyph::default();
     g.update_from_str("\x1b[2;23;24;25;26;27;29;30;48;5;196m ");
-    assert_eq!(g.background, crate::Color::EightBit(196));
+    assert_eq!(g.background(), crate::Color::EightBit(196));
 }
 #[test]
 fn update_glyph_twice_from_str() {
     let mut g = Glyph::default();
     g.update_from_str("\x1b[48;2;91;63;43m");
     g.update_from_str("\x1b[38;2;90;62;42m▀");
-    assert_eq!(g.background, crate::Color::Truecolor(91, 63, 43));
-    assert_eq!(g.color, crate::Color::Truecolor(90, 62, 42));
+    assert_eq!(g.background(), crate::Color::Truecolor(91, 63, 43));
+    assert_eq!(g.color(), crate::Color::Truecolor(90, 62, 42));
 }
 #[test]
 fn glyph_cake2() {
@@ -90,9 +92,1162 @@ fn glyph_cake2() {
     insert_glyph.set_background(crate::Color::blue());
     gc.update(insert_glyph, 1);
     let g = gc.get_glyph();
-    assert_eq!(g.background, crate::Color::blue());
+    assert_eq!(g.background(), crate::Color::blue());
     gc.update(Glyph::transparent(), 1);
     let g = gc.get_glyph();
-    assert_eq!(g.background, crate::Color::black());
-    assert_eq!(g.color, crate::Color::white());
+    assert_eq!(g.background(), crate::Color::black());
+    assert_eq!(g.color(), crate::Color::white());
+}
+#[test]
+fn glyph_is_compact() {
+    assert_eq!(std::mem::size_of::<Glyph>(), 12);
+    assert_eq!(std::mem::size_of::<Option<Glyph>>(), 12);
+}
+#[test]
+fn glyph_style_roundtrip() {
+    let mut g = Glyph::default_with_char('x');
+    g.set_color(crate::Color::Truecolor(1, 2, 3));
+    g.set_background(crate::Color::new_gray(7));
+    g.set_bright(true);
+    g.set_blink(true);
+    g.set_dim(true);
+    assert_eq!(g.color(), crate::Color::Truecolor(1, 2, 3));
+    assert_eq!(g.background(), crate::Color::Grayscale(239));
+    assert!(g.is_dim() && !g.is_bright() && g.is_blink());
+    let mut other = Glyph::default_with_char('y');
+    other.set_style(crate::Style::DIM | crate::Style::BLINK);
+    other.set_color(crate::Color::Truecolor(1, 2, 3));
+    other.set_background(crate::Color::Grayscale(239));
+    assert!(g.same_look(&other));
+    assert_ne!(g, other);
+}
+#[test]
+fn parse_frame_with_colon_subparameters() {
+    let (cols, frame) =
+        crate::ansi::parse_frame("\x1b[38:2::10:20:30;48:5:17mab\x1b[2K\x1b[0mc\n\x1b[4:3md")
+            .unwrap();
+    assert_eq!(cols, 3);
+    assert_eq!(frame.len(), 6);
+    assert_eq!(frame[0].color(), crate::Color::Truecolor(10, 20, 30));
+    assert_eq!(frame[1].background(), crate::Color::EightBit(17));
+    assert_eq!(frame[2].color(), crate::Color::white());
+    assert!(frame[3].is_underline());
+    assert!(frame[4].is_transparent());
+}
+#[test]
+fn parse_frame_reports_location() {
+    let result = crate::ansi::parse_frame("ok\nab\x1b[38;2;1;300;3mc");
+    match result {
+        Err(crate::AnimError::UnableToParseFrame(line, column, _reason)) => {
+            assert_eq!((line, column), (2, 5));
+        }
+        other => panic!("Expected parse error, got {:?}", other),
+    }
+    assert!(crate::ansi::parse_frame("abc\x1b[31").is_err());
+}
+#[test]
+fn parse_line_skips_escape_intermediates() {
+    let mut look = crate::style::DEFAULT;
+    let mut glyphs = Vec::new();
+    crate::ansi::parse_line("\x1b(B\x1b[m", 1, &mut look, &mut glyphs).unwrap();
+    assert!(glyphs.is_empty());
+    crate::ansi::parse_line("\x1b#8x", 1, &mut look, &mut glyphs).unwrap();
+    assert_eq!(glyphs.len(), 1);
+    assert!(crate::ansi::parse_frame("a\x1b(").is_err());
+}
+#[test]
+fn bundle_roundtrip() {
+    let mut first = vec![Glyph::default_with_char('a'); 6];
+    first[1].set_color(crate::Color::Truecolor(1, 2, 3));
+    first[2].set_underline(true);
+    first[5] = Glyph::transparent();
+    let mut library = HashMap::new();
+    library.insert(0, first.clone());
+    library.insert(1, vec![Glyph::default_with_char('b'); 6]);
+    let mut graphic = Graphic::new(3, 2, 1, library, None);
+    graphic.layer = Some(4);
+    graphic.set_frame_name(0, "first");
+    graphic.add_animation(crate::Animation::new(
+        false,
+        true,
+        vec![
+            (0, crate::Timestamp::new(1, 250)),
+            (1, crate::Timestamp::new(0, 40)),
+        ],
+        crate::Timestamp::now(),
+    ));
+    let text = graphic.to_bundle();
+    assert!(text.starts_with("animaterm-bundle 1\n"));
+    assert!(text.contains("animation loop first:1250 frame_1:40"));
+
+    let loaded = Graphic::from_bundle(&text).unwrap();
+    assert_eq!((loaded.cols, loaded.rows, loaded.layer), (3, 2, Some(4)));
+    assert_eq!(loaded.current_frame, 1);
+    assert_eq!(loaded.frame_name(0), Some("first"));
+    let frame = loaded.get_frame(0).unwrap();
+    assert!(frame
+        .iter()
+        .zip(first.iter())
+        .all(|(a, b)| a.character == b.character));
+    assert_eq!(frame[1].color(), crate::Color::Truecolor(1, 2, 3));
+    assert!(frame[2].is_underline() && !frame[3].is_underline());
+    assert!(frame[5].is_transparent());
+    assert_eq!(loaded.animations[&0].ordering()[0].1.as_millis(), 1250);
+}
+#[test]
+fn wide_chars_keep_their_columns_when_saved_and_loaded() {
+    let mut row = crate::utilities::text_to_cells("漢a字", Glyph::default());
+    row[2].set_color(crate::Color::red());
+    row.append(&mut crate::utilities::text_to_cells(
+        "bc😀d",
+        Glyph::default(),
+    ));
+    assert_eq!(row.len(), 10);
+    let characters = |frame: &[Glyph]| frame.iter().map(|g| g.character).collect::<String>();
+    let graphic = Graphic::new(5, 2, 0, HashMap::from([(0, row.clone())]), None);
+
+    let path = std::env::temp_dir().join(format!("animaterm_wide_{}.txb", std::process::id()));
+    graphic.save_bundle(&path).unwrap();
+    let loaded = Graphic::load_bundle(&path);
+    std::fs::remove_file(&path).unwrap();
+    let loaded = loaded.unwrap();
+    assert_eq!((loaded.cols, loaded.rows), (5, 2));
+    let frame = loaded.get_frame(0).unwrap();
+    assert_eq!(characters(&frame), characters(&row));
+    assert_eq!(frame[2].color(), crate::Color::red());
+
+    let dir = std::env::temp_dir().join(format!("animaterm_wide_{}", std::process::id()));
+    let index = graphic.write_to_dir(&dir).unwrap();
+    let loaded = Graphic::from_file(&index);
+    std::fs::remove_dir_all(&dir).unwrap();
+    let loaded = loaded.unwrap();
+    assert_eq!((loaded.cols, loaded.rows), (5, 2));
+    assert_eq!(characters(&loaded.get_frame(0).unwrap()), characters(&row));
+}
+#[test]
+fn bundle_fallback_frame_names_do_not_collide() {
+    let library = HashMap::from([
+        (0, vec![Glyph::default_with_char('a'); 2]),
+        (3, vec![Glyph::default_with_char('b'); 2]),
+    ]);
+    let mut graphic = Graphic::new(2, 1, 3, library, None);
+    graphic.set_frame_name(0, "frame_3");
+    let names = crate::bundle::frame_names(&graphic, &[0, 3]);
+    assert_eq!(names[&0], "frame_3");
+    assert_eq!(names[&3], "frame_3_1");
+    let loaded = Graphic::from_bundle(&graphic.to_bundle()).unwrap();
+    assert_eq!(loaded.get_frame(0).unwrap()[0].character, 'a');
+    assert_eq!(loaded.get_frame(1).unwrap()[0].character, 'b');
+}
+#[test]
+fn bundle_reports_bad_entries() {
+    let text = "animaterm-bundle 1\nsize 2 1\nframe a\nxy\nanimation b:10\n";
+    match Graphic::from_bundle(text) {
+        Err(crate::AnimError::UnableToParseGraphic(line, _reason)) => assert_eq!(line, 5),
+        other => panic!("Expected parse error, got {:?}", other),
+    }
+    assert!(Graphic::from_bundle("animaterm-bundle 9\nsize 1 1\n").is_err());
+}
+#[test]
+fn graphic_written_to_dir_reads_back() {
+    let mut library = HashMap::new();
+    let mut bar = vec![Glyph::default_with_char('░'); 4];
+    bar[0].set_color(crate::Color::green());
+    library.insert(0, bar.clone());
+    library.insert(1, vec![Glyph::default_with_char('█'); 4]);
+    let mut graphic = Graphic::new(2, 2, 0, library, None);
+    graphic.invisible = true;
+    graphic.add_animation(crate::Animation::new(
+        true,
+        false,
+        vec![
+            (1, crate::Timestamp::new(0, 300)),
+            (0, crate::Timestamp::new(2, 0)),
+        ],
+        crate::Timestamp::now(),
+    ));
+    graphic.running_anim = Some(0);
+    graphic.current_frame = 1;
+    graphic.layer = Some(3);
+    let dir = std::env::temp_dir().join(format!("animaterm_write_{}", std::process::id()));
+    let index = graphic.write_to_dir(&dir).unwrap();
+    let text = std::fs::read_to_string(&index).unwrap();
+    assert!(text.contains("invisible\nlayer 3\nframe frame_0 frame_0.txf\n"));
+    assert!(text.contains("animation run frame_1:300 frame_0:2000"));
+    assert!(text.ends_with("current frame_1\n"));
+
+    let loaded = Graphic::from_file(&index).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!((loaded.cols, loaded.rows), (2, 2));
+    assert!(loaded.invisible);
+    assert_eq!(loaded.running_anim, Some(0));
+    assert_eq!(loaded.current_frame, 1);
+    assert_eq!(loaded.layer, Some(3));
+    let frame = loaded.library_frame(0).unwrap();
+    assert_eq!(frame[0].color(), crate::Color::green());
+    assert!(frame
+        .iter()
+        .zip(bar.iter())
+        .all(|(a, b)| a.character == b.character));
+}
+#[test]
+fn graphic_from_str_with_resolver() {
+    let index = "frame a first\nframe b second # comment\nanimation loop run a:100 b:200\n";
+    let graphic = Graphic::from_str(index, |reference| match reference {
+        "first" => crate::frame_from_str("ab\ncd"),
+        "second" => crate::frame_from_reader("\x1b[31mef\ngh".as_bytes()),
+        _ => Err(crate::AnimError::UnableToOpenFile(reference.into())),
+    })
+    .unwrap();
+    assert_eq!((graphic.cols, graphic.rows), (2, 2));
+    assert_eq!(graphic.running_anim, Some(0));
+    assert_eq!(graphic.frame_name(1), Some("b"));
+    assert_eq!(
+        graphic.library_frame(1).unwrap()[0].color(),
+        crate::Color::red()
+    );
+
+    let missing = Graphic::from_str("frame a first\nframe b missing\n", |reference| {
+        if reference == "first" {
+            crate::frame_from_str("ab")
+        } else {
+            Err(crate::AnimError::UnableToOpenFile(reference.into()))
+        }
+    });
+    match missing {
+        Err(crate::AnimError::UnableToReadFrame(line, name, source)) => {
+            assert_eq!((line, name.as_str()), (2, "b"));
+            assert!(matches!(*source, crate::AnimError::UnableToOpenFile(_)));
+        }
+        other => panic!("Expected frame error, got {:?}", other),
+    }
+}
+#[test]
+fn graphic_from_file_skips_bad_lines() {
+    let dir = std::env::temp_dir().join(format!("animaterm_tolerant_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a.txf"), "ab\ncd\n").unwrap();
+    std::fs::write(dir.join("wide.txf"), "abc\ndef\n").unwrap();
+    let index = dir.join("index.txg");
+    std::fs::write(
+        &index,
+        "frame a a.txf\nframe b missing.txf\nframe c wide.txf\nunknown\nanimation loop a:100 b:100\n",
+    )
+    .unwrap();
+    let loaded = Graphic::from_file(&index);
+    // Text loaded from anywhere else still has to be well formed
+    let text = std::fs::read_to_string(&index).unwrap();
+    let strict = Graphic::from_str(&text, |_| crate::frame_from_str("ab\ncd"));
+    std::fs::remove_dir_all(&dir).unwrap();
+    let loaded = loaded.unwrap();
+    assert_eq!((loaded.cols, loaded.rows), (2, 2));
+    assert_eq!(loaded.frame_ids(), vec![0]);
+    assert!(loaded.animations.is_empty());
+    assert!(strict.is_err());
+}
+#[test]
+fn text_input_editing() {
+    use crate::utilities::{InputEvent, TextInput};
+    use crate::Key;
+    let mut input = TextInput::new(5, 1, false).with_max_length(8);
+    for key in [
+        Key::H,
+        Key::I,
+        Key::Space,
+        Key::T,
+        Key::H,
+        Key::E,
+        Key::R,
+        Key::E,
+        Key::X,
+    ] {
+        input.handle_key(&key);
+    }
+    assert_eq!(input.value(), "hi there");
+    input.handle_key(&Key::CtrlLeft);
+    input.handle_key(&Key::CtrlShiftRight);
+    assert_eq!(input.selection(), Some("there".to_string()));
+    assert_eq!(input.handle_key(&Key::Dash), InputEvent::Edited);
+    assert_eq!(input.value(), "hi -");
+    assert_eq!(
+        input.handle_key(&Key::Enter),
+        InputEvent::Submitted("hi -".to_string())
+    );
+    input.clear();
+    assert_eq!(input.handle_key(&Key::Up), InputEvent::Edited);
+    assert_eq!(input.value(), "hi -");
+    let frame = input.render();
+    assert_eq!(frame.len(), 5);
+    assert!(frame[4].is_reverse());
+
+    let mut numbers = TextInput::new(10, 3, true).with_validator(|value| {
+        if value.chars().all(|c| c.is_ascii_digit() || c == '\n') {
+            Ok(())
+        } else {
+            Err("digits only".to_string())
+        }
+    });
+    numbers.set_value("12\n3a");
+    assert_eq!(numbers.cursor(), (2, 1));
+    numbers.handle_key(&Key::Delete);
+    numbers.handle_key(&Key::Delete);
+    numbers.handle_key(&Key::Delete);
+    assert_eq!(numbers.value(), "12");
+    numbers.handle_key(&Key::A);
+    assert_eq!(
+        numbers.handle_key(&Key::AltEnter),
+        InputEvent::Invalid("digits only".to_string())
+    );
+
+    // Wide chars take two columns, cursor and selection follow them
+    let characters = |frame: &[Glyph]| frame.iter().map(|g| g.character).collect::<String>();
+    let mut wide = TextInput::new(6, 2, true);
+    wide.set_value("a漢b\n漢字x");
+    let frame = wide.render();
+    assert_eq!(characters(&frame), "a漢\u{0}b  漢\u{0}字\u{0}x ");
+    assert!(frame[11].is_reverse());
+    wide.handle_key(&Key::Up);
+    assert_eq!(wide.cursor(), (3, 0));
+    wide.handle_key(&Key::Down);
+    assert_eq!(wide.cursor(), (2, 1));
+    wide.handle_key(&Key::Left);
+    wide.handle_key(&Key::Up);
+    // Cursor was on 字 in third column, which 漢 covers in line above
+    assert_eq!(wide.cursor(), (1, 0));
+    wide.handle_key(&Key::Home);
+    wide.handle_key(&Key::ShiftRight);
+    wide.handle_key(&Key::ShiftRight);
+    assert_eq!(wide.selection(), Some("a漢".to_string()));
+    let frame = wide.render();
+    assert!(frame[..3].iter().all(|g| g.is_reverse()));
+    assert!(!frame[4].is_reverse());
+
+    let mut scrolled = TextInput::new(4, 1, false);
+    scrolled.set_value("a漢字xy");
+    // Wide char cut by left edge is blank
+    assert_eq!(characters(&scrolled.render()), " xy ");
+    scrolled.handle_key(&Key::Home);
+    assert_eq!(characters(&scrolled.render()), "a漢\u{0} ");
+}
+#[test]
+fn list_navigation_and_search() {
+    use crate::utilities::{List, ListEvent};
+    use crate::Key;
+    let items = ["apple", "banana", "blueberry", "cherry", "date", "fig"];
+    let mut list = List::new(8, 3, items.iter().map(|i| i.to_string()).collect());
+    assert_eq!(list.handle_key(&Key::Up), ListEvent::Ignored);
+    assert_eq!(list.handle_key(&Key::End), ListEvent::Moved(5));
+    assert_eq!(list.handle_key(&Key::B), ListEvent::Moved(1));
+    assert_eq!(list.handle_key(&Key::L), ListEvent::Moved(2));
+    assert_eq!(list.handle_key(&Key::Enter), ListEvent::Activated(2));
+    list.handle_key(&Key::PgDn);
+    let frame = list.render();
+    assert_eq!(frame.len(), 24);
+    // Items 3..6 are visible, highlighted "fig" is the last row
+    assert_eq!(frame[16].character, 'f');
+    assert!(frame[16].is_reverse());
+    assert_eq!(frame[23].character, '█');
+
+    let mut multi = List::new(6, 2, vec!["one".into(), "two".into()]).with_multi_select();
+    multi.handle_key(&Key::Down);
+    assert_eq!(multi.handle_key(&Key::Space), ListEvent::Toggled(1));
+    assert_eq!(multi.checked(), vec![1]);
+    let frame = multi.render();
+    assert_eq!(frame[7].character, 'x');
+    assert_eq!(frame[11].character, '…');
+}
+#[test]
+fn table_widths_and_sorting() {
+    use crate::utilities::{Align, Cell, Column, Table, TableEvent, Width};
+    use crate::Key;
+    let columns = vec![
+        Column::new("Name", Width::Auto, Align::Left),
+        Column::new("Size", Width::Fixed(4), Align::Right),
+        Column::new("Note", Width::Percent(50), Align::Center),
+    ];
+    let mut table = Table::new(20, 3, columns);
+    table.set_rows(vec![
+        vec![Cell::from("beta"), Cell::from("10"), Cell::from("x")],
+        vec![Cell::from("alpha"), Cell::from("9"), Cell::from("y")],
+        vec![Cell::from("gamma"), Cell::from("100"), Cell::from("z")],
+    ]);
+    assert_eq!(table.column_widths(), vec![5, 4, 9]);
+    assert_eq!(table.handle_key(&Key::Two), TableEvent::Sorted(1, true));
+    assert_eq!(table.selected(), Some(0));
+    assert_eq!(table.handle_key(&Key::Two), TableEvent::Sorted(1, false));
+    assert_eq!(table.handle_key(&Key::End), TableEvent::Moved(1));
+
+    let frame = table.render();
+    let line = |row: usize| -> String {
+        frame[row * 20..(row + 1) * 20]
+            .iter()
+            .map(|g| g.character)
+            .collect()
+    };
+    // Header stays on top, highlighted "alpha" row is scrolled into view
+    assert_eq!(line(0), "Name │Siz…│  Note   ");
+    assert_eq!(line(2), "alpha│   9│    y    ");
+    assert!(frame[40].is_reverse());
+    assert_eq!(table.handle_key(&Key::Zero), TableEvent::Unsorted);
+    assert_eq!(table.handle_key(&Key::Zero), TableEvent::Ignored);
+
+    // Rows added to a sorted table do not move highlight to a different row
+    assert_eq!(table.selected(), Some(1));
+    assert_eq!(table.handle_key(&Key::One), TableEvent::Sorted(0, true));
+    table.push_row(vec![Cell::from("aaron"), Cell::from("1"), Cell::from("w")]);
+    assert_eq!(table.selected(), Some(1));
+    let mut rows: Vec<Vec<Cell>> = (0..4).map(|i| table.row(i).unwrap().clone()).collect();
+    rows.push(vec![Cell::from("abe"), Cell::from("2"), Cell::from("v")]);
+    table.set_rows(rows);
+    assert_eq!(table.selected(), Some(1));
+    table.sort_by(None);
+
+    // Wide chars take two columns, next columns stay aligned
+    table.set_rows(vec![vec![
+        Cell::from("漢字"),
+        Cell::from("1"),
+        Cell::from("w"),
+    ]]);
+    let frame = table.render();
+    let cells: String = frame[20..26].iter().map(|g| g.character).collect();
+    assert_eq!(cells, "漢\u{0}字\u{0} │");
+    let list = crate::utilities::List::new(4, 1, vec!["字a".into()]);
+    let cells: String = list.render().iter().map(|g| g.character).collect();
+    assert_eq!(cells, "字\u{0}a ");
+}
+#[test]
+fn gauge_renders_eighths_and_label() {
+    use crate::utilities::{Gauge, Orientation};
+    let mut gauge = Gauge::new(4, 1, Orientation::Horizontal);
+    gauge.set_fraction(0.5 + 3.0 / 32.0);
+    let chars: String = gauge.render().iter().map(|g| g.character).collect();
+    assert_eq!(chars, "██▍ ");
+
+    let mut vertical = Gauge::new(1, 3, Orientation::Vertical);
+    vertical.set_fraction(0.5);
+    let chars: String = vertical.render().iter().map(|g| g.character).collect();
+    assert_eq!(chars, " ▄█");
+
+    let mut labeled = Gauge::new(6, 1, Orientation::Horizontal).with_percentage();
+    labeled.set_fraction(1.5);
+    let frame = labeled.render();
+    let chars: String = frame.iter().map(|g| g.character).collect();
+    assert_eq!(chars, "█100%█");
+    assert!(frame[1].is_reverse() && !frame[0].is_reverse());
+
+    let mut spinner = Gauge::indeterminate(8, 1, Orientation::Horizontal);
+    let mut starts = Vec::new();
+    for _i in 0..8 {
+        let frame = spinner.render();
+        starts.push(frame.iter().position(|g| g.character == '█').unwrap());
+        spinner.tick();
+    }
+    assert_eq!(starts, vec![0, 1, 2, 3, 4, 5, 6, 5]);
+}
+#[test]
+fn wrap_and_message_box_measure_columns() {
+    use crate::utilities::{char_width, message_box, text_to_cells, text_width, wrap};
+    assert_eq!(char_width('é'), 1);
+    assert_eq!(char_width('漢'), 2);
+    assert_eq!(char_width('\u{301}'), 0);
+    assert_eq!(text_width("zażółć 漢字"), 11);
+    assert_eq!(
+        wrap("zażółć gęślą jaźń\n漢字漢字漢字", 8),
+        vec!["zażółć", "gęślą", "jaźń", "漢字漢字", "漢字"]
+    );
+    let cells = text_to_cells("e\u{301}漢x", Glyph::plain());
+    let chars: Vec<char> = cells.iter().map(|g| g.character).collect();
+    assert_eq!(chars, vec!['e', '漢', '\u{0}', 'x']);
+
+    // Multi-byte words used to be measured in bytes, breaking box's right border
+    let mbox = message_box(None, "źdźbło żółw".to_string(), Glyph::plain(), 12, 4);
+    let frame = mbox.library_frame(0).unwrap();
+    let line = |row: usize| -> String {
+        frame[row * 12..(row + 1) * 12]
+            .iter()
+            .map(|g| g.character)
+            .collect()
+    };
+    assert_eq!(line(1), "│ źdźbło   │");
+    assert_eq!(line(2), "│ żółw     │");
+}
+#[test]
+fn layout_splits_with_constraints() {
+    use crate::layout::{Constraint, Layout, Rect};
+    let area = Rect::new(0, 0, 100, 20);
+    let widths: Vec<usize> = Layout::horizontal(vec![
+        Constraint::Fixed(10),
+        Constraint::Percentage(50),
+        Constraint::Min(5),
+        Constraint::Max(10),
+    ])
+    .split(area)
+    .iter()
+    .map(|r| r.cols)
+    .collect();
+    assert_eq!(widths, vec![10, 50, 30, 10]);
+
+    let rows = Layout::vertical(vec![Constraint::Ratio(1, 3), Constraint::Min(0)])
+        .with_margins(2, 1)
+        .split(area);
+    assert_eq!(rows[0], Rect::new(2, 1, 96, 6));
+    assert_eq!(rows[1], Rect::new(2, 7, 96, 12));
+
+    // Not enough space, last chunks give way
+    let cramped: Vec<usize> = Layout::horizontal(vec![Constraint::Fixed(8), Constraint::Fixed(8)])
+        .split(Rect::new(0, 0, 10, 1))
+        .iter()
+        .map(|r| r.cols)
+        .collect();
+    assert_eq!(cramped, vec![8, 2]);
+}
+#[test]
+fn focus_ring_cycles_and_bubbles() {
+    use crate::focus::{FocusRing, Focusable};
+    use crate::{Key, Manager};
+    struct Stub(bool);
+    impl Focusable for Stub {
+        fn handle_key(&mut self, _mgr: &mut Manager, _key: &Key) -> bool {
+            false
+        }
+        fn set_focused(&mut self, _mgr: &mut Manager, _focused: bool) {}
+        fn accepts_focus(&self) -> bool {
+            self.0
+        }
+    }
+    let mut ring = FocusRing::default();
+    let root = ring.add(Box::new(Stub(true)), None);
+    let panel = ring.add(Box::new(Stub(false)), Some(root));
+    let input = ring.add(Box::new(Stub(true)), Some(panel));
+    assert_eq!(ring.next(true), Some(root));
+    ring.focused = Some(root);
+    // Panel does not accept focus, so it is skipped both ways
+    assert_eq!(ring.next(true), Some(input));
+    assert_eq!(ring.next(false), Some(input));
+    assert_eq!(ring.chain(input), vec![input, panel, root]);
+
+    assert!(ring.remove(panel).is_some());
+    assert_eq!(ring.chain(input), vec![input, root]);
+    ring.focused = Some(input);
+    assert!(ring.remove(input).is_some());
+    assert_eq!(ring.focused, None);
+}
+#[test]
+fn dialog_gives_up_once_keyboard_input_ends() {
+    use crate::utilities::Dialog;
+    use std::sync::mpsc;
+    let (answer_sender, answer) = mpsc::channel();
+    std::thread::spawn(move || {
+        let (mut mgr, _output) = test_manager(30, 10);
+        let (key_sender, key_receiver) = mpsc::channel();
+        mgr.set_key_receiver(key_receiver);
+        key_sender.send(b'x').unwrap();
+        drop(key_sender);
+        let choice = Dialog::new("Pick", "one").choose(&mut mgr, &["a", "b"]);
+        let _ = answer_sender.send((choice, mgr.captures_keyboard()));
+    });
+    // Without telling disconnection apart from timeout dialog would never return
+    assert_eq!(
+        answer.recv_timeout(std::time::Duration::from_secs(5)),
+        Ok((None, false))
+    );
+}
+#[test]
+fn route_key_bubbles_to_parent_and_tab_moves_focus() {
+    use crate::focus::{Focusable, Routed};
+    use crate::{Key, Manager};
+    struct Consumer {
+        consumes: Key,
+        seen: Vec<Key>,
+        focused: bool,
+    }
+    impl Focusable for Consumer {
+        fn handle_key(&mut self, _mgr: &mut Manager, key: &Key) -> bool {
+            self.seen.push(key.clone());
+            *key == self.consumes
+        }
+        fn set_focused(&mut self, _mgr: &mut Manager, focused: bool) {
+            self.focused = focused;
+        }
+    }
+    let consumer = |consumes| {
+        Box::new(Consumer {
+            consumes,
+            seen: Vec::new(),
+            focused: false,
+        })
+    };
+    let (mut mgr, _output) = test_manager(10, 3);
+    let parent = mgr.register_widget(consumer(Key::Enter), None);
+    let child = mgr.register_widget(consumer(Key::X), Some(parent));
+    mgr.set_focus(child);
+
+    assert_eq!(mgr.route_key(Key::X), Routed::Handled(child));
+    // Child ignores Enter, so it bubbles up to parent
+    assert_eq!(mgr.route_key(Key::Enter), Routed::Handled(parent));
+    assert_eq!(
+        mgr.widget::<Consumer>(child).unwrap().seen,
+        vec![Key::X, Key::Enter]
+    );
+    assert_eq!(
+        mgr.widget::<Consumer>(parent).unwrap().seen,
+        vec![Key::Enter]
+    );
+    assert_eq!(mgr.route_key(Key::Y), Routed::Unhandled(Key::Y));
+
+    // Tab nobody consumed moves focus
+    assert_eq!(mgr.route_key(Key::Tab), Routed::FocusMoved(parent));
+    assert!(mgr.widget::<Consumer>(parent).unwrap().focused);
+    assert!(!mgr.widget::<Consumer>(child).unwrap().focused);
+    assert_eq!(mgr.route_key(Key::ShiftTab), Routed::FocusMoved(child));
+    assert_eq!(mgr.focused_widget(), Some(child));
+}
+#[test]
+fn paragraph_wraps_aligns_and_scrolls() {
+    use crate::utilities::{Align, Paragraph};
+    use crate::Key;
+    let lines = |p: &Paragraph| -> Vec<String> {
+        p.render()
+            .chunks(p.cols)
+            .map(|row| row.iter().map(|g| g.character).collect())
+            .collect()
+    };
+    let text = "the quick brown fox jumps over the lazy dog";
+    let mut p =
+        Paragraph::new(12, 2, vec![(text.to_string(), Glyph::plain())]).with_align(Align::Justify);
+    assert_eq!(p.line_count(), 4);
+    assert_eq!(lines(&p), vec!["the    quick", "brown    fox"]);
+    assert!(p.handle_key(&Key::End));
+    // Last line of a paragraph is not stretched
+    assert_eq!(lines(&p), vec!["jumps   over", "the lazy dog"]);
+    assert!(!p.handle_key(&Key::Down));
+
+    let p = Paragraph::new(10, 1, vec![("漢字 and more".to_string(), Glyph::plain())])
+        .with_align(Align::Right)
+        .truncated();
+    assert_eq!(lines(&p), vec!["漢\u{0}字\u{0} and …"]);
+
+    let markup = Paragraph::from_markup(8, 1, "[b]on[/b] [[x] [fg=red]hi[/]!", Glyph::plain());
+    let spans = markup.spans();
+    assert_eq!(spans.len(), 4);
+    assert!(spans[0].1.is_bright() && spans[0].0 == "on");
+    assert_eq!(spans[1].0, " [x] ");
+    assert_eq!(spans[2].1.color(), crate::Color::red());
+    assert!(!spans[3].1.is_bright());
+}
+#[test]
+fn panel_borders_and_tabs_selection() {
+    use crate::utilities::{Align, BorderSet, Panel, Tabs, TabsEvent};
+    use crate::Key;
+    let lines = |frame: Vec<Glyph>, cols: usize| -> Vec<String> {
+        frame
+            .chunks(cols)
+            .map(|row| row.iter().map(|g| g.character).collect())
+            .collect()
+    };
+    let mut panel = Panel::new(12, 3)
+        .with_title("Files")
+        .with_footer("a very long footer")
+        .with_focused_border(BorderSet::Double);
+    assert_eq!(panel.inner_size(), (10, 1));
+    assert_eq!(
+        lines(panel.render(), 12),
+        vec!["┌─ Files ──┐", "│          │", "└ a very … ┘"]
+    );
+    panel.focused = true;
+    panel.title_align = Align::Right;
+    assert_eq!(lines(panel.render(), 12)[0], "╔══ Files ═╗");
+    assert!(panel.render()[0].is_bright());
+    let ascii = Panel::new(4, 2).with_border(BorderSet::Ascii);
+    assert_eq!(lines(ascii.render(), 4), vec!["+--+", "+--+"]);
+
+    let mut tabs = Tabs::new(20, vec!["One".to_string(), "Two".to_string()]);
+    assert_eq!(lines(tabs.render(), 20)[0], " One │ Two          ");
+    assert!(tabs.render()[1].is_reverse());
+    assert_eq!(tabs.handle_key(&Key::Left), TabsEvent::Ignored);
+    assert_eq!(tabs.handle_key(&Key::Right), TabsEvent::Selected(1));
+    assert!(!tabs.render()[1].is_reverse() && tabs.render()[7].is_reverse());
+    assert_eq!(tabs.add_tab("Three", vec![]), 2);
+    assert_eq!(tabs.handle_key(&Key::End), TabsEvent::Selected(2));
+}
+#[test]
+fn viewport_clips_scrolls_and_shows_scrollbars() {
+    use crate::Viewport;
+    let text = "abcdefghijklmnopqrstuvwx";
+    let source = Graphic::from_text(6, text, Glyph::plain());
+    let lines = |v: &Viewport| -> Vec<String> {
+        v.render()
+            .chunks(v.cols)
+            .map(|row| row.iter().map(|g| g.character).collect())
+            .collect()
+    };
+    let mut viewport = Viewport::new(source, 3, 2);
+    assert_eq!(lines(&viewport), vec!["abc", "ghi"]);
+    assert!(viewport.scroll_by((2, 1)));
+    assert_eq!(lines(&viewport), vec!["ijk", "opq"]);
+    // Scrolling is limited to source's size
+    assert!(viewport.scroll_by((10, 10)));
+    assert_eq!(viewport.scroll(), (3, 2));
+    assert_eq!(lines(&viewport), vec!["pqr", "vwx"]);
+    assert!(!viewport.scroll_by((1, 0)));
+
+    let mut viewport = viewport.with_scrollbars();
+    viewport.rows = 3;
+    assert!(viewport.scroll_to((0, 0)));
+    assert_eq!(viewport.content_size(), (2, 2));
+    assert_eq!(viewport.max_scroll(), (4, 2));
+    assert_eq!(lines(&viewport), vec!["ab█", "gh│", "█─ "]);
+    viewport.scroll_to((4, 2));
+    assert_eq!(lines(&viewport), vec!["qr│", "wx█", "─█ "]);
+}
+#[test]
+fn pending_receives_only_its_own_response() {
+    use crate::pending::{self, Request};
+    use crate::{AnimError, AnimOk};
+    use std::sync::mpsc;
+    use std::thread;
+    let (sender, receiver) = mpsc::channel::<Request>();
+    let mut handles = vec![];
+    for _ in 0..4 {
+        let (request, pending) = Request::new(pending::id);
+        handles.push((request.id(), pending));
+        sender.send(request).unwrap();
+    }
+    // Screen answers in reverse order, echoing request ids
+    let screen = thread::spawn(move || {
+        let mut requests: Vec<Request> = receiver.iter().take(4).collect();
+        while let Some(request) = requests.pop() {
+            let id = request.id();
+            request.respond(Ok(AnimOk::GraphicAdded(id)));
+        }
+    });
+    screen.join().unwrap();
+    for (id, pending) in handles {
+        assert_eq!(pending.id(), id);
+        assert_eq!(pending.wait().unwrap(), id);
+    }
+    // Dropped request means no response will ever come
+    let (request, pending) = Request::new(pending::glyph);
+    let id = request.id();
+    drop(request);
+    assert!(matches!(pending.wait(), Err(AnimError::NoResponse(i)) if i == id));
+    // A response of unexpected kind is reported as well
+    let (request, pending) = Request::new(pending::text);
+    request.respond(Ok(AnimOk::GraphicAdded(0)));
+    assert!(matches!(pending.wait(), Err(AnimError::NoResponse(_))));
+}
+#[cfg(feature = "async")]
+#[test]
+fn pending_resolves_as_future_and_wakes_its_task() {
+    use crate::pending::{self, Request};
+    use crate::AnimOk;
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+    struct Counter(AtomicUsize);
+    impl Wake for Counter {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+    let counter = Arc::new(Counter(AtomicUsize::new(0)));
+    let waker = Waker::from(counter.clone());
+    let mut cx = Context::from_waker(&waker);
+    let (request, pending) = Request::new(pending::id);
+    let mut pending = pin!(pending);
+    assert!(pending.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(counter.0.load(Ordering::SeqCst), 0);
+    request.respond(Ok(AnimOk::FrameAdded(3, 7)));
+    assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+    assert!(matches!(pending.as_mut().poll(&mut cx), Poll::Ready(Ok(7))));
+}
+#[test]
+fn terminal_restore_without_guard_does_nothing() {
+    use super::terminal;
+    use std::time::{Duration, Instant};
+    // Nothing was modified, so nothing gets written nor reset
+    terminal::restore();
+    terminal::restore();
+    let start = Instant::now();
+    let _ = terminal::read_byte(Duration::from_millis(10));
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+#[test]
+fn errors_carry_context_and_reach_error_hook() {
+    use crate::AnimError;
+    use std::error::Error;
+    use std::sync::{Arc, Mutex};
+
+    match Graphic::from_file("no/such/graphic.txg") {
+        Err(AnimError::UnableToOpenFile(path)) => {
+            assert_eq!(path, std::path::Path::new("no/such/graphic.txg"))
+        }
+        other => panic!("Expected open error, got {:?}", other),
+    }
+    let error = AnimError::UnableToParseGraphic(3, "bad line".to_string())
+        .in_file(std::path::Path::new("index.txg"));
+    assert_eq!(error.to_string(), "index.txg: graphic line 3: bad line");
+    assert!(error.source().is_some());
+    assert_eq!(
+        Graphic::new(1, 1, 0, HashMap::from([(0, vec![Glyph::default()])]), None)
+            .get_frame(7)
+            .unwrap_err()
+            .to_string(),
+        "there is no frame 7"
+    );
+
+    let reported = Arc::new(Mutex::new(Vec::new()));
+    let sink = reported.clone();
+    crate::set_error_hook(move |e| sink.lock().unwrap().push(e.to_string()));
+    crate::error::report(AnimError::MessageNotSent("Test"));
+    // Unexpected key upon recording start goes to hook instead of being printed
+    let mut macros = crate::macros::Macros::new(Some(vec![(
+        crate::Key::AltM,
+        crate::macros::MacroSequence::empty(),
+    )]));
+    macros.record(&crate::Key::X);
+    assert!(macros.recording.is_none());
+    crate::remove_error_hook();
+    crate::error::report(AnimError::MessageNotSent("Ignored"));
+    {
+        let reported = reported.lock().unwrap();
+        assert!(reported.contains(&"unable to send Test message".to_string()));
+        assert!(reported.contains(&"unexpected key upon recording start: X".to_string()));
+        assert!(!reported.iter().any(|e| e.contains("Ignored")));
+    }
+
+    // A hook replacing itself does not deadlock
+    let replaced = Arc::new(Mutex::new(Vec::new()));
+    let sink = replaced.clone();
+    crate::set_error_hook(move |_e| {
+        let sink = sink.clone();
+        crate::set_error_hook(move |e| sink.lock().unwrap().push(e.to_string()));
+    });
+    crate::error::report(AnimError::MessageNotSent("First"));
+    crate::error::report(AnimError::MessageNotSent("Second"));
+    crate::remove_error_hook();
+    assert!(replaced
+        .lock()
+        .unwrap()
+        .contains(&"unable to send Second message".to_string()));
+}
+#[test]
+fn suspend_response_and_repaint_flag() {
+    use crate::pending;
+    use crate::response::AnimOk;
+    let (request, suspended) = crate::Request::new(pending::suspended);
+    request.respond(Ok(AnimOk::Suspended(true)));
+    assert!(suspended.wait().unwrap());
+    // Process was never stopped, so nothing to repaint
+    assert!(!crate::terminal::take_repaint());
+}
+#[test]
+fn inline_cursor_moves_relative_to_region() {
+    use crate::screen::relative_move;
+    assert_eq!(relative_move(1, (5, 3)), "\x1b[2B\x1b[5G");
+    assert_eq!(relative_move(4, (1, 2)), "\x1b[2A\x1b[1G");
+    assert_eq!(relative_move(2, (7, 2)), "\x1b[7G");
+}
+#[test]
+fn cursor_shapes_map_to_decscusr() {
+    use crate::CursorShape;
+    assert_eq!(CursorShape::default().sequence(), "\x1b[0 q");
+    assert_eq!(CursorShape::BlinkingBlock.sequence(), "\x1b[1 q");
+    assert_eq!(CursorShape::SteadyUnderline.sequence(), "\x1b[4 q");
+    assert_eq!(CursorShape::SteadyBar.sequence(), "\x1b[6 q");
+}
+#[test]
+fn graphic_info_describes_placement_and_animation() {
+    use crate::{Animation, Timestamp};
+    let library = HashMap::from([
+        (0, vec![Glyph::default(); 6]),
+        (1, vec![Glyph::default(); 6]),
+    ]);
+    let mut graphic = Graphic::new(3, 2, 1, library, None);
+    let anim = Animation::new(
+        true,
+        true,
+        vec![(0, Timestamp::new(0, 100)), (1, Timestamp::new(0, 100))],
+        Timestamp::now(),
+    );
+    let aid = graphic.add_animation(anim).unwrap();
+    graphic.invisible = true;
+    let info = graphic.info(4, 2, (-1, 5));
+    assert_eq!((info.id, info.layer, info.current_frame), (4, 2, 1));
+    assert_eq!(info.frames, vec![0, 1]);
+    assert_eq!(info.animations, vec![aid]);
+    assert!(info.invisible);
+    assert_eq!(info.bounds(), (-1, 5, 2, 7));
+    assert!(info.contains(1, 6));
+    assert!(!info.contains(2, 6));
+}
+#[test]
+fn deleted_graphic_ids_are_reused_with_new_generation() {
+    use crate::screen::GraphicIds;
+    let mut ids = GraphicIds::default();
+    let first = ids.next_id().unwrap();
+    let second = ids.next_id().unwrap();
+    assert_eq!((first, second), (0, 1));
+    ids.free(first);
+    let reused = ids.next_id().unwrap();
+    assert_ne!(reused, first);
+    assert_eq!(reused & 0xffff, first);
+    // Stale id does not free a slot used by a newer graphic
+    ids.free(first);
+    assert_eq!(ids.next_id(), Some(2));
+}
+#[test]
+fn batch_defers_printing_until_outermost_ends() {
+    use crate::screen::BatchState;
+    let mut batch = BatchState::default();
+    assert!(!batch.defer(true));
+    batch.begin();
+    batch.begin();
+    assert!(batch.defer(false));
+    assert!(batch.defer(true));
+    assert_eq!(batch.end(), None);
+    assert_eq!(batch.end(), Some(true));
+    // Force is not carried over to next batch
+    batch.begin();
+    assert_eq!(batch.end(), Some(false));
+    assert_eq!(batch.end(), Some(false));
+}
+/// Output of a Screen not attached to a terminal, shared with a test.
+#[derive(Clone, Default)]
+struct SharedOutput(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl SharedOutput {
+    fn len(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+
+    fn take(&self) -> String {
+        String::from_utf8(std::mem::take(&mut *self.0.lock().unwrap())).unwrap()
+    }
+}
+
+impl std::io::Write for SharedOutput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Screen of given size writing to a buffer instead of a terminal.
+fn test_screen(cols: usize, rows: usize) -> (crate::screen::Screen, SharedOutput) {
+    let output = SharedOutput::default();
+    let screen = crate::screen::Screen::with_output(cols, rows, None, Box::new(output.clone()));
+    (screen, output)
+}
+
+/// Manager not capturing keyboard, with it's Screen writing to a buffer.
+fn test_manager(cols: usize, rows: usize) -> (crate::Manager, SharedOutput) {
+    let (screen, output) = test_screen(cols, rows);
+    (crate::Manager::start(screen, false, None, None), output)
+}
+
+#[test]
+fn batch_keeps_message_order_and_prints_once_it_ends() {
+    let (mut mgr, output) = test_manager(10, 3);
+    let library = HashMap::from([(0, vec![Glyph::char('a'); 2])]);
+    let gid = mgr
+        .add_graphic(Graphic::new(2, 1, 0, library, None), 1, (0, 0))
+        .unwrap();
+    let printed = output.len();
+    let swapped = mgr.batch(|mgr| {
+        mgr.set_glyph(gid, Glyph::char('b'), 0, 0);
+        mgr.move_graphic(gid, 1, (3, 1));
+        let swapped = mgr.swap_frame(gid, 0, vec![Glyph::char('c'); 2]);
+        let info = mgr.graphic_info(gid).wait().unwrap();
+        assert_eq!(info.offset, (3, 1));
+        assert_eq!(output.len(), printed);
+        swapped
+    });
+    // Glyph was set before frame got swapped
+    assert_eq!(swapped.unwrap()[0].character, 'b');
+    assert_eq!(mgr.get_glyph(gid, 0, 0).wait().unwrap().character, 'c');
+    assert!(output.len() > printed);
+}
+#[test]
+fn screen_deletes_graphics_and_frees_ids_of_dropped_displays() {
+    let (mut screen, _output) = test_screen(4, 2);
+    let graphic = || {
+        Graphic::new(
+            2,
+            1,
+            0,
+            HashMap::from([(0, vec![Glyph::char('x'); 2])]),
+            None,
+        )
+    };
+    let gid = screen.add_graphic(graphic(), 1, (1, 0)).unwrap();
+    screen.set_graphic(&gid, &0, true);
+    assert!(screen.print_screen().concat().contains("xx"));
+    assert!(screen.delete_graphic(&gid));
+    assert!(screen.graphic_info(gid).is_none());
+    assert!(!screen.print_screen().concat().contains('x'));
+    assert!(!screen.delete_graphic(&gid));
+
+    // Graphics of a display that is not kept give their ids back
+    let kept = screen.add_graphic(graphic(), 1, (0, 0)).unwrap();
+    assert_eq!(kept & 0xffff, gid & 0xffff);
+    screen.new_display(1, false);
+    let fresh = screen.add_graphic(graphic(), 1, (0, 0)).unwrap();
+    assert_eq!(fresh & 0xffff, kept & 0xffff);
+    assert_ne!(fresh, kept);
+}
+#[test]
+fn resized_screen_keeps_graphics_and_shows_their_uncovered_parts() {
+    let (mut screen, output) = test_screen(4, 2);
+    let library = HashMap::from([(0, "abcdef".chars().map(Glyph::char).collect())]);
+    let gid = screen
+        .add_graphic(Graphic::new(6, 1, 0, library, None), 1, (0, 0))
+        .unwrap();
+    screen.set_graphic(&gid, &0, true);
+    assert!(screen.print_screen().concat().contains("abcd"));
+    assert!(!screen.print_screen().concat().contains("abcde"));
+
+    output.take();
+    screen.resize(6, 3);
+    assert_eq!((screen.cols, screen.rows), (6, 3));
+    assert_eq!(screen.display.array.len(), 18);
+    assert!(screen.print_screen().concat().contains("abcdef"));
+    // Whole display is printed again in it's new size
+    assert!(output.take().contains("abcdef"));
+
+    screen.resize(2, 2);
+    assert_eq!(screen.display.array.len(), 4);
+    let text = screen.print_screen().concat();
+    assert!(text.contains("ab") && !text.contains('c'));
+    screen.resize(3, 2);
+    assert!(screen.print_screen().concat().contains("abc"));
+}
+#[test]
+fn screen_sends_only_attributes_that_changed() {
+    let (mut screen, output) = test_screen(4, 2);
+    let mut red = Glyph::char('a');
+    red.set_color(crate::Color::red());
+    let mut underlined = red;
+    underlined.set_char('b');
+    underlined.set_underline(true);
+    let mut same = underlined;
+    same.set_char('c');
+    let library = HashMap::from([(0, vec![red, underlined, same])]);
+    let gid = screen
+        .add_graphic(Graphic::new(3, 1, 0, library, None), 1, (0, 0))
+        .unwrap();
+    output.take();
+    screen.set_graphic(&gid, &0, true);
+    let printed = output.take();
+    // Full description after a flush, then only differences
+    assert!(
+        printed.contains("\x1b[0;31;40ma\x1b[4;31;40mbc"),
+        "{:?}",
+        printed
+    );
+}
+#[test]
+fn suspended_screen_writes_nothing_and_repaints_on_resume() {
+    let (mut screen, output) = test_screen(4, 2);
+    let library = HashMap::from([(0, vec![Glyph::char('x'); 2])]);
+    let gid = screen
+        .add_graphic(Graphic::new(2, 1, 0, library, None), 1, (0, 0))
+        .unwrap();
+    screen.set_graphic(&gid, &0, true);
+    output.take();
+
+    screen.suspend();
+    assert!(screen.is_suspended());
+    let written = output.len();
+    screen.set_glyph(gid, Glyph::char('y'), 1, 0);
+    screen.clear_screen();
+    screen.place_cursor(3, 2);
+    assert_eq!(output.len(), written);
+    // Screen keeps track of changes anyway
+    assert!(screen.print_screen().concat().contains("xy"));
+
+    output.take();
+    screen.resume();
+    assert!(!screen.is_suspended());
+    // Glyph that did not change while suspended gets printed again as well
+    let repainted = output.take();
+    assert!(repainted.contains('x'));
+    assert!(repainted.contains('y'));
+}
+#[test]
+fn inline_clear_starts_from_region_top_and_parks_cursor() {
+    let (screen, output) = test_screen(5, 10);
+    let mut screen = screen.into_inline(3, None);
+    screen.initialize();
+    screen.show_cursor(true);
+    screen.place_cursor(2, 3);
+    output.take();
+    screen.clear_screen();
+    assert_eq!(
+        output.take(),
+        "\x1b[?25l\x1b[0m\x1b[2A\x1b[1G\x1b[2K\x1b[B\x1b[2K\x1b[B\x1b[2K\x1b[2G\x1b[?25h"
+    );
+}
+#[cfg(feature = "async")]
+#[test]
+fn keys_stream_decodes_sequences_without_busy_polling() {
+    use crate::helpers::sequence_len;
+    use crate::wake::KeyWaker;
+    use crate::Key;
+    use futures_core::Stream;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{mpsc, Arc};
+    use std::task::{Context, Poll, Wake, Waker};
+    struct Counter(AtomicUsize);
+    impl Wake for Counter {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+    assert_eq!(sequence_len(&[27]), None);
+    assert_eq!(sequence_len(&[27, b'[', b'1', b';']), None);
+    assert_eq!(
+        sequence_len(&[27, b'[', b'1', b';', b'5', b'C', b'x']),
+        Some(6)
+    );
+    assert_eq!(sequence_len(&[0xe6, 0xbc]), None);
+    assert_eq!(sequence_len(&[0xe6, 0xbc, 0xa2, b'a']), Some(3));
+
+    let counter = Arc::new(Counter(AtomicUsize::new(0)));
+    let waker = Waker::from(counter.clone());
+    let mut cx = Context::from_waker(&waker);
+    let (mut mgr, _output) = test_manager(4, 2);
+    let (sender, receiver) = mpsc::channel();
+    mgr.set_key_receiver(receiver);
+    let mut keys = mgr.keys();
+    let mut keys = Pin::new(&mut keys);
+    for byte in [b'a', 27, b'[', b'A', 27] {
+        sender.send(byte).unwrap();
+    }
+    assert_eq!(keys.as_mut().poll_next(&mut cx), Poll::Ready(Some(Key::A)));
+    assert_eq!(keys.as_mut().poll_next(&mut cx), Poll::Ready(Some(Key::Up)));
+    // Lone Escape waits for keyboard to go quiet, without waking task by itself
+    assert_eq!(keys.as_mut().poll_next(&mut cx), Poll::Pending);
+    assert_eq!(counter.0.load(Ordering::SeqCst), 0);
+    drop(sender);
+    assert_eq!(
+        keys.as_mut().poll_next(&mut cx),
+        Poll::Ready(Some(Key::Escape))
+    );
+    assert_eq!(keys.as_mut().poll_next(&mut cx), Poll::Ready(None));
+
+    let key_waker = KeyWaker::default();
+    key_waker.register(&waker);
+    key_waker.idle();
+    assert_eq!(counter.0.load(Ordering::SeqCst), 0);
+    key_waker.wake_when_idle();
+    key_waker.idle();
+    assert_eq!(counter.0.load(Ordering::SeqCst), 1);
 }
@@ -23,6 +23,11 @@ impl Timestamp {
     pub fn new(sec: u64, msec: u32) -> Self {
         Timestamp(sec, msec, Instant::now())
     }
+
+    /// Get represented time in miliseconds.
+    pub fn as_millis(&self) -> u64 {
+        self.0 * 1000 + self.1 as u64
+    }
 }
 impl PartialEq for Timestamp {
     fn eq(&self, other: &Timestamp) -> bool {
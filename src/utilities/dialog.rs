@@ -0,0 +1,283 @@
+use super::{fit, text_to_cells, text_width, wrap, Align, InputEvent, List, ListEvent, TextInput};
+use crate::error::{report, AnimError};
+use crate::glyph::Glyph;
+use crate::key::Key;
+use crate::manager::Manager;
+use std::ops::ControlFlow;
+
+/// Choose is shown with at most that many options visible at once.
+const MAX_OPTIONS_VISIBLE: usize = 10;
+
+/// A modal window centered on screen asking user a question.
+/// While a dialog is open it takes all keys read by Manager
+/// and returns only when user answers or presses Escape.
+/// Dialog is placed on a layer that should be above all other graphics,
+/// and glyphs below it can be dimmed to bring user's attention.
+pub struct Dialog {
+    pub title: String,
+    pub message: String,
+    pub layer: usize,
+    pub dim_background: bool,
+    /// Widest a dialog can get including it's border, message is wrapped to fit.
+    pub max_width: usize,
+    pub glyph: Glyph,
+    pub border_glyph: Glyph,
+    pub button_glyph: Glyph,
+    pub selected_glyph: Glyph,
+}
+
+impl Dialog {
+    /// Create a new dialog with given title and message.
+    pub fn new(title: &str, message: &str) -> Self {
+        let mut glyph = Glyph::default();
+        glyph.set_char(' ');
+        let mut border_glyph = glyph;
+        border_glyph.set_bright(true);
+        let mut selected_glyph = glyph;
+        selected_glyph.set_reverse(true);
+        Dialog {
+            title: title.to_string(),
+            message: message.to_string(),
+            layer: 16,
+            dim_background: true,
+            max_width: 60,
+            glyph,
+            border_glyph,
+            button_glyph: glyph,
+            selected_glyph,
+        }
+    }
+
+    /// Place dialog on given layer.
+    pub fn with_layer(mut self, layer: usize) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    /// Keep glyphs below dialog at their normal brightness.
+    pub fn without_dimming(mut self) -> Self {
+        self.dim_background = false;
+        self
+    }
+
+    /// Limit how wide a dialog can get.
+    pub fn with_max_width(mut self, max_width: usize) -> Self {
+        self.max_width = max_width;
+        self
+    }
+
+    /// Ask a yes or no question. Left, Right and Tab switch between buttons,
+    /// Enter confirms highlighted one, y and n answer directly, Escape means no.
+    pub fn confirm(&self, mgr: &mut Manager) -> bool {
+        let mut yes = true;
+        let answer = self.run(mgr, 15, 1, |key| {
+            match key {
+                None => {}
+                Some(Key::Left | Key::Right | Key::Tab | Key::ShiftTab) => yes = !yes,
+                Some(Key::Enter) => return ControlFlow::Break(yes),
+                Some(Key::Y | Key::ShiftY) => return ControlFlow::Break(true),
+                Some(Key::N | Key::ShiftN) => return ControlFlow::Break(false),
+                Some(_) => {}
+            }
+            ControlFlow::Continue(self.buttons(yes))
+        });
+        answer.unwrap_or(false)
+    }
+
+    /// Ask user to type in some text, starting with initial value.
+    /// Returns None when user pressed Escape.
+    pub fn prompt(&self, mgr: &mut Manager, initial: &str) -> Option<String> {
+        let cols = self.inner_width(mgr);
+        let mut input = TextInput::new(cols, 1, false);
+        input.glyph = self.glyph;
+        input.set_value(initial);
+        self.run(mgr, cols, 1, |key| {
+            if let Some(key) = key {
+                if let InputEvent::Submitted(value) = input.handle_key(key) {
+                    return ControlFlow::Break(value);
+                }
+            }
+            ControlFlow::Continue(input.render())
+        })
+    }
+
+    /// Ask user to pick one of options, returns index of chosen one
+    /// or None when user pressed Escape.
+    pub fn choose(&self, mgr: &mut Manager, options: &[&str]) -> Option<usize> {
+        if options.is_empty() {
+            return None;
+        }
+        let widest = options.iter().map(|o| text_width(o)).max().unwrap_or(0);
+        let inner = self.inner_width(mgr);
+        if inner == 0 {
+            return None;
+        }
+        let cols = (widest + 2).max(15).min(inner);
+        let rows = options.len().min(MAX_OPTIONS_VISIBLE);
+        let mut list = List::new(
+            cols,
+            rows,
+            options.iter().map(|option| option.to_string()).collect(),
+        );
+        list.glyph = self.glyph;
+        list.highlight_glyph = self.selected_glyph;
+        self.run(mgr, cols, rows, |key| {
+            if let Some(key) = key {
+                if let ListEvent::Activated(index) = list.handle_key(key) {
+                    return ControlFlow::Break(index);
+                }
+            }
+            ControlFlow::Continue(list.render())
+        })
+    }
+
+    /// Widest a dialog's content can be on Manager's screen.
+    fn inner_width(&self, mgr: &Manager) -> usize {
+        let (cols, _rows) = mgr.screen_size();
+        self.max_width
+            .min(cols.saturating_sub(4))
+            .saturating_sub(4)
+            .max(1)
+    }
+
+    fn buttons(&self, yes: bool) -> Vec<Glyph> {
+        let (yes_glyph, no_glyph) = if yes {
+            (self.selected_glyph, self.button_glyph)
+        } else {
+            (self.button_glyph, self.selected_glyph)
+        };
+        let mut glyphs = text_to_cells("[ Yes ]", yes_glyph);
+        glyphs.append(&mut text_to_cells("  ", self.glyph));
+        glyphs.append(&mut text_to_cells("[ No ]", no_glyph));
+        glyphs
+    }
+
+    /// Show dialog with a body of given size below it's message, and pass every key to step,
+    /// which returns body's new look or an answer. Escape closes dialog without an answer.
+    /// Without keyboard captured by Manager there is no answer and dialog is not shown.
+    fn run<R, F>(
+        &self,
+        mgr: &mut Manager,
+        body_cols: usize,
+        body_rows: usize,
+        mut step: F,
+    ) -> Option<R>
+    where
+        F: FnMut(Option<&Key>) -> ControlFlow<R, Vec<Glyph>>,
+    {
+        if !mgr.captures_keyboard() {
+            report(AnimError::KeyboardNotCaptured);
+            return None;
+        }
+        let inner = self.inner_width(mgr);
+        let lines = wrap(&self.message, inner);
+        let content = lines
+            .iter()
+            .map(|line| text_width(line))
+            .chain([body_cols, text_width(&self.title) + 2])
+            .max()
+            .unwrap_or(1)
+            .min(inner);
+        let width = content + 4;
+        let height = lines.len() + body_rows + 3;
+        let (cols, rows) = mgr.screen_size();
+        let offset = (
+            (cols.saturating_sub(width) / 2) as isize,
+            (rows.saturating_sub(height) / 2) as isize,
+        );
+
+        let frame = |body: Vec<Glyph>| self.frame(&lines, content, body, body_cols);
+        let ControlFlow::Continue(body) = step(None) else {
+            return None;
+        };
+        let mut shown = 0;
+        let gid = mgr.add_graphic(
+            super::widget_graphic(width, frame(body)),
+            self.layer,
+            offset,
+        )?;
+        if self.dim_background {
+            mgr.dim_below(Some(self.layer));
+        }
+        let answer = loop {
+            let Some(key) = mgr.read_key() else {
+                // Keyboard input has ended, so no answer will ever come
+                if !mgr.captures_keyboard() {
+                    break None;
+                }
+                continue;
+            };
+            if key == Key::Escape {
+                break None;
+            }
+            match step(Some(&key)) {
+                ControlFlow::Break(answer) => break Some(answer),
+                ControlFlow::Continue(body) => {
+                    super::redraw(mgr, gid, frame(body), &mut shown);
+                }
+            }
+        };
+        mgr.delete_graphic(gid);
+        if self.dim_background {
+            mgr.dim_below(None);
+        }
+        answer
+    }
+
+    /// Build dialog's frame: a rounded border with title, wrapped message,
+    /// an empty line and body centered below.
+    fn frame(
+        &self,
+        lines: &[String],
+        content: usize,
+        body: Vec<Glyph>,
+        body_cols: usize,
+    ) -> Vec<Glyph> {
+        let width = content + 4;
+        let mut frame = Vec::with_capacity(width * (lines.len() + 4));
+        let mut border = self.border_glyph;
+        let push_row = |frame: &mut Vec<Glyph>, cells: &[Glyph], align: Align| {
+            let mut side = self.border_glyph;
+            side.set_char('│');
+            frame.push(side);
+            frame.push(self.glyph);
+            frame.append(&mut fit(cells, content, align, self.glyph));
+            frame.push(self.glyph);
+            frame.push(side);
+        };
+
+        let mut title = Vec::new();
+        if !self.title.is_empty() {
+            title = text_to_cells(&format!(" {} ", self.title), self.border_glyph);
+            if title.len() > content + 2 {
+                title = fit(&title, content + 2, Align::Left, self.border_glyph);
+            }
+        }
+        let before = (content + 2 - title.len()) / 2;
+        border.set_char('─');
+        let mut top = vec![border; before];
+        top.append(&mut title);
+        top.resize(content + 2, border);
+        border.set_char('╭');
+        frame.push(border);
+        frame.append(&mut top);
+        border.set_char('╮');
+        frame.push(border);
+
+        for line in lines {
+            push_row(&mut frame, &text_to_cells(line, self.glyph), Align::Left);
+        }
+        push_row(&mut frame, &[], Align::Left);
+        for row in body.chunks(body_cols.max(1)) {
+            push_row(&mut frame, row, Align::Center);
+        }
+
+        border.set_char('╰');
+        frame.push(border);
+        border.set_char('─');
+        frame.resize(frame.len() + content + 2, border);
+        border.set_char('╯');
+        frame.push(border);
+        frame
+    }
+}
@@ -0,0 +1,198 @@
+use crate::glyph::Glyph;
+use crate::manager::Manager;
+
+/// Partially filled cells of a horizontal gauge, from empty to full in eighths.
+const HORIZONTAL: [char; 9] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+/// Partially filled cells of a vertical gauge, from empty to full in eighths.
+const VERTICAL: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Direction in which a gauge fills up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    /// Fills from left to right.
+    Horizontal,
+    /// Fills from bottom to top.
+    Vertical,
+}
+
+/// A bar of cols x rows size showing how much of a task is done,
+/// with eight steps of resolution within every cell.
+/// An indeterminate gauge instead shows a segment bouncing from side to side,
+/// moved by one cell with every tick.
+/// Only glyphs that changed since last draw are printed on screen.
+pub struct Gauge {
+    pub cols: usize,
+    pub rows: usize,
+    pub orientation: Orientation,
+    /// Text placed in the middle of a gauge.
+    pub label: Option<String>,
+    /// Show percentage as a label, when no label is set.
+    pub show_percentage: bool,
+    /// Glyph used for filled part, only it's color and style matter.
+    pub glyph: Glyph,
+    /// Glyph used for empty part.
+    pub empty_glyph: Glyph,
+    pub indeterminate: bool,
+    pub graphic_id: Option<usize>,
+    fraction: f32,
+    ticks: usize,
+    shown_frame: usize,
+}
+
+impl Gauge {
+    /// Create an empty gauge of given size.
+    pub fn new(cols: usize, rows: usize, orientation: Orientation) -> Self {
+        let mut empty_glyph = Glyph::default();
+        empty_glyph.set_char(' ');
+        Gauge {
+            cols: cols.max(1),
+            rows: rows.max(1),
+            orientation,
+            label: None,
+            show_percentage: false,
+            glyph: Glyph::default(),
+            empty_glyph,
+            indeterminate: false,
+            graphic_id: None,
+            fraction: 0.0,
+            ticks: 0,
+            shown_frame: 0,
+        }
+    }
+
+    /// Create a gauge showing activity without known progress.
+    pub fn indeterminate(cols: usize, rows: usize, orientation: Orientation) -> Self {
+        let mut gauge = Gauge::new(cols, rows, orientation);
+        gauge.indeterminate = true;
+        gauge
+    }
+
+    /// Show percentage in the middle of a gauge.
+    pub fn with_percentage(mut self) -> Self {
+        self.show_percentage = true;
+        self
+    }
+
+    /// How much of a gauge is filled, from 0.0 to 1.0.
+    pub fn fraction(&self) -> f32 {
+        self.fraction
+    }
+
+    /// Set how much of a gauge is filled, value is clamped to 0.0..=1.0.
+    pub fn set_fraction(&mut self, fraction: f32) {
+        self.fraction = if fraction.is_nan() {
+            0.0
+        } else {
+            fraction.clamp(0.0, 1.0)
+        };
+    }
+
+    /// Move indeterminate gauge's segment by one cell.
+    pub fn tick(&mut self) {
+        self.ticks = self.ticks.wrapping_add(1);
+    }
+
+    /// Build a frame presenting gauge's current state.
+    pub fn render(&self) -> Vec<Glyph> {
+        let length = match self.orientation {
+            Orientation::Horizontal => self.cols,
+            Orientation::Vertical => self.rows,
+        };
+        // Eighths filled in every cell along gauge's length
+        let units: Vec<usize> = if self.indeterminate {
+            let segment = (length / 4).max(1);
+            let period = 2 * (length - segment);
+            let mut start = if period > 0 { self.ticks % period } else { 0 };
+            if start > length - segment {
+                start = period - start;
+            }
+            (0..length)
+                .map(|i| {
+                    if i >= start && i < start + segment {
+                        8
+                    } else {
+                        0
+                    }
+                })
+                .collect()
+        } else {
+            let filled = (self.fraction * (length * 8) as f32).round() as usize;
+            (0..length)
+                .map(|i| filled.saturating_sub(i * 8).min(8))
+                .collect()
+        };
+
+        let mut partial = self.glyph;
+        partial.set_background(self.empty_glyph.background());
+        let cell = |units: usize| -> Glyph {
+            match units {
+                0 => self.empty_glyph,
+                _ => {
+                    let mut g = partial;
+                    g.set_char(match self.orientation {
+                        Orientation::Horizontal => HORIZONTAL[units],
+                        Orientation::Vertical => VERTICAL[units],
+                    });
+                    g
+                }
+            }
+        };
+        let mut frame = Vec::with_capacity(self.cols * self.rows);
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                frame.push(match self.orientation {
+                    Orientation::Horizontal => cell(units[col]),
+                    Orientation::Vertical => cell(units[self.rows - 1 - row]),
+                });
+            }
+        }
+
+        let label = match (&self.label, self.show_percentage && !self.indeterminate) {
+            (Some(label), _) => label.clone(),
+            (None, true) => format!("{:.0}%", self.fraction * 100.0),
+            (None, false) => String::new(),
+        };
+        let label: Vec<char> = label.chars().take(self.cols).collect();
+        if !label.is_empty() {
+            let row = self.rows / 2;
+            let start = (self.cols - label.len()) / 2;
+            for (i, character) in label.into_iter().enumerate() {
+                let col = start + i;
+                let position = match self.orientation {
+                    Orientation::Horizontal => col,
+                    Orientation::Vertical => self.rows - 1 - row,
+                };
+                let glyph = &mut frame[row * self.cols + col];
+                // Over filled part label is printed with swapped colors
+                if units[position] >= 4 {
+                    *glyph = partial;
+                    glyph.set_reverse(!partial.is_reverse());
+                } else {
+                    *glyph = self.empty_glyph;
+                }
+                glyph.set_char(character);
+            }
+        }
+        frame
+    }
+
+    /// Add gauge to Manager's current display as a new graphic.
+    pub fn show(
+        &mut self,
+        mgr: &mut Manager,
+        layer: usize,
+        offset: (isize, isize),
+    ) -> Option<usize> {
+        let graphic = super::widget_graphic(self.cols, self.render());
+        self.shown_frame = 0;
+        self.graphic_id = mgr.add_graphic(graphic, layer, offset);
+        self.graphic_id
+    }
+
+    /// Update gauge's graphic on screen.
+    pub fn draw(&mut self, mgr: &mut Manager) {
+        if let Some(gid) = self.graphic_id {
+            super::redraw(mgr, gid, self.render(), &mut self.shown_frame);
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use super::{fit, text_to_cells, Align};
+use crate::focus::Focusable;
+use crate::glyph::Glyph;
+use crate::helpers::map_key_to_char;
+use crate::key::Key;
+use crate::manager::Manager;
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+
+/// Typed chars are joined into a single search phrase when they come within this time.
+const SEARCH_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// Outcome of passing a Key to a List.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListEvent {
+    /// Key has no meaning for a list.
+    Ignored,
+    /// Highlight has moved to item with given index.
+    Moved(usize),
+    /// Item with given index was checked or unchecked in a multi-select list.
+    Toggled(usize),
+    /// Enter was pressed on item with given index.
+    Activated(usize),
+}
+
+/// A vertical list of items of cols x rows size with one item highlighted.
+/// Up, Down, PgUp, PgDn, Home and End move highlight, typing moves it to the next item
+/// starting with typed text, Enter activates highlighted item.
+/// In a multi-select list Space checks or unchecks highlighted item.
+/// When there are more items than rows a scrollbar is shown in the last column.
+pub struct List {
+    pub cols: usize,
+    pub rows: usize,
+    pub items: Vec<String>,
+    pub multi_select: bool,
+    pub glyph: Glyph,
+    pub highlight_glyph: Glyph,
+    pub scrollbar_glyph: Glyph,
+    /// Highlight is dimmed when list is not focused.
+    pub focused: bool,
+    pub graphic_id: Option<usize>,
+    shown_frame: usize,
+    selected: usize,
+    top: usize,
+    checked: BTreeSet<usize>,
+    search: String,
+    last_search: Option<Instant>,
+    event: Option<ListEvent>,
+}
+
+impl List {
+    /// Create a new list of given size presenting items.
+    pub fn new(cols: usize, rows: usize, items: Vec<String>) -> Self {
+        let mut highlight_glyph = Glyph::default();
+        highlight_glyph.set_reverse(true);
+        let mut scrollbar_glyph = Glyph::default();
+        scrollbar_glyph.set_dim(true);
+        List {
+            cols: cols.max(1),
+            rows: rows.max(1),
+            items,
+            multi_select: false,
+            glyph: Glyph::default(),
+            highlight_glyph,
+            scrollbar_glyph,
+            focused: true,
+            graphic_id: None,
+            shown_frame: 0,
+            selected: 0,
+            top: 0,
+            checked: BTreeSet::new(),
+            search: String::new(),
+            last_search: None,
+            event: None,
+        }
+    }
+
+    /// Allow checking multiple items with Space.
+    pub fn with_multi_select(mut self) -> Self {
+        self.multi_select = true;
+        self
+    }
+
+    /// Index of highlighted item.
+    pub fn selected(&self) -> Option<usize> {
+        if self.items.is_empty() {
+            None
+        } else {
+            Some(self.selected)
+        }
+    }
+
+    /// Highlight item with given index.
+    pub fn select(&mut self, index: usize) {
+        if !self.items.is_empty() {
+            self.selected = index.min(self.items.len() - 1);
+            self.scroll_to_selected();
+        }
+    }
+
+    /// Indices of checked items in ascending order.
+    pub fn checked(&self) -> Vec<usize> {
+        self.checked.iter().copied().collect()
+    }
+
+    /// Replace all items, keeping highlight within bounds.
+    pub fn set_items(&mut self, items: Vec<String>) {
+        self.items = items;
+        let len = self.items.len();
+        self.checked.retain(|i| *i < len);
+        self.selected = self.selected.min(len.saturating_sub(1));
+        self.scroll_to_selected();
+    }
+
+    /// Update list according to a key pressed by user.
+    pub fn handle_key(&mut self, key: &Key) -> ListEvent {
+        if self.items.is_empty() {
+            return ListEvent::Ignored;
+        }
+        let last = self.items.len() - 1;
+        let previous = self.selected;
+        let mut searching = false;
+        self.selected = match key {
+            Key::Up | Key::CtrlP => self.selected.saturating_sub(1),
+            Key::Down | Key::CtrlN => (self.selected + 1).min(last),
+            Key::PgUp => self.selected.saturating_sub(self.rows),
+            Key::PgDn => (self.selected + self.rows).min(last),
+            Key::Home => 0,
+            Key::End => last,
+            Key::Enter => return ListEvent::Activated(self.selected),
+            Key::Space if self.multi_select => {
+                if !self.checked.remove(&self.selected) {
+                    self.checked.insert(self.selected);
+                }
+                return ListEvent::Toggled(self.selected);
+            }
+            other => match map_key_to_char(other) {
+                Some(character) => {
+                    searching = true;
+                    self.search_for(character)
+                }
+                None => return ListEvent::Ignored,
+            },
+        };
+        if !searching {
+            self.search.clear();
+        }
+        self.scroll_to_selected();
+        if self.selected == previous {
+            ListEvent::Ignored
+        } else {
+            ListEvent::Moved(self.selected)
+        }
+    }
+
+    /// Build a frame presenting list's current state.
+    pub fn render(&self) -> Vec<Glyph> {
+        let mut frame = Vec::with_capacity(self.cols * self.rows);
+        let with_scrollbar = self.items.len() > self.rows && self.cols > 1;
+        let width = if with_scrollbar {
+            self.cols - 1
+        } else {
+            self.cols
+        };
+        let thumb = self.scrollbar_thumb();
+        let mut highlight_glyph = self.highlight_glyph;
+        if !self.focused {
+            highlight_glyph.set_dim(true);
+        }
+        for row in 0..self.rows {
+            let index = self.top + row;
+            let glyph = if index == self.selected {
+                highlight_glyph
+            } else {
+                self.glyph
+            };
+            let mut text = String::with_capacity(width);
+            if let Some(item) = self.items.get(index) {
+                if self.multi_select {
+                    text.push_str(if self.checked.contains(&index) {
+                        "[x] "
+                    } else {
+                        "[ ] "
+                    });
+                }
+                text.push_str(item);
+            }
+            frame.append(&mut fit(
+                &text_to_cells(&text, glyph),
+                width,
+                Align::Left,
+                glyph,
+            ));
+            if with_scrollbar {
+                let mut g = self.scrollbar_glyph;
+                g.set_char(if thumb.contains(&row) { '█' } else { '│' });
+                frame.push(g);
+            }
+        }
+        frame
+    }
+
+    /// Add list to Manager's current display as a new graphic.
+    pub fn show(
+        &mut self,
+        mgr: &mut Manager,
+        layer: usize,
+        offset: (isize, isize),
+    ) -> Option<usize> {
+        let graphic = super::widget_graphic(self.cols, self.render());
+        self.shown_frame = 0;
+        self.graphic_id = mgr.add_graphic(graphic, layer, offset);
+        self.graphic_id
+    }
+
+    /// Redraw list's graphic on screen.
+    pub fn draw(&mut self, mgr: &mut Manager) {
+        if let Some(gid) = self.graphic_id {
+            super::redraw(mgr, gid, self.render(), &mut self.shown_frame);
+        }
+    }
+
+    /// Event caused by last key routed to list by Manager.
+    pub fn take_event(&mut self) -> Option<ListEvent> {
+        self.event.take()
+    }
+
+    /// Find next item starting with search phrase extended by given char.
+    fn search_for(&mut self, character: char) -> usize {
+        let now = Instant::now();
+        let expired = self
+            .last_search
+            .map(|t| now.duration_since(t) > SEARCH_TIMEOUT)
+            .unwrap_or(true);
+        if expired {
+            self.search.clear();
+        }
+        self.last_search = Some(now);
+        self.search.extend(character.to_lowercase());
+        // Repeating the same char cycles through items starting with it
+        let start = if self.search.chars().count() == 1 {
+            self.selected + 1
+        } else {
+            self.selected
+        };
+        let len = self.items.len();
+        (start..start + len)
+            .map(|i| i % len)
+            .find(|i| self.items[*i].to_lowercase().starts_with(&self.search))
+            .unwrap_or(self.selected)
+    }
+
+    fn scroll_to_selected(&mut self) {
+        if self.selected < self.top {
+            self.top = self.selected;
+        } else if self.selected >= self.top + self.rows {
+            self.top = self.selected + 1 - self.rows;
+        }
+        self.top = self.top.min(self.items.len().saturating_sub(self.rows));
+    }
+
+    /// Rows occupied by scrollbar's thumb.
+    fn scrollbar_thumb(&self) -> std::ops::Range<usize> {
+        let len = self.items.len().max(1);
+        let size = (self.rows * self.rows / len).clamp(1, self.rows);
+        let max_top = len.saturating_sub(self.rows).max(1);
+        let start = (self.rows - size) * self.top / max_top;
+        start..start + size
+    }
+}
+
+impl Focusable for List {
+    fn handle_key(&mut self, mgr: &mut Manager, key: &Key) -> bool {
+        let event = List::handle_key(self, key);
+        if event == ListEvent::Ignored {
+            return false;
+        }
+        self.event = Some(event);
+        self.draw(mgr);
+        true
+    }
+
+    fn set_focused(&mut self, mgr: &mut Manager, focused: bool) {
+        self.focused = focused;
+        self.draw(mgr);
+    }
+}
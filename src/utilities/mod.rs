@@ -0,0 +1,322 @@
+use super::animation::Animation;
+use super::glyph::Glyph;
+use super::graphic::Graphic;
+use super::manager::Manager;
+use super::time::Timestamp;
+use std::collections::HashMap;
+use std::mem::replace;
+
+mod dialog;
+mod gauge;
+mod list;
+mod panel;
+mod paragraph;
+mod table;
+mod tabs;
+mod text_input;
+pub use dialog::Dialog;
+pub use gauge::{Gauge, Orientation};
+pub use list::{List, ListEvent};
+pub use panel::{BorderSet, Panel};
+pub use paragraph::Paragraph;
+pub use table::{Cell, Column, Table, TableEvent, Width};
+pub use tabs::{Tabs, TabsEvent};
+pub use text_input::{InputEvent, TextInput};
+
+/// Creates a horizontal progress bar from provided Glyphs.
+/// It loops endlessly, use Gauge to show actual progress.
+pub fn progress_bar(
+    width: usize,
+    empty: Glyph,
+    full: Glyph,
+    stages: Option<Vec<Glyph>>,
+) -> Graphic {
+    let mut total_states = 1;
+    let mut states = vec![];
+    if let Some(stages) = stages {
+        total_states += stages.len();
+        for s in stages {
+            states.push(s)
+        }
+    }
+    states.push(full);
+    let mut library = HashMap::with_capacity(width * total_states);
+    let mut ordering = Vec::with_capacity(total_states);
+    let mut contstruction_state = vec![empty; width];
+    library.insert(0, contstruction_state.clone());
+    let mut j = 1;
+    for i in 0..width {
+        for state_no in 0..total_states {
+            let _r = replace(&mut contstruction_state[i], *states.get(state_no).unwrap());
+            library.insert(j, contstruction_state.clone());
+            j += 1;
+            ordering.push((j % (total_states * width), Timestamp::new(0, 100)));
+        }
+    }
+    let anim = Animation::new(true, true, ordering, Timestamp::now());
+    let mut anims = HashMap::new();
+    anims.insert(0, anim);
+    Graphic::new(width, 1, 0, library, Some(anims))
+}
+
+/// Builds a bordered Graphic based on glyph
+/// with optional title and given contents
+/// wrapped within width x height box.
+pub fn message_box(
+    title: Option<String>,
+    content: String,
+    glyph: Glyph,
+    width: usize,
+    height: usize,
+) -> Graphic {
+    let mut mbox = Vec::with_capacity(width * height);
+    let mut cgl = glyph;
+    cgl.set_char('╭');
+    mbox.push(cgl);
+    let mut i = 1;
+    if let Some(name) = title {
+        for c in name.chars() {
+            if i > width.saturating_sub(2) {
+                break;
+            }
+            cgl.set_char(c);
+            mbox.push(cgl);
+            i += 1;
+        }
+    }
+    cgl.set_char('─');
+    for _i in i..width - 1 {
+        mbox.push(cgl);
+    }
+    cgl.set_char('╮');
+    mbox.push(cgl);
+
+    let lines = wrap(&content, width.saturating_sub(4));
+    for j in 1..height - 1 {
+        cgl.set_char('│');
+        mbox.push(cgl);
+        mbox.push(glyph);
+        let line = lines.get(j - 1).map(|l| l.as_str()).unwrap_or("");
+        mbox.append(&mut fit(
+            &text_to_cells(line, glyph),
+            width.saturating_sub(4),
+            Align::Left,
+            glyph,
+        ));
+        mbox.push(glyph);
+        mbox.push(cgl);
+    }
+    cgl.set_char('╰');
+    mbox.push(cgl);
+    cgl.set_char('─');
+    for _i in 1..width - 1 {
+        mbox.push(cgl);
+    }
+    cgl.set_char('╯');
+    mbox.push(cgl);
+
+    let mut library = HashMap::new();
+    library.insert(0, mbox);
+    Graphic::new(width, height, 0, library, None)
+}
+
+/// Adds horizontal and vertical borders around a frame.
+/// Elements inside border define Glyphs to be used as
+/// building blocks, ordering:
+/// top-left, top, top-right,
+/// left, right,
+/// bottom-left, bottom, bottom-right,
+pub fn wrap_border_around(
+    frame: Vec<Glyph>,
+    cols: usize,
+    border: [Glyph; 8],
+    title: Option<&str>,
+) -> Vec<Glyph> {
+    let mut result = Vec::with_capacity(((frame.len() / cols) + 2) * (cols + 2));
+    result.push(border[0]);
+    let mut start = 0;
+    if let Some(text) = title {
+        let mut g = border[1];
+        for character in text.chars().take(cols.saturating_sub(2)) {
+            g.set_char(character);
+            start += 1;
+            result.push(g);
+        }
+    }
+    for _i in start..cols {
+        result.push(border[1]);
+    }
+    result.push(border[2]);
+
+    for chunk in frame.chunks(cols) {
+        result.push(border[3]);
+        for glyph in chunk {
+            result.push(*glyph);
+        }
+        result.push(border[4]);
+    }
+    result.push(border[5]);
+    for _i in 0..cols {
+        result.push(border[6]);
+    }
+    result.push(border[7]);
+
+    result
+}
+
+/// Converts text to a Vector of Glyphs based on provided glyph.
+pub fn text_to_frame(text: &str, mut glyph: Glyph) -> Vec<Glyph> {
+    let mut frame = Vec::with_capacity(text.len());
+    for character in text.chars() {
+        glyph.set_char(character);
+        frame.push(glyph)
+    }
+    frame
+}
+
+/// Number of terminal columns a char occupies: 0 for combining and zero width chars,
+/// 2 for wide East Asian chars and emoji, 1 for everything else.
+pub fn char_width(character: char) -> usize {
+    match character as u32 {
+        0..=0x1f | 0x7f..=0x9f => 0,
+        0x0300..=0x036f
+        | 0x1ab0..=0x1aff
+        | 0x1dc0..=0x1dff
+        | 0x200b..=0x200f
+        | 0x20d0..=0x20ff
+        | 0xfe00..=0xfe0f
+        | 0xfe20..=0xfe2f => 0,
+        0x1100..=0x115f
+        | 0x2e80..=0x303e
+        | 0x3041..=0x33ff
+        | 0x3400..=0x4dbf
+        | 0x4e00..=0x9fff
+        | 0xa000..=0xa4cf
+        | 0xac00..=0xd7a3
+        | 0xf900..=0xfaff
+        | 0xfe30..=0xfe4f
+        | 0xff00..=0xff60
+        | 0xffe0..=0xffe6
+        | 0x1f300..=0x1f64f
+        | 0x1f900..=0x1f9ff
+        | 0x20000..=0x3fffd => 2,
+        _ => 1,
+    }
+}
+
+/// Number of terminal columns a text occupies.
+pub fn text_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+/// Break text into lines no wider than width, splitting at whitespace
+/// and keeping line breaks already present. Words wider than a line are split.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        let mut line_width = 0;
+        for word in paragraph.split_whitespace() {
+            let word_width = text_width(word);
+            if line_width > 0 && line_width + 1 + word_width > width {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0;
+            }
+            if line_width > 0 {
+                line.push(' ');
+                line_width += 1;
+            }
+            for character in word.chars() {
+                let char_width = char_width(character);
+                if line_width + char_width > width {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0;
+                }
+                line.push(character);
+                line_width += char_width;
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Like text_to_frame, but with one glyph per terminal column:
+/// zero width chars are skipped and wide chars are followed by a filler glyph
+/// that prints nothing, so that following glyphs stay aligned.
+pub fn text_to_cells(text: &str, mut glyph: Glyph) -> Vec<Glyph> {
+    let mut cells = Vec::with_capacity(text.len());
+    for character in text.chars() {
+        let width = char_width(character);
+        if width == 0 {
+            continue;
+        }
+        glyph.set_char(character);
+        cells.push(glyph);
+        if width == 2 {
+            glyph.set_char('\u{0}');
+            cells.push(glyph);
+        }
+    }
+    cells
+}
+
+/// Build a graphic for a widget, holding two copies of it's frame,
+/// so that every redraw can switch to the other one sending only glyphs that changed.
+fn widget_graphic(cols: usize, frame: Vec<Glyph>) -> Graphic {
+    let rows = frame.len() / cols.max(1);
+    let mut library = HashMap::with_capacity(2);
+    library.insert(0, frame.clone());
+    library.insert(1, frame);
+    Graphic::new(cols, rows, 0, library, None)
+}
+
+/// Put a new frame into widget's graphic and show it, shown holds id of frame on screen.
+fn redraw(mgr: &mut Manager, graphic_id: usize, frame: Vec<Glyph>, shown: &mut usize) {
+    let next = 1 - *shown;
+    mgr.swap_frame(graphic_id, next, frame);
+    mgr.set_graphic(graphic_id, next, false);
+    *shown = next;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// Horizontal placement of text within available space.
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+    /// Stretch spaces between words so that text fills whole width.
+    /// Where that is not possible text is aligned left.
+    Justify,
+}
+
+/// Fit glyphs into width, cutting them with an ellipsis when too long,
+/// or padding them with fill according to align when too short.
+fn fit(glyphs: &[Glyph], width: usize, align: Align, fill: Glyph) -> Vec<Glyph> {
+    let mut fill = fill;
+    fill.set_char(' ');
+    if glyphs.len() > width {
+        let mut fitted = glyphs[..width].to_vec();
+        // Wide char whose filler is replaced would spill over ellipsis
+        if width > 1 && fitted[width - 1].character == '\u{0}' {
+            fitted[width - 2].set_char(' ');
+        }
+        if let Some(last) = fitted.last_mut() {
+            last.set_char('…');
+        }
+        return fitted;
+    }
+    let padding = width - glyphs.len();
+    let before = match align {
+        Align::Left | Align::Justify => 0,
+        Align::Center => padding / 2,
+        Align::Right => padding,
+    };
+    let mut fitted = Vec::with_capacity(width);
+    fitted.resize(before, fill);
+    fitted.extend_from_slice(glyphs);
+    fitted.resize(width, fill);
+    fitted
+}
@@ -0,0 +1,215 @@
+use super::{fit, text_to_cells, text_width, Align};
+use crate::glyph::Glyph;
+use crate::manager::Manager;
+
+/// Chars used to draw a border.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BorderSet {
+    /// ┌─┐
+    #[default]
+    Single,
+    /// ╔═╗
+    Double,
+    /// ╭─╮
+    Rounded,
+    /// ┏━┓
+    Heavy,
+    /// +-+
+    Ascii,
+}
+
+impl BorderSet {
+    /// Border chars in order used by wrap_border_around:
+    /// top-left, top, top-right, left, right, bottom-left, bottom, bottom-right.
+    pub fn chars(&self) -> [char; 8] {
+        match self {
+            BorderSet::Single => ['┌', '─', '┐', '│', '│', '└', '─', '┘'],
+            BorderSet::Double => ['╔', '═', '╗', '║', '║', '╚', '═', '╝'],
+            BorderSet::Rounded => ['╭', '─', '╮', '│', '│', '╰', '─', '╯'],
+            BorderSet::Heavy => ['┏', '━', '┓', '┃', '┃', '┗', '━', '┛'],
+            BorderSet::Ascii => ['+', '-', '+', '|', '|', '+', '-', '+'],
+        }
+    }
+
+    /// Border glyphs looking like given glyph, ready for wrap_border_around.
+    pub fn glyphs(&self, glyph: Glyph) -> [Glyph; 8] {
+        self.chars().map(|character| {
+            let mut g = glyph;
+            g.set_char(character);
+            g
+        })
+    }
+}
+
+/// A bordered window of cols x rows size, border included,
+/// with optional title in top border and footer in bottom border.
+/// When focused border is drawn with focused_glyph and optionally a different border set.
+/// Panel only draws a frame, content is shown as separate graphics
+/// placed one layer above, inside of it.
+pub struct Panel {
+    pub cols: usize,
+    pub rows: usize,
+    pub border: BorderSet,
+    /// Border set used instead of border when panel is focused.
+    pub focused_border: Option<BorderSet>,
+    pub title: Option<String>,
+    pub title_align: Align,
+    pub footer: Option<String>,
+    pub footer_align: Align,
+    /// Glyph used for border of a panel that is not focused.
+    pub glyph: Glyph,
+    pub focused_glyph: Glyph,
+    /// Glyph filling panel's inside.
+    pub fill: Glyph,
+    pub focused: bool,
+    pub graphic_id: Option<usize>,
+    shown_frame: usize,
+}
+
+impl Panel {
+    /// Create a panel of given size, including it's border.
+    pub fn new(cols: usize, rows: usize) -> Self {
+        let mut glyph = Glyph::default();
+        glyph.set_dim(true);
+        let mut focused_glyph = Glyph::default();
+        focused_glyph.set_bright(true);
+        let mut fill = Glyph::default();
+        fill.set_char(' ');
+        Panel {
+            cols: cols.max(2),
+            rows: rows.max(2),
+            border: BorderSet::Single,
+            focused_border: None,
+            title: None,
+            title_align: Align::Left,
+            footer: None,
+            footer_align: Align::Right,
+            glyph,
+            focused_glyph,
+            fill,
+            focused: false,
+            graphic_id: None,
+            shown_frame: 0,
+        }
+    }
+
+    pub fn with_border(mut self, border: BorderSet) -> Self {
+        self.border = border;
+        self
+    }
+
+    /// Switch to a different border set when panel is focused.
+    pub fn with_focused_border(mut self, border: BorderSet) -> Self {
+        self.focused_border = Some(border);
+        self
+    }
+
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    pub fn with_footer(mut self, footer: &str) -> Self {
+        self.footer = Some(footer.to_string());
+        self
+    }
+
+    /// Size of panel's inside, where content goes.
+    pub fn inner_size(&self) -> (usize, usize) {
+        (self.cols - 2, self.rows - 2)
+    }
+
+    /// Build a frame presenting panel in it's current state.
+    pub fn render(&self) -> Vec<Glyph> {
+        let (glyph, border) = if self.focused {
+            (
+                self.focused_glyph,
+                self.focused_border.unwrap_or(self.border),
+            )
+        } else {
+            (self.glyph, self.border)
+        };
+        let [top_left, top, top_right, left, right, bottom_left, bottom, bottom_right] =
+            border.glyphs(glyph);
+        let inner = self.cols - 2;
+        let mut frame = Vec::with_capacity(self.cols * self.rows);
+        frame.push(top_left);
+        frame.append(&mut border_with_text(
+            self.title.as_deref(),
+            inner,
+            self.title_align,
+            top,
+        ));
+        frame.push(top_right);
+        for _row in 2..self.rows {
+            frame.push(left);
+            frame.resize(frame.len() + inner, self.fill);
+            frame.push(right);
+        }
+        frame.push(bottom_left);
+        frame.append(&mut border_with_text(
+            self.footer.as_deref(),
+            inner,
+            self.footer_align,
+            bottom,
+        ));
+        frame.push(bottom_right);
+        frame
+    }
+
+    /// Add panel to Manager's current display as a new graphic.
+    pub fn show(
+        &mut self,
+        mgr: &mut Manager,
+        layer: usize,
+        offset: (isize, isize),
+    ) -> Option<usize> {
+        let graphic = super::widget_graphic(self.cols, self.render());
+        self.shown_frame = 0;
+        self.graphic_id = mgr.add_graphic(graphic, layer, offset);
+        self.graphic_id
+    }
+
+    /// Redraw panel's graphic on screen.
+    pub fn draw(&mut self, mgr: &mut Manager) {
+        if let Some(gid) = self.graphic_id {
+            super::redraw(mgr, gid, self.render(), &mut self.shown_frame);
+        }
+    }
+
+    /// Change focus state and redraw panel.
+    pub fn set_focused(&mut self, mgr: &mut Manager, focused: bool) {
+        if self.focused != focused {
+            self.focused = focused;
+            self.draw(mgr);
+        }
+    }
+}
+
+/// A border line of given width with text placed on it, surrounded by spaces.
+fn border_with_text(text: Option<&str>, width: usize, align: Align, line: Glyph) -> Vec<Glyph> {
+    let Some(text) = text.filter(|t| !t.is_empty() && width > 2) else {
+        return vec![line; width];
+    };
+    let mut label = text_to_cells(text, line);
+    if text_width(text) + 2 > width {
+        label = fit(&label, width - 2, Align::Left, line);
+    }
+    label.insert(0, space(line));
+    label.push(space(line));
+    let free = width - label.len();
+    let before = match align {
+        Align::Left | Align::Justify => free.min(1),
+        Align::Center => free / 2,
+        Align::Right => free.saturating_sub(1),
+    };
+    let mut cells = vec![line; before];
+    cells.append(&mut label);
+    cells.resize(width, line);
+    cells
+}
+
+fn space(mut glyph: Glyph) -> Glyph {
+    glyph.set_char(' ');
+    glyph
+}
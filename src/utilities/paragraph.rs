@@ -0,0 +1,402 @@
+use super::{char_width, fit, Align};
+use crate::color::Color;
+use crate::focus::Focusable;
+use crate::glyph::Glyph;
+use crate::key::Key;
+use crate::manager::Manager;
+
+/// A row of laid out text, with one glyph per terminal column.
+struct Line {
+    cells: Vec<Glyph>,
+    /// Positions of spaces between words, where justify can insert more.
+    gaps: Vec<usize>,
+    /// Last line of a paragraph is never justified.
+    last: bool,
+}
+
+/// A block of styled text of cols x rows size.
+/// Text is given as spans, each with a glyph defining how it looks,
+/// or as markup with tags like [b]bold[/b], [i], [u], [d], [r], [s], [blink],
+/// [fg=red] and [bg=#203040], where [/] closes all open tags and [[ is a literal [.
+/// Lines are word wrapped according to terminal width of chars, or when wrapping is off
+/// cut with an ellipsis. Content taller than a paragraph is scrolled with
+/// Up, Down, PgUp, PgDn, Home and End.
+pub struct Paragraph {
+    pub cols: usize,
+    pub rows: usize,
+    pub align: Align,
+    /// Break lines at word boundaries instead of cutting them.
+    pub wrap: bool,
+    /// Glyph used for empty space around text.
+    pub glyph: Glyph,
+    pub graphic_id: Option<usize>,
+    spans: Vec<(String, Glyph)>,
+    scroll: usize,
+    shown_frame: usize,
+}
+
+impl Paragraph {
+    /// Create a new paragraph of given size presenting styled spans.
+    pub fn new(cols: usize, rows: usize, spans: Vec<(String, Glyph)>) -> Self {
+        let mut glyph = Glyph::default();
+        glyph.set_char(' ');
+        Paragraph {
+            cols: cols.max(1),
+            rows: rows.max(1),
+            align: Align::Left,
+            wrap: true,
+            glyph,
+            graphic_id: None,
+            spans,
+            scroll: 0,
+            shown_frame: 0,
+        }
+    }
+
+    /// Create a new paragraph of given size from markup, untagged text looks like glyph.
+    pub fn from_markup(cols: usize, rows: usize, markup: &str, glyph: Glyph) -> Self {
+        Paragraph::new(cols, rows, markup_to_spans(markup, glyph))
+    }
+
+    /// Align lines within paragraph's width.
+    pub fn with_align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Cut lines that are too long with an ellipsis instead of wrapping them.
+    pub fn truncated(mut self) -> Self {
+        self.wrap = false;
+        self
+    }
+
+    pub fn spans(&self) -> &[(String, Glyph)] {
+        &self.spans
+    }
+
+    /// Replace paragraph's text, keeping scroll within bounds.
+    pub fn set_spans(&mut self, spans: Vec<(String, Glyph)>) {
+        self.spans = spans;
+        self.scroll = self.scroll.min(self.max_scroll());
+    }
+
+    /// Replace paragraph's text with markup.
+    pub fn set_markup(&mut self, markup: &str, glyph: Glyph) {
+        self.set_spans(markup_to_spans(markup, glyph));
+    }
+
+    /// Number of lines text takes after wrapping.
+    pub fn line_count(&self) -> usize {
+        self.layout().len()
+    }
+
+    /// Index of first line shown.
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    /// Show text starting from given line.
+    pub fn scroll_to(&mut self, line: usize) {
+        self.scroll = line.min(self.max_scroll());
+    }
+
+    /// Scroll paragraph according to a key pressed by user, returns true when it has moved.
+    pub fn handle_key(&mut self, key: &Key) -> bool {
+        let previous = self.scroll;
+        let target = match key {
+            Key::Up => self.scroll.saturating_sub(1),
+            Key::Down => self.scroll + 1,
+            Key::PgUp => self.scroll.saturating_sub(self.rows),
+            Key::PgDn => self.scroll + self.rows,
+            Key::Home => 0,
+            Key::End => usize::MAX,
+            _ => return false,
+        };
+        self.scroll_to(target);
+        self.scroll != previous
+    }
+
+    /// Build a frame presenting visible part of text.
+    pub fn render(&self) -> Vec<Glyph> {
+        let lines = self.layout();
+        let mut frame = Vec::with_capacity(self.cols * self.rows);
+        for row in 0..self.rows {
+            match lines.get(self.scroll + row) {
+                Some(line) => frame.append(&mut self.render_line(line)),
+                None => frame.append(&mut fit(&[], self.cols, Align::Left, self.glyph)),
+            }
+        }
+        frame
+    }
+
+    /// Add paragraph to Manager's current display as a new graphic.
+    pub fn show(
+        &mut self,
+        mgr: &mut Manager,
+        layer: usize,
+        offset: (isize, isize),
+    ) -> Option<usize> {
+        let graphic = super::widget_graphic(self.cols, self.render());
+        self.shown_frame = 0;
+        self.graphic_id = mgr.add_graphic(graphic, layer, offset);
+        self.graphic_id
+    }
+
+    /// Redraw paragraph's graphic on screen.
+    pub fn draw(&mut self, mgr: &mut Manager) {
+        if let Some(gid) = self.graphic_id {
+            super::redraw(mgr, gid, self.render(), &mut self.shown_frame);
+        }
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.line_count().saturating_sub(self.rows)
+    }
+
+    fn render_line(&self, line: &Line) -> Vec<Glyph> {
+        let free = self.cols.saturating_sub(line.cells.len());
+        if self.align != Align::Justify || line.last || line.gaps.is_empty() || free == 0 {
+            return fit(&line.cells, self.cols, self.align, self.glyph);
+        }
+        let (share, rest) = (free / line.gaps.len(), free % line.gaps.len());
+        let mut cells = Vec::with_capacity(self.cols);
+        let mut gaps = line.gaps.iter().enumerate().peekable();
+        for (i, cell) in line.cells.iter().enumerate() {
+            cells.push(*cell);
+            if let Some((gap, _)) = gaps.next_if(|(_, position)| **position == i) {
+                let extra = share + usize::from(gap < rest);
+                cells.resize(cells.len() + extra, *cell);
+            }
+        }
+        cells
+    }
+
+    /// Break text into lines fitting paragraph's width.
+    fn layout(&self) -> Vec<Line> {
+        let mut glyphs = Vec::new();
+        for (text, glyph) in &self.spans {
+            let mut glyph = *glyph;
+            for character in text.chars() {
+                glyph.set_char(character);
+                glyphs.push(glyph);
+            }
+        }
+        let mut lines = Vec::new();
+        for paragraph in glyphs.split(|g| g.character == '\n') {
+            if self.wrap {
+                self.wrap_paragraph(paragraph, &mut lines);
+            } else {
+                let mut cells = Vec::with_capacity(paragraph.len());
+                for glyph in paragraph {
+                    let mut glyph = *glyph;
+                    if glyph.character.is_whitespace() {
+                        glyph.set_char(' ');
+                    }
+                    push_cell(&mut cells, glyph);
+                }
+                lines.push(Line {
+                    cells,
+                    gaps: Vec::new(),
+                    last: true,
+                });
+            }
+        }
+        lines
+    }
+
+    fn wrap_paragraph(&self, paragraph: &[Glyph], lines: &mut Vec<Line>) {
+        let mut line = Line {
+            cells: Vec::with_capacity(self.cols),
+            gaps: Vec::new(),
+            last: false,
+        };
+        let mut words = Vec::new();
+        let mut space = self.glyph;
+        let mut word: Vec<Glyph> = Vec::new();
+        for glyph in paragraph {
+            if glyph.character.is_whitespace() {
+                if !word.is_empty() {
+                    words.push((space, std::mem::take(&mut word)));
+                }
+                space = *glyph;
+                space.set_char(' ');
+            } else {
+                word.push(*glyph);
+            }
+        }
+        if !word.is_empty() {
+            words.push((space, word));
+        }
+
+        let width = |glyphs: &[Glyph]| glyphs.iter().map(|g| char_width(g.character)).sum();
+        for (space, word) in words {
+            let word_width: usize = width(&word);
+            let used = line.cells.len();
+            if used > 0 && used + 1 + word_width > self.cols {
+                lines.push(std::mem::replace(
+                    &mut line,
+                    Line {
+                        cells: Vec::with_capacity(self.cols),
+                        gaps: Vec::new(),
+                        last: false,
+                    },
+                ));
+            }
+            if !line.cells.is_empty() {
+                line.gaps.push(line.cells.len());
+                line.cells.push(space);
+            }
+            // Words wider than a line are split wherever they hit the edge
+            for glyph in word {
+                if line.cells.len() + char_width(glyph.character) > self.cols {
+                    lines.push(std::mem::replace(
+                        &mut line,
+                        Line {
+                            cells: Vec::with_capacity(self.cols),
+                            gaps: Vec::new(),
+                            last: false,
+                        },
+                    ));
+                }
+                push_cell(&mut line.cells, glyph);
+            }
+        }
+        line.last = true;
+        lines.push(line);
+    }
+}
+
+impl Focusable for Paragraph {
+    fn handle_key(&mut self, mgr: &mut Manager, key: &Key) -> bool {
+        if !Paragraph::handle_key(self, key) {
+            return false;
+        }
+        self.draw(mgr);
+        true
+    }
+
+    fn set_focused(&mut self, _mgr: &mut Manager, _focused: bool) {}
+}
+
+/// Push a glyph taking as many cells as it's char is wide.
+fn push_cell(cells: &mut Vec<Glyph>, mut glyph: Glyph) {
+    match char_width(glyph.character) {
+        0 => {}
+        1 => cells.push(glyph),
+        _ => {
+            cells.push(glyph);
+            glyph.set_char('\u{0}');
+            cells.push(glyph);
+        }
+    }
+}
+
+/// Split markup into spans of text looking the same.
+fn markup_to_spans(markup: &str, glyph: Glyph) -> Vec<(String, Glyph)> {
+    let mut spans: Vec<(String, Glyph)> = Vec::new();
+    let mut current = glyph;
+    // Open tags with glyph that was in use before each of them
+    let mut open: Vec<(String, Glyph)> = Vec::new();
+    let mut text = String::new();
+    let mut rest = markup;
+    while let Some(start) = rest.find('[') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(after) = rest.strip_prefix("[[") {
+            text.push('[');
+            rest = after;
+            continue;
+        }
+        let Some(end) = rest.find(']') else {
+            break;
+        };
+        let tag = &rest[1..end];
+        let mut next = current;
+        let applied = if tag == "/" {
+            open.clear();
+            next = glyph;
+            true
+        } else if let Some(name) = tag.strip_prefix('/') {
+            match open.iter().rposition(|(open_name, _)| open_name == name) {
+                Some(index) => {
+                    next = open[index].1;
+                    open.truncate(index);
+                    true
+                }
+                None => false,
+            }
+        } else if apply_tag(tag, &mut next) {
+            let name = tag.split('=').next().unwrap_or(tag);
+            open.push((name.to_string(), current));
+            true
+        } else {
+            false
+        };
+        if applied {
+            if !text.is_empty() {
+                spans.push((std::mem::take(&mut text), current));
+            }
+            current = next;
+        } else {
+            // Unknown tags are just text
+            text.push_str(&rest[..=end]);
+        }
+        rest = &rest[end + 1..];
+    }
+    text.push_str(rest);
+    if !text.is_empty() {
+        spans.push((text, current));
+    }
+    spans
+}
+
+/// Change glyph according to a markup tag, returns false for unknown tags.
+fn apply_tag(tag: &str, glyph: &mut Glyph) -> bool {
+    match tag.split_once('=') {
+        Some(("fg", value)) => color_from_markup(value)
+            .map(|c| glyph.set_color(c))
+            .is_some(),
+        Some(("bg", value)) => color_from_markup(value)
+            .map(|c| glyph.set_background(c))
+            .is_some(),
+        Some(_) => false,
+        None => {
+            match tag {
+                "b" => glyph.set_bright(true),
+                "d" => glyph.set_dim(true),
+                "i" => glyph.set_italic(true),
+                "u" => glyph.set_underline(true),
+                "r" => glyph.set_reverse(true),
+                "s" => glyph.set_strike(true),
+                "blink" => glyph.set_blink(true),
+                _ => return false,
+            }
+            true
+        }
+    }
+}
+
+/// Color given by name or as #rrggbb.
+fn color_from_markup(value: &str) -> Option<Color> {
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return Some(Color::new_truecolor(channel(0)?, channel(2)?, channel(4)?));
+    }
+    Some(match value {
+        "black" => Color::black(),
+        "red" => Color::red(),
+        "green" => Color::green(),
+        "yellow" => Color::yellow(),
+        "blue" => Color::blue(),
+        "magenta" => Color::magenta(),
+        "cyan" => Color::cyan(),
+        "white" => Color::white(),
+        "orange" => Color::orange(),
+        "indigo" => Color::indigo(),
+        "violet" => Color::violet(),
+        _ => return None,
+    })
+}
@@ -0,0 +1,473 @@
+use super::{fit, text_to_cells, text_width, Align};
+use crate::focus::Focusable;
+use crate::glyph::Glyph;
+use crate::key::Key;
+use crate::manager::Manager;
+use std::cmp::Ordering;
+
+/// How much space a column takes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Width {
+    /// Exact number of chars.
+    Fixed(usize),
+    /// Percentage of table's width.
+    Percent(usize),
+    /// As wide as the widest value in a column, header included.
+    Auto,
+}
+
+/// Definition of a single column.
+#[derive(Clone, Debug)]
+pub struct Column {
+    pub header: String,
+    pub width: Width,
+    pub align: Align,
+}
+
+impl Column {
+    pub fn new(header: &str, width: Width, align: Align) -> Self {
+        Column {
+            header: header.to_owned(),
+            width,
+            align,
+        }
+    }
+}
+
+/// Contents of a single table cell, either plain text printed with table's glyph,
+/// or a run of glyphs printed as they are.
+#[derive(Clone, Debug)]
+pub enum Cell {
+    Text(String),
+    Glyphs(Vec<Glyph>),
+}
+
+impl Cell {
+    /// Cell's text, used for sorting.
+    pub fn text(&self) -> String {
+        match self {
+            Cell::Text(text) => text.clone(),
+            Cell::Glyphs(glyphs) => glyphs.iter().map(|g| g.character).collect(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Cell::Text(text) => text_width(text),
+            Cell::Glyphs(glyphs) => glyphs.len(),
+        }
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        Cell::Text(text.to_owned())
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Self {
+        Cell::Text(text)
+    }
+}
+
+impl From<Vec<Glyph>> for Cell {
+    fn from(glyphs: Vec<Glyph>) -> Self {
+        Cell::Glyphs(glyphs)
+    }
+}
+
+/// Outcome of passing a Key to a Table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableEvent {
+    /// Key has no meaning for a table.
+    Ignored,
+    /// Highlight has moved to a row with given index (as it was added to a table).
+    Moved(usize),
+    /// Table was scrolled horizontally.
+    Scrolled,
+    /// Rows were sorted by given column, ascending when true.
+    Sorted(usize, bool),
+    /// Sorting was cleared, rows are back in order they were added.
+    Unsorted,
+    /// Enter was pressed on a row with given index (as it was added to a table).
+    Activated(usize),
+}
+
+/// A table of cols x rows size, with a header row and one data row highlighted.
+/// Up, Down, PgUp, PgDn, Home and End move highlight, Left and Right scroll horizontally
+/// when columns do not fit, Enter activates highlighted row.
+/// Keys 1 to 9 sort rows by given column, pressing the same key again reverses order,
+/// 0 restores original order.
+pub struct Table {
+    pub cols: usize,
+    pub rows: usize,
+    pub columns: Vec<Column>,
+    /// Keep header visible when rows are scrolled.
+    pub sticky_header: bool,
+    pub separator: char,
+    pub glyph: Glyph,
+    pub header_glyph: Glyph,
+    pub highlight_glyph: Glyph,
+    /// Highlight is dimmed when table is not focused.
+    pub focused: bool,
+    pub graphic_id: Option<usize>,
+    shown_frame: usize,
+    data: Vec<Vec<Cell>>,
+    order: Vec<usize>,
+    sort: Option<(usize, bool)>,
+    selected: usize,
+    top: usize,
+    left: usize,
+    event: Option<TableEvent>,
+}
+
+impl Table {
+    /// Create an empty table of given size.
+    pub fn new(cols: usize, rows: usize, columns: Vec<Column>) -> Self {
+        let mut header_glyph = Glyph::default();
+        header_glyph.set_bright(true);
+        header_glyph.set_underline(true);
+        let mut highlight_glyph = Glyph::default();
+        highlight_glyph.set_reverse(true);
+        Table {
+            cols: cols.max(1),
+            rows: rows.max(1),
+            columns,
+            sticky_header: true,
+            separator: '│',
+            glyph: Glyph::default(),
+            header_glyph,
+            highlight_glyph,
+            focused: true,
+            graphic_id: None,
+            shown_frame: 0,
+            data: Vec::new(),
+            order: Vec::new(),
+            sort: None,
+            selected: 0,
+            top: 0,
+            left: 0,
+            event: None,
+        }
+    }
+
+    /// Replace all rows.
+    pub fn set_rows(&mut self, rows: Vec<Vec<Cell>>) {
+        let selected = self.selected();
+        self.data = rows;
+        self.order = (0..self.data.len()).collect();
+        self.apply_sort();
+        self.reselect(selected);
+        self.scroll_to_selected();
+    }
+
+    /// Add a row at the end of a table, keeping current sort order.
+    pub fn push_row(&mut self, row: Vec<Cell>) {
+        let selected = self.selected();
+        self.data.push(row);
+        self.order.push(self.data.len() - 1);
+        self.apply_sort();
+        self.reselect(selected);
+        self.scroll_to_selected();
+    }
+
+    /// Get a row by it's index (as it was added to a table).
+    pub fn row(&self, index: usize) -> Option<&Vec<Cell>> {
+        self.data.get(index)
+    }
+
+    /// Index of highlighted row (as it was added to a table).
+    pub fn selected(&self) -> Option<usize> {
+        self.order.get(self.selected).copied()
+    }
+
+    /// Sort rows by given column, or restore original order with None.
+    pub fn sort_by(&mut self, sort: Option<(usize, bool)>) {
+        let selected = self.selected();
+        self.sort = sort.filter(|(column, _asc)| *column < self.columns.len());
+        self.order = (0..self.data.len()).collect();
+        self.apply_sort();
+        self.reselect(selected);
+        self.scroll_to_selected();
+    }
+
+    /// Highlight row with given index (as it was added to a table) after rows got reordered.
+    /// When there is no such row highlight stays where it was, within table's rows.
+    fn reselect(&mut self, selected: Option<usize>) {
+        self.selected = selected
+            .and_then(|index| self.order.iter().position(|i| *i == index))
+            .unwrap_or_else(|| self.selected.min(self.data.len().saturating_sub(1)));
+    }
+
+    /// Update table according to a key pressed by user.
+    pub fn handle_key(&mut self, key: &Key) -> TableEvent {
+        let last = self.data.len().saturating_sub(1);
+        let page = self.data_rows();
+        let previous = self.selected;
+        self.selected = match key {
+            Key::Up | Key::CtrlP => self.selected.saturating_sub(1),
+            Key::Down | Key::CtrlN => (self.selected + 1).min(last),
+            Key::PgUp => self.selected.saturating_sub(page),
+            Key::PgDn => (self.selected + page).min(last),
+            Key::Home => 0,
+            Key::End => last,
+            Key::Left | Key::Right => {
+                let max_left = self.total_width().saturating_sub(self.cols);
+                let left = if *key == Key::Left {
+                    self.left.saturating_sub(4)
+                } else {
+                    (self.left + 4).min(max_left)
+                };
+                if left == self.left {
+                    return TableEvent::Ignored;
+                }
+                self.left = left;
+                return TableEvent::Scrolled;
+            }
+            Key::Enter => {
+                return match self.selected() {
+                    Some(index) => TableEvent::Activated(index),
+                    None => TableEvent::Ignored,
+                }
+            }
+            Key::Zero => {
+                if self.sort.is_none() {
+                    return TableEvent::Ignored;
+                }
+                self.sort_by(None);
+                return TableEvent::Unsorted;
+            }
+            other => {
+                let column = match other {
+                    Key::One => 0,
+                    Key::Two => 1,
+                    Key::Three => 2,
+                    Key::Four => 3,
+                    Key::Five => 4,
+                    Key::Six => 5,
+                    Key::Seven => 6,
+                    Key::Eight => 7,
+                    Key::Nine => 8,
+                    _ => return TableEvent::Ignored,
+                };
+                if column >= self.columns.len() {
+                    return TableEvent::Ignored;
+                }
+                let ascending = !matches!(self.sort, Some((c, true)) if c == column);
+                self.sort_by(Some((column, ascending)));
+                return TableEvent::Sorted(column, ascending);
+            }
+        };
+        self.scroll_to_selected();
+        match self.selected() {
+            Some(index) if self.selected != previous => TableEvent::Moved(index),
+            _ => TableEvent::Ignored,
+        }
+    }
+
+    /// Widths of every column for current table width.
+    pub fn column_widths(&self) -> Vec<usize> {
+        let separators = self.columns.len().saturating_sub(1);
+        let available = self.cols.saturating_sub(separators);
+        self.columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| match column.width {
+                Width::Fixed(width) => width,
+                Width::Percent(percent) => available * percent / 100,
+                Width::Auto => self
+                    .data
+                    .iter()
+                    .filter_map(|row| row.get(i).map(|cell| cell.len()))
+                    .chain([text_width(&column.header) + 1])
+                    .max()
+                    .unwrap_or(0),
+            })
+            .collect()
+    }
+
+    /// Build a frame presenting table's current state.
+    pub fn render(&self) -> Vec<Glyph> {
+        let widths = self.column_widths();
+        let mut lines = Vec::with_capacity(self.rows);
+        if self.sticky_header || self.top == 0 {
+            lines.push(self.render_header(&widths));
+        }
+        let first = if self.sticky_header {
+            self.top
+        } else {
+            self.top.saturating_sub(1)
+        };
+        for position in first..self.order.len() {
+            if lines.len() == self.rows {
+                break;
+            }
+            let row = &self.data[self.order[position]];
+            let highlighted = position == self.selected;
+            lines.push(self.render_row(row, &widths, highlighted));
+        }
+        let mut empty = self.glyph;
+        empty.set_char(' ');
+        let mut frame = Vec::with_capacity(self.cols * self.rows);
+        for row in 0..self.rows {
+            let line = lines.get(row).map(|l| l.as_slice()).unwrap_or(&[]);
+            for col in self.left..self.left + self.cols {
+                frame.push(line.get(col).copied().unwrap_or(empty));
+            }
+        }
+        frame
+    }
+
+    /// Add table to Manager's current display as a new graphic.
+    pub fn show(
+        &mut self,
+        mgr: &mut Manager,
+        layer: usize,
+        offset: (isize, isize),
+    ) -> Option<usize> {
+        let graphic = super::widget_graphic(self.cols, self.render());
+        self.shown_frame = 0;
+        self.graphic_id = mgr.add_graphic(graphic, layer, offset);
+        self.graphic_id
+    }
+
+    /// Redraw table's graphic on screen.
+    pub fn draw(&mut self, mgr: &mut Manager) {
+        if let Some(gid) = self.graphic_id {
+            super::redraw(mgr, gid, self.render(), &mut self.shown_frame);
+        }
+    }
+
+    /// Event caused by last key routed to table by Manager.
+    pub fn take_event(&mut self) -> Option<TableEvent> {
+        self.event.take()
+    }
+
+    fn render_header(&self, widths: &[usize]) -> Vec<Glyph> {
+        let cells = self.columns.iter().enumerate().map(|(i, column)| {
+            let mut header = column.header.clone();
+            match self.sort {
+                Some((c, true)) if c == i => header.push('▲'),
+                Some((c, false)) if c == i => header.push('▼'),
+                _ => {}
+            }
+            text_to_cells(&header, self.header_glyph)
+        });
+        self.join_cells(cells, widths, self.header_glyph)
+    }
+
+    fn render_row(&self, row: &[Cell], widths: &[usize], highlighted: bool) -> Vec<Glyph> {
+        let glyph = if highlighted {
+            let mut glyph = self.highlight_glyph;
+            if !self.focused {
+                glyph.set_dim(true);
+            }
+            glyph
+        } else {
+            self.glyph
+        };
+        let cells = (0..self.columns.len()).map(|i| match row.get(i) {
+            Some(Cell::Text(text)) => text_to_cells(text, glyph),
+            Some(Cell::Glyphs(glyphs)) if highlighted => glyphs
+                .iter()
+                .map(|g| {
+                    let mut g = *g;
+                    g.set_reverse(!g.is_reverse());
+                    g
+                })
+                .collect(),
+            Some(Cell::Glyphs(glyphs)) => glyphs.clone(),
+            None => Vec::new(),
+        });
+        self.join_cells(cells, widths, glyph)
+    }
+
+    fn join_cells<I>(&self, cells: I, widths: &[usize], glyph: Glyph) -> Vec<Glyph>
+    where
+        I: Iterator<Item = Vec<Glyph>>,
+    {
+        let mut line = Vec::with_capacity(self.total_width());
+        let mut separator = glyph;
+        separator.set_char(self.separator);
+        for (i, cell) in cells.enumerate() {
+            if i > 0 {
+                line.push(separator);
+            }
+            line.append(&mut fit(&cell, widths[i], self.columns[i].align, glyph));
+        }
+        line
+    }
+
+    fn total_width(&self) -> usize {
+        let widths = self.column_widths();
+        widths.iter().sum::<usize>() + widths.len().saturating_sub(1)
+    }
+
+    /// How many data rows fit on screen.
+    fn data_rows(&self) -> usize {
+        if self.sticky_header {
+            self.rows.saturating_sub(1).max(1)
+        } else {
+            self.rows
+        }
+    }
+
+    fn apply_sort(&mut self) {
+        if let Some((column, ascending)) = self.sort {
+            let data = &self.data;
+            let text = |i: usize| data[i].get(column).map(|c| c.text()).unwrap_or_default();
+            self.order.sort_by(|a, b| {
+                let ordering = compare(&text(*a), &text(*b));
+                if ascending {
+                    ordering
+                } else {
+                    ordering.reverse()
+                }
+            });
+        }
+    }
+
+    fn scroll_to_selected(&mut self) {
+        let page = self.data_rows();
+        // Without sticky header, header occupies first line of scrolled content
+        let line = if self.sticky_header {
+            self.selected
+        } else {
+            self.selected + 1
+        };
+        if line < self.top {
+            self.top = line;
+        } else if line >= self.top + page {
+            self.top = line + 1 - page;
+        }
+        if !self.sticky_header && self.selected == 0 {
+            self.top = 0;
+        }
+    }
+}
+
+/// Compare cells numerically when both hold numbers, otherwise as text.
+fn compare(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
+
+impl Focusable for Table {
+    fn handle_key(&mut self, mgr: &mut Manager, key: &Key) -> bool {
+        let event = Table::handle_key(self, key);
+        if event == TableEvent::Ignored {
+            return false;
+        }
+        self.event = Some(event);
+        self.draw(mgr);
+        true
+    }
+
+    fn set_focused(&mut self, mgr: &mut Manager, focused: bool) {
+        self.focused = focused;
+        self.draw(mgr);
+    }
+}
@@ -0,0 +1,203 @@
+use super::{fit, text_to_cells, Align};
+use crate::focus::Focusable;
+use crate::glyph::Glyph;
+use crate::key::Key;
+use crate::manager::Manager;
+
+/// Outcome of passing a Key to Tabs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TabsEvent {
+    /// Key has no meaning for tabs.
+    Ignored,
+    /// Tab with given index was selected.
+    Selected(usize),
+}
+
+/// A single row tab bar, switching between pages of graphics.
+/// Every tab has a title and graphics shown only when that tab is selected,
+/// others are made invisible.
+/// Left and Right select previous and next tab, Home and End first and last one.
+pub struct Tabs {
+    pub cols: usize,
+    pub glyph: Glyph,
+    pub selected_glyph: Glyph,
+    /// Char placed between titles.
+    pub separator: char,
+    /// Selected title is dimmed when tabs are not focused.
+    pub focused: bool,
+    pub graphic_id: Option<usize>,
+    titles: Vec<String>,
+    pages: Vec<Vec<usize>>,
+    selected: usize,
+    // Tab whose graphics are currently visible on screen
+    visible: Option<usize>,
+    shown_frame: usize,
+    event: Option<TabsEvent>,
+}
+
+impl Tabs {
+    /// Create a tab bar cols wide with a tab for every title.
+    pub fn new(cols: usize, titles: Vec<String>) -> Self {
+        let mut selected_glyph = Glyph::default();
+        selected_glyph.set_reverse(true);
+        let pages = vec![vec![]; titles.len()];
+        Tabs {
+            cols: cols.max(1),
+            glyph: Glyph::default(),
+            selected_glyph,
+            separator: '│',
+            focused: true,
+            graphic_id: None,
+            titles,
+            pages,
+            selected: 0,
+            visible: None,
+            shown_frame: 0,
+            event: None,
+        }
+    }
+
+    /// Show graphic with given id only when tab with given index is selected.
+    pub fn with_graphic(mut self, tab: usize, graphic_id: usize) -> Self {
+        self.add_graphic(tab, graphic_id);
+        self
+    }
+
+    /// Show graphic with given id only when tab with given index is selected.
+    /// Visibility is updated on next draw.
+    pub fn add_graphic(&mut self, tab: usize, graphic_id: usize) {
+        if let Some(page) = self.pages.get_mut(tab) {
+            page.push(graphic_id);
+            self.visible = None;
+        }
+    }
+
+    /// Add a new tab at the end, returning it's index.
+    pub fn add_tab(&mut self, title: &str, graphics: Vec<usize>) -> usize {
+        self.titles.push(title.to_string());
+        self.pages.push(graphics);
+        self.visible = None;
+        self.titles.len() - 1
+    }
+
+    pub fn titles(&self) -> &[String] {
+        &self.titles
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Select tab with given index, takes effect on screen on next draw.
+    pub fn select(&mut self, index: usize) {
+        if index < self.titles.len() {
+            self.selected = index;
+        }
+    }
+
+    /// Update tabs according to a key pressed by user.
+    pub fn handle_key(&mut self, key: &Key) -> TabsEvent {
+        if self.titles.is_empty() {
+            return TabsEvent::Ignored;
+        }
+        let last = self.titles.len() - 1;
+        let previous = self.selected;
+        self.selected = match key {
+            Key::Left => self.selected.saturating_sub(1),
+            Key::Right => (self.selected + 1).min(last),
+            Key::Home => 0,
+            Key::End => last,
+            _ => return TabsEvent::Ignored,
+        };
+        if self.selected == previous {
+            TabsEvent::Ignored
+        } else {
+            TabsEvent::Selected(self.selected)
+        }
+    }
+
+    /// Build a frame presenting tab bar's current state.
+    pub fn render(&self) -> Vec<Glyph> {
+        let mut selected_glyph = self.selected_glyph;
+        if !self.focused {
+            selected_glyph.set_dim(true);
+        }
+        let mut separator = self.glyph;
+        separator.set_char(self.separator);
+        let mut bar = Vec::with_capacity(self.cols);
+        for (index, title) in self.titles.iter().enumerate() {
+            if index > 0 {
+                bar.push(separator);
+            }
+            let glyph = if index == self.selected {
+                selected_glyph
+            } else {
+                self.glyph
+            };
+            bar.append(&mut text_to_cells(&format!(" {} ", title), glyph));
+        }
+        fit(&bar, self.cols, Align::Left, self.glyph)
+    }
+
+    /// Add tab bar to Manager's current display as a new graphic
+    /// and hide graphics of tabs that are not selected.
+    pub fn show(
+        &mut self,
+        mgr: &mut Manager,
+        layer: usize,
+        offset: (isize, isize),
+    ) -> Option<usize> {
+        let graphic = super::widget_graphic(self.cols, self.render());
+        self.shown_frame = 0;
+        self.graphic_id = mgr.add_graphic(graphic, layer, offset);
+        self.visible = None;
+        self.update_pages(mgr);
+        self.graphic_id
+    }
+
+    /// Redraw tab bar and switch visible graphics when selected tab has changed.
+    pub fn draw(&mut self, mgr: &mut Manager) {
+        if let Some(gid) = self.graphic_id {
+            super::redraw(mgr, gid, self.render(), &mut self.shown_frame);
+        }
+        self.update_pages(mgr);
+    }
+
+    /// Event caused by last key routed to tabs by Manager.
+    pub fn take_event(&mut self) -> Option<TabsEvent> {
+        self.event.take()
+    }
+
+    fn update_pages(&mut self, mgr: &mut Manager) {
+        if self.visible == Some(self.selected) {
+            return;
+        }
+        for (index, page) in self.pages.iter().enumerate() {
+            // Only graphics of previously visible tab need hiding
+            if index != self.selected && self.visible.is_some_and(|v| v != index) {
+                continue;
+            }
+            for gid in page {
+                mgr.set_invisible(*gid, index != self.selected);
+            }
+        }
+        self.visible = Some(self.selected);
+    }
+}
+
+impl Focusable for Tabs {
+    fn handle_key(&mut self, mgr: &mut Manager, key: &Key) -> bool {
+        let event = Tabs::handle_key(self, key);
+        if event == TabsEvent::Ignored {
+            return false;
+        }
+        self.event = Some(event);
+        self.draw(mgr);
+        true
+    }
+
+    fn set_focused(&mut self, mgr: &mut Manager, focused: bool) {
+        self.focused = focused;
+        self.draw(mgr);
+    }
+}
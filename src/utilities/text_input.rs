@@ -0,0 +1,579 @@
+use crate::focus::Focusable;
+use crate::glyph::Glyph;
+use crate::helpers::map_key_to_char;
+use crate::key::Key;
+use crate::manager::Manager;
+use crate::utilities::{char_width, text_to_cells};
+
+/// Outcome of passing a Key to a TextInput.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputEvent {
+    /// Key has no meaning for an input.
+    Ignored,
+    /// Cursor or selection has changed.
+    Moved,
+    /// Value has changed.
+    Edited,
+    /// Enter (Alt+Enter for multi-line input) was pressed with a valid value.
+    Submitted(String),
+    /// Enter was pressed, but validation hook rejected value with given reason.
+    Invalid(String),
+}
+
+type Validator = Box<dyn Fn(&str) -> Result<(), String>>;
+
+/// An editable text field of cols x rows size, driven by Key events.
+/// Single-line input submits it's value on Enter and keeps a history of submitted values,
+/// browsable with Up and Down keys. Multi-line input inserts a new line on Enter
+/// and submits on Alt+Enter.
+/// Both Backspace and Delete (which most terminals send for backspace key) remove a char
+/// before cursor, Ctrl+D removes char under cursor.
+/// Shift with arrows selects text, Ctrl or Alt with arrows moves by words.
+pub struct TextInput {
+    pub cols: usize,
+    pub rows: usize,
+    pub multiline: bool,
+    /// Maximum number of chars value can hold, including new lines.
+    pub max_length: Option<usize>,
+    /// Text shown when value is empty.
+    pub placeholder: String,
+    pub glyph: Glyph,
+    pub placeholder_glyph: Glyph,
+    /// Used for value when validation hook rejects it.
+    pub invalid_glyph: Glyph,
+    /// Cursor is only drawn when input is focused.
+    pub focused: bool,
+    pub graphic_id: Option<usize>,
+    shown_frame: usize,
+    lines: Vec<Vec<char>>,
+    cursor: (usize, usize),
+    anchor: Option<(usize, usize)>,
+    scroll: (usize, usize),
+    history: Vec<String>,
+    history_index: Option<usize>,
+    draft: String,
+    validator: Option<Validator>,
+    event: Option<InputEvent>,
+}
+
+impl TextInput {
+    /// Create a new empty input of given size.
+    pub fn new(cols: usize, rows: usize, multiline: bool) -> Self {
+        let mut placeholder_glyph = Glyph::default();
+        placeholder_glyph.set_dim(true);
+        let mut invalid_glyph = Glyph::default();
+        invalid_glyph.set_color(crate::Color::red());
+        TextInput {
+            cols: cols.max(1),
+            rows: if multiline { rows.max(1) } else { 1 },
+            multiline,
+            max_length: None,
+            placeholder: String::new(),
+            glyph: Glyph::default(),
+            placeholder_glyph,
+            invalid_glyph,
+            focused: true,
+            graphic_id: None,
+            shown_frame: 0,
+            lines: vec![Vec::new()],
+            cursor: (0, 0),
+            anchor: None,
+            scroll: (0, 0),
+            history: Vec::new(),
+            history_index: None,
+            event: None,
+            draft: String::new(),
+            validator: None,
+        }
+    }
+
+    /// Set text shown when input is empty.
+    pub fn with_placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = placeholder.to_owned();
+        self
+    }
+
+    /// Limit how many chars value can hold.
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Set a hook that decides if value can be submitted,
+    /// returning a reason when it can not.
+    pub fn with_validator<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&str) -> Result<(), String> + 'static,
+    {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// Current value, with lines joined by '\n'.
+    pub fn value(&self) -> String {
+        let lines: Vec<String> = self.lines.iter().map(|l| l.iter().collect()).collect();
+        lines.join("\n")
+    }
+
+    /// Replace value, placing cursor at it's end.
+    pub fn set_value(&mut self, value: &str) {
+        self.lines = vec![Vec::new()];
+        self.cursor = (0, 0);
+        self.anchor = None;
+        self.scroll = (0, 0);
+        self.insert_str(value);
+        self.scroll_to_cursor();
+    }
+
+    /// Remove whole value.
+    pub fn clear(&mut self) {
+        self.set_value("");
+    }
+
+    /// Cursor's position as (col, row) within value.
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.cursor.1, self.cursor.0)
+    }
+
+    /// Selected text, if any.
+    pub fn selection(&self) -> Option<String> {
+        let (start, end) = self.selection_range()?;
+        Some(self.text_between(start, end))
+    }
+
+    /// Values submitted so far, oldest first.
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Check value against validation hook.
+    pub fn validate(&self) -> Result<(), String> {
+        match &self.validator {
+            Some(validator) => validator(&self.value()),
+            None => Ok(()),
+        }
+    }
+
+    /// Update input according to a key pressed by user.
+    pub fn handle_key(&mut self, key: &Key) -> InputEvent {
+        let before = (self.cursor, self.anchor);
+        let event = match key {
+            Key::Left | Key::Right | Key::Up | Key::Down | Key::Home | Key::End => {
+                if !self.multiline && matches!(key, Key::Up | Key::Down) {
+                    return self.browse_history(*key == Key::Up);
+                }
+                self.anchor = None;
+                self.move_cursor(key);
+                InputEvent::Moved
+            }
+            Key::CtrlA => self.move_and_select(false, |s| s.cursor.1 = 0),
+            Key::CtrlE => self.move_and_select(false, |s| s.cursor.1 = s.lines[s.cursor.0].len()),
+            Key::ShiftLeft => self.move_and_select(true, |s| s.move_cursor(&Key::Left)),
+            Key::ShiftRight => self.move_and_select(true, |s| s.move_cursor(&Key::Right)),
+            Key::ShiftUp => self.move_and_select(true, |s| s.move_cursor(&Key::Up)),
+            Key::ShiftDown => self.move_and_select(true, |s| s.move_cursor(&Key::Down)),
+            Key::CtrlLeft | Key::AltLeft | Key::AltB => {
+                self.move_and_select(false, |s| s.cursor = s.word_start())
+            }
+            Key::CtrlRight | Key::AltRight | Key::AltF => {
+                self.move_and_select(false, |s| s.cursor = s.word_end())
+            }
+            Key::CtrlShiftLeft => self.move_and_select(true, |s| s.cursor = s.word_start()),
+            Key::CtrlShiftRight => self.move_and_select(true, |s| s.cursor = s.word_end()),
+            Key::Backspace | Key::Delete => {
+                if self.selection_range().is_none() {
+                    self.anchor = Some(self.cursor);
+                    self.move_cursor(&Key::Left);
+                }
+                self.delete_selection()
+            }
+            Key::CtrlD => {
+                if self.selection_range().is_none() {
+                    self.anchor = Some(self.cursor);
+                    self.move_cursor(&Key::Right);
+                }
+                self.delete_selection()
+            }
+            Key::CtrlW | Key::AltDelete => {
+                self.anchor = Some(self.cursor);
+                self.cursor = self.word_start();
+                self.delete_selection()
+            }
+            Key::CtrlK => {
+                self.anchor = Some(self.cursor);
+                self.cursor.1 = self.lines[self.cursor.0].len();
+                self.delete_selection()
+            }
+            Key::CtrlU => {
+                self.anchor = Some(self.cursor);
+                self.cursor.1 = 0;
+                self.delete_selection()
+            }
+            Key::Enter if self.multiline => self.insert_event("\n"),
+            Key::Enter | Key::AltEnter => self.submit(),
+            Key::Unicode(bytes) => match std::str::from_utf8(bytes) {
+                Ok(text) => self.insert_event(text),
+                Err(_) => InputEvent::Ignored,
+            },
+            other => match map_key_to_char(other) {
+                Some(character) => self.insert_event(&character.to_string()),
+                None => InputEvent::Ignored,
+            },
+        };
+        self.scroll_to_cursor();
+        if event == InputEvent::Moved && before == (self.cursor, self.anchor) {
+            return InputEvent::Ignored;
+        }
+        event
+    }
+
+    /// Build a frame presenting input's current state.
+    pub fn render(&self) -> Vec<Glyph> {
+        let mut frame = vec![self.glyph; self.cols * self.rows];
+        for glyph in frame.iter_mut() {
+            glyph.set_char(' ');
+        }
+        let is_empty = self.lines.len() == 1 && self.lines[0].is_empty();
+        if is_empty && !self.placeholder.is_empty() {
+            let placeholder = text_to_cells(&self.placeholder, self.placeholder_glyph);
+            for (glyph, cell) in frame.iter_mut().zip(placeholder) {
+                *glyph = cell;
+            }
+        } else {
+            let glyph = if self.validate().is_ok() {
+                self.glyph
+            } else {
+                self.invalid_glyph
+            };
+            let selection = self.selection_range();
+            let (left, right) = (self.scroll.1, self.scroll.1 + self.cols);
+            for row in 0..self.rows {
+                let line_no = row + self.scroll.0;
+                let Some(line) = self.lines.get(line_no) else {
+                    continue;
+                };
+                let cells = &mut frame[row * self.cols..(row + 1) * self.cols];
+                for cell in cells.iter_mut() {
+                    *cell = glyph;
+                    cell.set_char(' ');
+                }
+                // Position right after line's end stands for it's line break
+                let mut x = 0;
+                for char_no in 0..=line.len() {
+                    let character = line.get(char_no).copied();
+                    let width = character.map(char_width).unwrap_or(1);
+                    let selected = selection.is_some_and(|(start, end)| {
+                        (line_no, char_no) >= start && (line_no, char_no) < end
+                    });
+                    // Wide char cut by an edge of input is shown as blank
+                    let fits = x >= left && x + width <= right;
+                    for part in 0..width {
+                        let col = x + part;
+                        if col < left || col >= right {
+                            continue;
+                        }
+                        let cell = &mut cells[col - left];
+                        match character {
+                            Some(c) if fits && part == 0 => cell.set_char(c),
+                            Some(_c) if fits => cell.set_char('\u{0}'),
+                            _ => {}
+                        }
+                        if selected {
+                            cell.set_reverse(!glyph.is_reverse());
+                        }
+                    }
+                    x += width;
+                    if x >= right {
+                        break;
+                    }
+                }
+            }
+        }
+        if self.focused {
+            let row = self.cursor.0 - self.scroll.0;
+            let col = self.display_col(self.cursor) - self.scroll.1;
+            if let Some(cell) = frame.get_mut(row * self.cols + col) {
+                cell.set_reverse(!cell.is_reverse());
+                cell.set_blink(true);
+            }
+        }
+        frame
+    }
+
+    /// Add input to Manager's current display as a new graphic.
+    pub fn show(
+        &mut self,
+        mgr: &mut Manager,
+        layer: usize,
+        offset: (isize, isize),
+    ) -> Option<usize> {
+        let graphic = super::widget_graphic(self.cols, self.render());
+        self.shown_frame = 0;
+        self.graphic_id = mgr.add_graphic(graphic, layer, offset);
+        self.graphic_id
+    }
+
+    /// Redraw input's graphic on screen.
+    pub fn draw(&mut self, mgr: &mut Manager) {
+        if let Some(gid) = self.graphic_id {
+            super::redraw(mgr, gid, self.render(), &mut self.shown_frame);
+        }
+    }
+
+    /// Event caused by last key routed to input by Manager.
+    pub fn take_event(&mut self) -> Option<InputEvent> {
+        self.event.take()
+    }
+
+    fn insert_event(&mut self, text: &str) -> InputEvent {
+        if self.insert_str(text) {
+            InputEvent::Edited
+        } else {
+            InputEvent::Ignored
+        }
+    }
+
+    /// Insert text at cursor, replacing selection.
+    /// Chars exceeding max_length are dropped.
+    fn insert_str(&mut self, text: &str) -> bool {
+        let replaced = self.delete_selection() == InputEvent::Edited;
+        let mut length: usize =
+            self.lines.iter().map(|l| l.len()).sum::<usize>() + self.lines.len() - 1;
+        let mut inserted = false;
+        for character in text.chars() {
+            if let Some(max_length) = self.max_length {
+                if length >= max_length {
+                    break;
+                }
+            }
+            let (row, col) = self.cursor;
+            if character == '\n' {
+                if !self.multiline {
+                    continue;
+                }
+                let rest = self.lines[row].split_off(col);
+                self.lines.insert(row + 1, rest);
+                self.cursor = (row + 1, 0);
+            } else if character.is_control() {
+                continue;
+            } else {
+                self.lines[row].insert(col, character);
+                self.cursor.1 += 1;
+            }
+            length += 1;
+            inserted = true;
+        }
+        self.history_index = None;
+        inserted || replaced
+    }
+
+    fn submit(&mut self) -> InputEvent {
+        if let Err(reason) = self.validate() {
+            return InputEvent::Invalid(reason);
+        }
+        let value = self.value();
+        if !value.is_empty() && self.history.last() != Some(&value) {
+            self.history.push(value.clone());
+        }
+        self.history_index = None;
+        InputEvent::Submitted(value)
+    }
+
+    fn browse_history(&mut self, older: bool) -> InputEvent {
+        let index = match (self.history_index, older) {
+            (None, true) if !self.history.is_empty() => {
+                self.draft = self.value();
+                Some(self.history.len() - 1)
+            }
+            (Some(i), true) if i > 0 => Some(i - 1),
+            (Some(i), false) if i + 1 < self.history.len() => Some(i + 1),
+            (Some(_i), false) => None,
+            _ => return InputEvent::Ignored,
+        };
+        let value = match index {
+            Some(i) => self.history[i].clone(),
+            None => std::mem::take(&mut self.draft),
+        };
+        self.set_value(&value);
+        self.history_index = index;
+        InputEvent::Edited
+    }
+
+    fn move_and_select<F: FnOnce(&mut Self)>(&mut self, select: bool, movement: F) -> InputEvent {
+        if select {
+            if self.anchor.is_none() {
+                self.anchor = Some(self.cursor);
+            }
+        } else {
+            self.anchor = None;
+        }
+        movement(self);
+        InputEvent::Moved
+    }
+
+    fn move_cursor(&mut self, key: &Key) {
+        let (row, col) = self.cursor;
+        self.cursor = match key {
+            Key::Left if col > 0 => (row, col - 1),
+            Key::Left if row > 0 => (row - 1, self.lines[row - 1].len()),
+            Key::Right if col < self.lines[row].len() => (row, col + 1),
+            Key::Right if row + 1 < self.lines.len() => (row + 1, 0),
+            Key::Up if row > 0 => (
+                row - 1,
+                self.char_at(row - 1, self.display_col(self.cursor)),
+            ),
+            Key::Down if row + 1 < self.lines.len() => (
+                row + 1,
+                self.char_at(row + 1, self.display_col(self.cursor)),
+            ),
+            Key::Home => (row, 0),
+            Key::End => (row, self.lines[row].len()),
+            _ => (row, col),
+        };
+    }
+
+    fn is_word_char(&self, row: usize, col: usize) -> bool {
+        self.lines[row]
+            .get(col)
+            .map(|c| c.is_alphanumeric() || *c == '_')
+            .unwrap_or(false)
+    }
+
+    /// Position of a beginning of a word before cursor.
+    fn word_start(&self) -> (usize, usize) {
+        let (row, mut col) = self.cursor;
+        if col == 0 {
+            return if row > 0 {
+                (row - 1, self.lines[row - 1].len())
+            } else {
+                (0, 0)
+            };
+        }
+        while col > 0 && !self.is_word_char(row, col - 1) {
+            col -= 1;
+        }
+        while col > 0 && self.is_word_char(row, col - 1) {
+            col -= 1;
+        }
+        (row, col)
+    }
+
+    /// Position of an end of a word after cursor.
+    fn word_end(&self) -> (usize, usize) {
+        let (row, mut col) = self.cursor;
+        let len = self.lines[row].len();
+        if col == len {
+            return if row + 1 < self.lines.len() {
+                (row + 1, 0)
+            } else {
+                (row, col)
+            };
+        }
+        while col < len && !self.is_word_char(row, col) {
+            col += 1;
+        }
+        while col < len && self.is_word_char(row, col) {
+            col += 1;
+        }
+        (row, col)
+    }
+
+    fn selection_range(&self) -> Option<((usize, usize), (usize, usize))> {
+        let anchor = self.anchor?;
+        match anchor.cmp(&self.cursor) {
+            std::cmp::Ordering::Less => Some((anchor, self.cursor)),
+            std::cmp::Ordering::Greater => Some((self.cursor, anchor)),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    fn text_between(&self, start: (usize, usize), end: (usize, usize)) -> String {
+        let mut text = String::new();
+        for row in start.0..=end.0 {
+            let from = if row == start.0 { start.1 } else { 0 };
+            let to = if row == end.0 {
+                end.1
+            } else {
+                self.lines[row].len()
+            };
+            text.extend(&self.lines[row][from..to]);
+            if row < end.0 {
+                text.push('\n');
+            }
+        }
+        text
+    }
+
+    fn delete_selection(&mut self) -> InputEvent {
+        let range = self.selection_range();
+        self.anchor = None;
+        if let Some((start, end)) = range {
+            let tail = self.lines[end.0].split_off(end.1);
+            self.lines.drain(start.0 + 1..=end.0);
+            self.lines[start.0].truncate(start.1);
+            self.lines[start.0].extend(tail);
+            self.cursor = start;
+            self.history_index = None;
+            InputEvent::Edited
+        } else {
+            InputEvent::Ignored
+        }
+    }
+
+    /// Terminal column of a char at given position, counted from line's beginning.
+    fn display_col(&self, (row, col): (usize, usize)) -> usize {
+        self.lines[row][..col].iter().copied().map(char_width).sum()
+    }
+
+    /// Position within a line of a char covering given terminal column,
+    /// or line's end when line is not that wide.
+    fn char_at(&self, row: usize, display_col: usize) -> usize {
+        let mut x = 0;
+        for (col, character) in self.lines[row].iter().enumerate() {
+            x += char_width(*character);
+            if x > display_col {
+                return col;
+            }
+        }
+        self.lines[row].len()
+    }
+
+    /// Move visible part of a value, so that cursor is always on screen.
+    /// Horizontal scroll is kept in terminal columns, so wide chars are shown whole.
+    fn scroll_to_cursor(&mut self) {
+        let (row, col) = self.cursor;
+        if row < self.scroll.0 {
+            self.scroll.0 = row;
+        } else if row >= self.scroll.0 + self.rows {
+            self.scroll.0 = row + 1 - self.rows;
+        }
+        let x = self.display_col(self.cursor);
+        let width = self.lines[row]
+            .get(col)
+            .map(|c| char_width(*c))
+            .unwrap_or(1)
+            .clamp(1, self.cols);
+        if x < self.scroll.1 {
+            self.scroll.1 = x;
+        } else if x + width > self.scroll.1 + self.cols {
+            self.scroll.1 = x + width - self.cols;
+        }
+    }
+}
+
+impl Focusable for TextInput {
+    fn handle_key(&mut self, mgr: &mut Manager, key: &Key) -> bool {
+        let event = TextInput::handle_key(self, key);
+        if event == InputEvent::Ignored {
+            return false;
+        }
+        self.event = Some(event);
+        self.draw(mgr);
+        true
+    }
+
+    fn set_focused(&mut self, mgr: &mut Manager, focused: bool) {
+        self.focused = focused;
+        self.draw(mgr);
+    }
+}
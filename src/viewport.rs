@@ -0,0 +1,178 @@
+use super::glyph::Glyph;
+use super::graphic::Graphic;
+
+/// A window of cols x rows size into a Graphic that can be larger than the screen.
+/// Only the visible part of source graphic is shown, and it is changed by scrolling.
+/// Optional scrollbars take the last column and/or last row of a viewport
+/// whenever source does not fit in given direction.
+/// Source is shown at it's current frame, use `Manager::add_viewport` to place it on screen.
+#[derive(Debug)]
+pub struct Viewport {
+    pub cols: usize,
+    pub rows: usize,
+    pub scrollbars: bool,
+    pub scrollbar_glyph: Glyph,
+    source: Graphic,
+    scroll: (usize, usize),
+}
+
+impl Viewport {
+    /// Create a viewport of given size showing top left corner of source.
+    pub fn new(source: Graphic, cols: usize, rows: usize) -> Self {
+        let mut scrollbar_glyph = Glyph::default();
+        scrollbar_glyph.set_dim(true);
+        Viewport {
+            cols: cols.max(1),
+            rows: rows.max(1),
+            scrollbars: false,
+            scrollbar_glyph,
+            source,
+            scroll: (0, 0),
+        }
+    }
+
+    pub fn with_scrollbars(mut self) -> Self {
+        self.scrollbars = true;
+        self.clamp_scroll();
+        self
+    }
+
+    pub fn source(&self) -> &Graphic {
+        &self.source
+    }
+
+    pub fn source_mut(&mut self) -> &mut Graphic {
+        &mut self.source
+    }
+
+    /// Replace shown graphic, keeping scroll position when possible.
+    pub fn set_source(&mut self, source: Graphic) {
+        self.source = source;
+        self.clamp_scroll();
+    }
+
+    /// Column and row of source shown in top left corner.
+    pub fn scroll(&self) -> (usize, usize) {
+        self.scroll
+    }
+
+    /// Largest scroll offsets that still fill whole viewport with source.
+    pub fn max_scroll(&self) -> (usize, usize) {
+        let (cols, rows) = self.content_size();
+        (
+            self.source.cols.saturating_sub(cols),
+            self.source.rows.saturating_sub(rows),
+        )
+    }
+
+    /// Scroll by given number of columns and rows, returning true when position changed.
+    pub fn scroll_by(&mut self, delta: (isize, isize)) -> bool {
+        let x = self.scroll.0.saturating_add_signed(delta.0);
+        let y = self.scroll.1.saturating_add_signed(delta.1);
+        self.scroll_to((x, y))
+    }
+
+    /// Scroll to given column and row of source, returning true when position changed.
+    pub fn scroll_to(&mut self, position: (usize, usize)) -> bool {
+        let previous = self.scroll;
+        self.scroll = position;
+        self.clamp_scroll();
+        self.scroll != previous
+    }
+
+    /// Size of area showing source, that is viewport without scrollbars.
+    pub fn content_size(&self) -> (usize, usize) {
+        let (vertical, horizontal) = self.scrollbars_shown();
+        (
+            self.cols - vertical as usize,
+            self.rows - horizontal as usize,
+        )
+    }
+
+    /// Build a frame presenting visible part of source.
+    pub fn render(&self) -> Vec<Glyph> {
+        let (vertical, horizontal) = self.scrollbars_shown();
+        let (cols, rows) = self.content_size();
+        let glyphs = self.source.get_glyphs();
+        let mut frame = Vec::with_capacity(self.cols * self.rows);
+        for row in 0..rows {
+            let y = self.scroll.1 + row;
+            let start = frame.len();
+            for col in 0..cols {
+                let x = self.scroll.0 + col;
+                let glyph = if x < self.source.cols && y < self.source.rows {
+                    glyphs[x + y * self.source.cols]
+                } else {
+                    Glyph::transparent()
+                };
+                frame.push(glyph);
+            }
+            // Wide chars cut in half by viewport's edges are blanked
+            if frame[start].character == '\u{0}' {
+                frame[start].set_char(' ');
+            }
+            let last = frame.len() - 1;
+            let cut = self.scroll.0 + cols < self.source.cols
+                && y < self.source.rows
+                && glyphs[self.scroll.0 + cols + y * self.source.cols].character == '\u{0}';
+            if cut {
+                frame[last].set_char(' ');
+            }
+            if vertical {
+                frame.push(self.bar_glyph(row, rows, self.source.rows, self.scroll.1, '│'));
+            }
+        }
+        if horizontal {
+            for col in 0..cols {
+                frame.push(self.bar_glyph(col, cols, self.source.cols, self.scroll.0, '─'));
+            }
+            if vertical {
+                let mut corner = self.scrollbar_glyph;
+                corner.set_char(' ');
+                frame.push(corner);
+            }
+        }
+        frame
+    }
+
+    /// Which scrollbars are shown: vertical, horizontal.
+    fn scrollbars_shown(&self) -> (bool, bool) {
+        if !self.scrollbars {
+            return (false, false);
+        }
+        let mut vertical = self.source.rows > self.rows && self.cols > 1;
+        let horizontal = self.source.cols > self.cols - vertical as usize && self.rows > 1;
+        // Horizontal scrollbar takes a row, so vertical one might be needed after all
+        if horizontal && !vertical {
+            vertical = self.source.rows > self.rows - 1 && self.cols > 1;
+        }
+        (vertical, horizontal)
+    }
+
+    fn clamp_scroll(&mut self) {
+        let (max_x, max_y) = self.max_scroll();
+        self.scroll = (self.scroll.0.min(max_x), self.scroll.1.min(max_y));
+    }
+
+    /// Glyph of a scrollbar at given position along a track of given length.
+    fn bar_glyph(
+        &self,
+        position: usize,
+        length: usize,
+        total: usize,
+        scroll: usize,
+        track: char,
+    ) -> Glyph {
+        let total = total.max(1);
+        let size = (length * length / total).clamp(1, length);
+        let max_scroll = total.saturating_sub(length).max(1);
+        let start = (length - size) * scroll.min(max_scroll) / max_scroll;
+        let mut glyph = self.scrollbar_glyph;
+        glyph.set_char(if (start..start + size).contains(&position) {
+            '█'
+        } else {
+            track
+        });
+        glyph
+    }
+}
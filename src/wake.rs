@@ -0,0 +1,49 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::Waker;
+
+/// Waker of a task waiting for keys, shared with threads that produce them.
+/// Threads call `wake` after every key they send, which does nothing
+/// unless some task has registered itself.
+#[derive(Clone, Default)]
+pub struct KeyWaker(Arc<Shared>);
+
+#[derive(Default)]
+struct Shared {
+    waker: Mutex<Option<Waker>>,
+    // task holds bytes that may be an incomplete sequence
+    wake_when_idle: AtomicBool,
+}
+
+impl KeyWaker {
+    /// Make given waker the one to be woken when next key arrives.
+    #[cfg(feature = "async")]
+    pub fn register(&self, waker: &Waker) {
+        if let Ok(mut slot) = self.0.waker.lock() {
+            match *slot {
+                Some(ref current) if current.will_wake(waker) => {}
+                _ => *slot = Some(waker.clone()),
+            }
+        }
+    }
+
+    pub fn wake(&self) {
+        if let Some(waker) = self.0.waker.lock().ok().and_then(|mut slot| slot.take()) {
+            waker.wake();
+        }
+    }
+
+    /// Ask to be woken also once keyboard goes quiet, since bytes read so far
+    /// could be a lone Escape or the beginning of a longer sequence.
+    #[cfg(feature = "async")]
+    pub fn wake_when_idle(&self) {
+        self.0.wake_when_idle.store(true, Ordering::SeqCst);
+    }
+
+    /// Tell that no byte has arrived for a while, waking a task that asked for it.
+    pub fn idle(&self) {
+        if self.0.wake_when_idle.swap(false, Ordering::SeqCst) {
+            self.wake();
+        }
+    }
+}